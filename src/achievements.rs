@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Mutex;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use serenity::all::{ChannelId, Colour, CreateEmbed, CreateEmbedFooter, CreateMessage, Http, Mentionable, Timestamp, UserId};
+use crate::{hey, nay};
+
+lazy_static!(
+    // unlocks waiting to be announced, most hooks run deep in game code with no channel to post in
+    static ref UNLOCKED: Mutex<Vec<(UserId, Achievement)>> = Mutex::new(Vec::new());
+
+    // progress is bumped on every message, so the file is only read once
+    static ref ACHIEVEMENTS: Vec<Achievement> = Achievement::load();
+);
+
+// something counted for every user, achievements unlock when a counter reaches their goal and quests progress with it
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum Stat {
+    BlackjackWins,
+    // blackjack hands won in a row, a lost hand resets it
+    BlackjackStreak,
+    CreaturesDefeated,
+    // kills of one kind of creature, by name
+    Defeated(String),
+    CratesOpened,
+    Level,
+    Ascension,
+    MinionsOwned,
+    // successful mining trips at a mine tier
+    MinedAtTier(u8),
+    PvPWins,
+    SludgeCollected,
+    MessagesSent,
+}
+
+impl Stat {
+    // the counter's key in the user file
+    pub(crate) fn key(&self) -> String {
+        match self {
+            Stat::Defeated(name) => format!("Defeated:{}", name.to_lowercase()),
+            stat => format!("{:?}", stat),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct AchievementReward {
+    #[serde(default)]
+    pub bananas: u64,
+    #[serde(default)]
+    pub super_nanners: u16,
+    // cosmetic, shown on /info once picked with /achievements
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+impl AchievementReward {
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if self.bananas > 0 {
+            parts.push(format!("{}:banana:", self.bananas));
+        }
+        if self.super_nanners > 0 {
+            parts.push(format!("{}:zap:", self.super_nanners));
+        }
+        if let Some(title) = &self.title {
+            parts.push(format!("the title *{}*", title));
+        }
+        if parts.is_empty() { "Bragging rights".to_string() } else { parts.join(", ") }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Achievement {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub stat: Stat,
+    pub goal: u64,
+    #[serde(default)]
+    pub reward: AchievementReward,
+}
+
+impl Achievement {
+    // every achievement in ./achievements.json
+    pub fn all() -> &'static [Self] {
+        &ACHIEVEMENTS
+    }
+
+    fn load() -> Vec<Self> {
+        let Ok(file) = fs::read_to_string("./achievements.json") else {
+            hey!("No achievements found in ./achievements.json");
+            return Vec::new();
+        };
+        match serde_json::from_str(&file) {
+            Ok(achievements) => achievements,
+            Err(e) => {
+                hey!("Failed to load achievements: {}", e);
+                Vec::new()
+            }
+        }
+    }
+}
+
+// a user's counters and unlocks, saved in their user file
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct AchievementProgress {
+    counters: HashMap<String, u64>,
+    pub unlocked: Vec<String>,
+    // titles earned so far and the one being shown
+    pub titles: Vec<String>,
+    pub title: Option<String>,
+}
+
+impl AchievementProgress {
+
+    pub fn get(&self, stat: &Stat) -> u64 {
+        self.counters.get(&stat.key()).copied().unwrap_or(0)
+    }
+
+    pub fn is_unlocked(&self, id: &str) -> bool {
+        self.unlocked.iter().any(|u| u == id)
+    }
+
+    // returns the achievements this unlocked
+    pub fn add(&mut self, stat: &Stat, amount: u64, achievements: &[Achievement]) -> Vec<Achievement> {
+        *self.counters.entry(stat.key()).or_insert(0) += amount;
+        self.unlock_reached(stat, achievements)
+    }
+
+    // for counters that track a value rather than a running total, unlocks are never taken back
+    pub fn set(&mut self, stat: &Stat, value: u64, achievements: &[Achievement]) -> Vec<Achievement> {
+        self.counters.insert(stat.key(), value);
+        self.unlock_reached(stat, achievements)
+    }
+
+    fn unlock_reached(&mut self, stat: &Stat, achievements: &[Achievement]) -> Vec<Achievement> {
+        let value = self.get(stat);
+        let reached = achievements.iter()
+            .filter(|a| a.stat.key() == stat.key() && value >= a.goal && !self.is_unlocked(&a.id))
+            .cloned()
+            .collect::<Vec<Achievement>>();
+
+        for achievement in &reached {
+            self.unlocked.push(achievement.id.clone());
+            if let Some(title) = &achievement.reward.title {
+                if !self.titles.contains(title) {
+                    self.titles.push(title.clone());
+                }
+            }
+        }
+        reached
+    }
+}
+
+pub(crate) fn queue_unlock(user: UserId, achievement: Achievement) {
+    UNLOCKED.lock().unwrap().push((user, achievement));
+}
+
+// posts any achievements the user unlocked since their last announcement
+pub async fn announce(http: &Http, user: UserId, channel: ChannelId) {
+    let unlocked = {
+        let mut queue = UNLOCKED.lock().unwrap();
+        let (mine, rest) = std::mem::take(&mut *queue).into_iter().partition::<Vec<_>, _>(|(u, _)| *u == user);
+        *queue = rest;
+        mine
+    };
+
+    for (_, achievement) in unlocked {
+        let embed = CreateEmbed::new()
+            .title("Achievement Unlocked!")
+            .description(format!("{} unlocked **{}**\n*{}*", user.mention(), achievement.name, achievement.description))
+            .field("Reward", achievement.reward.describe(), false)
+            .color(Colour::GOLD)
+            .timestamp(Timestamp::now())
+            .footer(CreateEmbedFooter::new("See your progress with /achievements"));
+
+        if let Err(e) = channel.send_message(http, CreateMessage::new().embed(embed)).await {
+            nay!("Failed to announce achievement: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn achievement(id: &str, stat: Stat, goal: u64, title: Option<&str>) -> Achievement {
+        Achievement {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            stat,
+            goal,
+            reward: AchievementReward { title: title.map(|t| t.to_string()), ..Default::default() },
+        }
+    }
+
+    #[test]
+    fn counters_unlock_once_at_their_goal() {
+        let achievements = vec![
+            achievement("streak", Stat::BlackjackStreak, 3, Some("Shark")),
+            achievement("dragon", Stat::Defeated("Sludge Dragon".to_string()), 1, None),
+        ];
+        let mut progress = AchievementProgress::default();
+
+        assert!(progress.add(&Stat::BlackjackStreak, 2, &achievements).is_empty());
+        // a loss resets the streak, it has to be won in a row
+        progress.set(&Stat::BlackjackStreak, 0, &achievements);
+        assert!(progress.add(&Stat::BlackjackStreak, 2, &achievements).is_empty());
+        let unlocked = progress.add(&Stat::BlackjackStreak, 1, &achievements);
+        assert_eq!(unlocked.len(), 1);
+        assert_eq!(progress.titles, vec!["Shark".to_string()]);
+        assert!(progress.add(&Stat::BlackjackStreak, 1, &achievements).is_empty());
+
+        assert!(progress.add(&Stat::Defeated("Elder Sludge Dragon".to_string()), 1, &achievements).is_empty());
+        assert_eq!(progress.add(&Stat::Defeated("sludge dragon".to_string()), 1, &achievements).len(), 1);
+        assert!(progress.is_unlocked("dragon"));
+    }
+
+    #[test]
+    fn bundled_achievements_are_valid() {
+        let achievements: Vec<Achievement> = serde_json::from_str(&fs::read_to_string("./achievements.json").unwrap()).unwrap();
+        for (i, achievement) in achievements.iter().enumerate() {
+            assert!(achievement.goal > 0, "{}", achievement.id);
+            assert!(achievements[..i].iter().all(|a| a.id != achievement.id), "duplicate id {}", achievement.id);
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use crate::userfile::UserValues;
+
+// the smallest bet any game takes
+pub const MIN_BET: u64 = 5;
+
+// an amount like `500`, `5k`, `2m`, `half` or `all` of the balance
+fn amount(raw: &str, balance: u64) -> Result<u64, String> {
+    let raw = raw.trim().to_lowercase();
+    let (number, scale) = match raw.as_str() {
+        "all" => return Ok(balance),
+        "half" => return Ok(balance / 2),
+        _ if raw.ends_with('k') => (&raw[..raw.len() - 1], 1_000),
+        _ if raw.ends_with('m') => (&raw[..raw.len() - 1], 1_000_000),
+        _ => (raw.as_str(), 1),
+    };
+    let Ok(number) = number.parse::<u64>() else {
+        return Err("Me confused, You must bet a number of bananas".to_string());
+    };
+    number.checked_mul(scale).ok_or("That's more bananas than exist!".to_string())
+}
+
+fn bet_amount(raw: &str, balance: u64, max_bet: u64) -> Result<u64, String> {
+    let amt = amount(raw, balance)?;
+    if amt < MIN_BET {
+        return Err(format!("You must bet at least {} bananas", MIN_BET));
+    }
+    if balance < amt {
+        return Err("You too poor!".to_string());
+    }
+    // milestones raise how much can be bet at once
+    if amt > max_bet {
+        return Err(format!("Your bet limit is {}:banana:, reach milestones to raise it", max_bet));
+    }
+    Ok(amt)
+}
+
+// parses an amount of the user's bananas without checking they have it
+pub fn parse_amount(userfile: &mut UserValues, raw: &str) -> Result<u64, String> {
+    amount(raw, userfile.get_bananas())
+}
+
+// parses a bet and checks it against the minimum bet, the user's balance and their bet limit
+pub fn parse_bet_amount(userfile: &mut UserValues, raw: &str) -> Result<u64, String> {
+    bet_amount(raw, userfile.get_bananas(), userfile.max_bet())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amounts_parse_suffixes_and_shares() {
+        assert_eq!(amount("500", 1000), Ok(500));
+        assert_eq!(amount("5K", 0), Ok(5_000));
+        assert_eq!(amount(" 2m", 0), Ok(2_000_000));
+        assert_eq!(amount("all", 1234), Ok(1234));
+        assert_eq!(amount("half", 1235), Ok(617));
+        assert!(amount("k", 0).is_err());
+        assert!(amount("bananas", 0).is_err());
+        assert!(amount("99999999999999999m", 0).is_err());
+    }
+
+    #[test]
+    fn bets_are_checked() {
+        assert_eq!(bet_amount("100", 1000, u64::MAX), Ok(100));
+        assert!(bet_amount("4", 1000, u64::MAX).is_err());
+        assert!(bet_amount("all", 2, u64::MAX).is_err());
+        assert!(bet_amount("2k", 1000, u64::MAX).is_err());
+        assert!(bet_amount("all", 1000, 500).is_err());
+    }
+}
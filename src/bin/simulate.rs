@@ -0,0 +1,197 @@
+use std::env;
+use ape_bot_v2::commands::fiftyfifty;
+use ape_bot_v2::games::blackjack::{natural_payout, BlackJack, HandResult};
+use ape_bot_v2::games::CardType;
+use ape_bot_v2::games::rng::GameRng;
+use ape_bot_v2::slot_machines::{SlotMachine, SlotMachines, DEFAULT_MACHINE};
+
+// Monte-Carlo simulation of the gambling games to find their return to player
+// usage: simulate [slots|fiftyfifty|blackjack|all|<slot machine id>] [rounds] [seed], slots plays the classic machine
+
+const BET: u64 = 1000;
+const DEFAULT_ROUNDS: u64 = 1_000_000;
+
+// running totals for one game, results are tracked in units of the base bet
+#[derive(Default)]
+struct Stats {
+    rounds: u64,
+    wagered: u64,
+    returned: u64,
+    hits: u64,
+    sum: f64,
+    sum_sq: f64,
+    biggest_win: f64,
+}
+
+impl Stats {
+    fn record(&mut self, wagered: u64, returned: u64) {
+        let net = (returned as f64 - wagered as f64) / BET as f64;
+        self.rounds += 1;
+        self.wagered += wagered;
+        self.returned += returned;
+        if returned > wagered {
+            self.hits += 1;
+        }
+        self.sum += net;
+        self.sum_sq += net * net;
+        self.biggest_win = self.biggest_win.max(net);
+    }
+
+    fn report(&self, name: &str) {
+        let rounds = self.rounds as f64;
+        let mean = self.sum / rounds;
+        let variance = self.sum_sq / rounds - mean * mean;
+        println!("{}", name);
+        println!("  rounds:        {}", self.rounds);
+        println!("  RTP:           {:.4}%", self.returned as f64 / self.wagered as f64 * 100.0);
+        println!("  mean result:   {:+.4} bets", mean);
+        println!("  variance:      {:.4}", variance);
+        println!("  std dev:       {:.4}", variance.sqrt());
+        println!("  hit frequency: {:.4}%", self.hits as f64 / rounds * 100.0);
+        println!("  biggest win:   {:.2}x", self.biggest_win);
+    }
+}
+
+// the jackpot pool isn't counted in the RTP, only how often it's hit
+fn simulate_machine(machine: &SlotMachine, rounds: u64, rng: &mut GameRng) -> (Stats, u64) {
+    let mut stats = Stats::default();
+    let mut jackpots = 0;
+    for _ in 0..rounds {
+        let result = machine.play(BET, rng);
+        if result.jackpot {
+            jackpots += 1;
+        }
+        stats.record(BET, result.winnings());
+    }
+    (stats, jackpots)
+}
+
+fn simulate_fiftyfifty(rounds: u64, rng: &mut GameRng) -> Stats {
+    let mut stats = Stats::default();
+    for _ in 0..rounds {
+        let returned = if fiftyfifty::flip(rng) { BET * 2 } else { 0 };
+        stats.record(BET, returned);
+    }
+    stats
+}
+
+// plays one game of blackjack the way handle_message would with a simple basic strategy:
+// never take insurance, split aces and eights, double on 10 and 11 and hit below 17
+// unless George shows 2 to 6 and the hand is 12 or more
+fn play_blackjack(rng: &mut GameRng) -> (u64, u64) {
+    let mut game = BlackJack::new(BET, rng);
+    game.deal();
+
+    if game.player_blackjack {
+        return (BET, natural_payout(BET));
+    }
+
+    if game.offered_insurance {
+        if game.dealer_hand().is_blackjack() {
+            return (BET, 0);
+        }
+        game.offered_insurance = false;
+    }
+
+    let dealer_value = match game.dealer_card().card_type {
+        CardType::Two => 2,
+        CardType::Three => 3,
+        CardType::Four => 4,
+        CardType::Five => 5,
+        CardType::Six => 6,
+        _ => 0,
+    };
+
+    let mut wagered = BET;
+    let mut returned = 0;
+    loop {
+        let mut hand_bet = BET;
+        loop {
+            let hand = game.player.playing_hand();
+            let score = hand.score();
+            if hand.is_blackjack() {
+                game.stand();
+                break;
+            }
+            let pair = hand.cards[0].card_type;
+            if game.player.can_split() && matches!(pair, CardType::Ace | CardType::Eight) {
+                game.player.split();
+                wagered += BET;
+                game.hit();
+                continue;
+            }
+            if game.can_double_down() && (score == 10 || score == 11) {
+                game.double_down(BET);
+                wagered += BET;
+                hand_bet += BET;
+                break;
+            }
+            if score < 17 && !(dealer_value != 0 && score >= 12) {
+                game.hit();
+                if game.player.playing_hand().is_bust() {
+                    break;
+                }
+                continue;
+            }
+            game.stand();
+            break;
+        }
+
+        let result = HandResult::settle(game.player.playing_hand(), game.dealer_hand(), game.player_blackjack);
+        returned += result.payout(hand_bet, false);
+
+        if !game.next_player_hand() {
+            break;
+        }
+    }
+    (wagered, returned)
+}
+
+fn simulate_blackjack(rounds: u64, rng: &mut GameRng) -> Stats {
+    let mut stats = Stats::default();
+    for _ in 0..rounds {
+        let (wagered, returned) = play_blackjack(rng);
+        stats.record(wagered, returned);
+    }
+    stats
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let game = match args.get(1).map(|g| g.to_lowercase()) {
+        Some(game) if game == "slots" => DEFAULT_MACHINE.to_string(),
+        Some(game) => game,
+        None => "all".to_string(),
+    };
+    let rounds = args.get(2).and_then(|r| r.parse::<u64>().ok()).unwrap_or(DEFAULT_ROUNDS);
+    let mut rng = match args.get(3).and_then(|s| s.parse::<u64>().ok()) {
+        Some(seed) => GameRng::from_seed(seed),
+        None => GameRng::new(),
+    };
+
+    println!("Simulating {} rounds of {} with a bet of {} (seed {})", rounds, game, BET, rng.seed());
+
+    let machines = SlotMachines::get();
+    let all = game == "all";
+    for id in machines.ids() {
+        if !all && game != id {
+            continue;
+        }
+        let machine = machines.get_machine(&id).unwrap();
+        let (stats, jackpots) = simulate_machine(machine, rounds, &mut rng);
+        stats.report(&machine.name);
+        if let Some(config) = &machine.jackpot {
+            println!("  jackpot hits:  {} (1 in {:.0}), {}% of every spin feeds the pool",
+                     jackpots, rounds as f64 / jackpots.max(1) as f64, config.contribution_percent);
+        }
+    }
+    if all || game == "fiftyfifty" {
+        simulate_fiftyfifty(rounds, &mut rng).report("50/50");
+    }
+    if all || game == "blackjack" {
+        simulate_blackjack(rounds, &mut rng).report("Blackjack");
+    }
+    if !all && !["fiftyfifty", "blackjack"].contains(&game.as_str()) && machines.get_machine(&game).is_none() {
+        println!("Unknown game {}, expected slots, fiftyfifty, blackjack, all or one of {}", game, machines.ids().join(", "));
+    }
+}
@@ -0,0 +1,379 @@
+use std::fmt::Display;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use serenity::all::UserId;
+use crate::hey;
+
+pub const MAX_MEMBERS: usize = 20;
+// tags are also the clan's file name, so they are kept short and simple
+pub const TAG_LENGTH: std::ops::RangeInclusive<usize> = 2..=5;
+pub const NAME_LENGTH: std::ops::RangeInclusive<usize> = 3..=32;
+pub const CREATE_COST: u64 = 50_000;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, PartialOrd, Debug)]
+pub enum ClanRank {
+    Member,
+    Officer,
+    Owner,
+}
+
+impl ClanRank {
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "member" => Some(ClanRank::Member),
+            "officer" => Some(ClanRank::Officer),
+            "owner" => Some(ClanRank::Owner),
+            _ => None,
+        }
+    }
+}
+
+impl Display for ClanRank {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClanRank::Member => write!(f, "Member"),
+            ClanRank::Officer => write!(f, "Officer"),
+            ClanRank::Owner => write!(f, "Owner"),
+        }
+    }
+}
+
+// a player's contribution to their clan's ranking
+pub fn progress_score(level: u16, prestige: u16, ascension: u16) -> u64 {
+    ascension as u64 * 1000 + prestige.saturating_sub(1) as u64 * 100 + level as u64
+}
+
+pub fn valid_tag(tag: &str) -> bool {
+    TAG_LENGTH.contains(&tag.len()) && tag.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ClanFile {
+    pub tag: String,
+    pub name: String,
+    pub owner: u64,
+    pub officers: Vec<u64>,
+    // everyone in the clan, the owner and officers included
+    pub members: Vec<u64>,
+    pub invites: Vec<u64>,
+    pub bank: u64,
+    // the lowest rank allowed to take bananas out of the bank
+    pub withdraw_rank: ClanRank,
+    pub wars_won: u32,
+}
+
+impl ClanFile {
+
+    pub fn new(tag: &str, name: &str, owner: &UserId) -> Self {
+        Self {
+            tag: tag.to_string(),
+            name: name.to_string(),
+            owner: owner.get(),
+            officers: Vec::new(),
+            members: vec![owner.get()],
+            invites: Vec::new(),
+            bank: 0,
+            withdraw_rank: ClanRank::Officer,
+            wars_won: 0,
+        }
+    }
+
+    pub fn rank(&self, user: &UserId) -> Option<ClanRank> {
+        let user = user.get();
+        if self.owner == user {
+            Some(ClanRank::Owner)
+        } else if self.officers.contains(&user) {
+            Some(ClanRank::Officer)
+        } else if self.members.contains(&user) {
+            Some(ClanRank::Member)
+        } else {
+            None
+        }
+    }
+
+    fn require(&self, user: &UserId, rank: ClanRank) -> Result<(), String> {
+        match self.rank(user) {
+            Some(r) if r >= rank => Ok(()),
+            Some(_) => Err(format!("Only the clan's {}s can do that", rank.to_string().to_lowercase())),
+            None => Err("You aren't in this clan".to_string()),
+        }
+    }
+
+    // officers and the owner can invite
+    pub fn invite(&mut self, by: &UserId, user: &UserId) -> Result<(), String> {
+        self.require(by, ClanRank::Officer)?;
+        if self.rank(user).is_some() {
+            return Err("They are already in the clan".to_string());
+        }
+        if self.invites.contains(&user.get()) {
+            return Err("They have already been invited".to_string());
+        }
+        self.invites.push(user.get());
+        Ok(())
+    }
+
+    pub fn accept(&mut self, user: &UserId) -> Result<(), String> {
+        if !self.invites.contains(&user.get()) {
+            return Err(format!("You haven't been invited to [{}]", self.tag));
+        }
+        if self.members.len() >= MAX_MEMBERS {
+            return Err(format!("[{}] is full", self.tag));
+        }
+        self.invites.retain(|u| *u != user.get());
+        self.members.push(user.get());
+        Ok(())
+    }
+
+    // the owner can only leave once everyone else has, which disbands the clan
+    // returns what was left in the bank when it disbands, it's paid out to the owner
+    pub fn leave(&mut self, user: &UserId) -> Result<Option<u64>, String> {
+        match self.rank(user) {
+            None => Err("You aren't in this clan".to_string()),
+            Some(ClanRank::Owner) if self.members.len() > 1 => Err("Hand the clan to someone else before leaving".to_string()),
+            Some(ClanRank::Owner) => Ok(Some(std::mem::take(&mut self.bank))),
+            Some(_) => {
+                self.remove(user);
+                Ok(None)
+            }
+        }
+    }
+
+    fn remove(&mut self, user: &UserId) {
+        self.members.retain(|u| *u != user.get());
+        self.officers.retain(|u| *u != user.get());
+    }
+
+    // officers can kick members, the owner can kick anyone
+    pub fn kick(&mut self, by: &UserId, user: &UserId) -> Result<(), String> {
+        self.require(by, ClanRank::Officer)?;
+        match (self.rank(by), self.rank(user)) {
+            (_, None) => Err("They aren't in the clan".to_string()),
+            (Some(by), Some(rank)) if rank >= by => Err("You can only kick players below your rank".to_string()),
+            _ => {
+                self.remove(user);
+                Ok(())
+            }
+        }
+    }
+
+    // only the owner changes ranks, promoting an officer hands over the clan
+    pub fn promote(&mut self, by: &UserId, user: &UserId) -> Result<ClanRank, String> {
+        self.require(by, ClanRank::Owner)?;
+        match self.rank(user) {
+            None => Err("They aren't in the clan".to_string()),
+            Some(ClanRank::Member) => {
+                self.officers.push(user.get());
+                Ok(ClanRank::Officer)
+            }
+            Some(ClanRank::Officer) => {
+                self.officers.retain(|u| *u != user.get());
+                self.officers.push(self.owner);
+                self.owner = user.get();
+                Ok(ClanRank::Owner)
+            }
+            Some(ClanRank::Owner) => Err("You already own the clan".to_string()),
+        }
+    }
+
+    pub fn demote(&mut self, by: &UserId, user: &UserId) -> Result<ClanRank, String> {
+        self.require(by, ClanRank::Owner)?;
+        match self.rank(user) {
+            Some(ClanRank::Officer) => {
+                self.officers.retain(|u| *u != user.get());
+                Ok(ClanRank::Member)
+            }
+            Some(_) => Err("Only officers can be demoted".to_string()),
+            None => Err("They aren't in the clan".to_string()),
+        }
+    }
+
+    pub fn deposit(&mut self, user: &UserId, amount: u64) -> Result<u64, String> {
+        self.require(user, ClanRank::Member)?;
+        self.bank = self.bank.saturating_add(amount);
+        Ok(self.bank)
+    }
+
+    pub fn withdraw(&mut self, user: &UserId, amount: u64) -> Result<u64, String> {
+        self.require(user, self.withdraw_rank)?;
+        if amount > self.bank {
+            return Err(format!("The bank only holds {}:banana:", self.bank));
+        }
+        self.bank -= amount;
+        Ok(self.bank)
+    }
+
+    pub fn set_withdraw_rank(&mut self, by: &UserId, rank: ClanRank) -> Result<(), String> {
+        self.require(by, ClanRank::Owner)?;
+        self.withdraw_rank = rank;
+        Ok(())
+    }
+}
+
+pub struct Clan {
+    pub tag: String,
+    pub file: ClanFile,
+}
+
+impl Clan {
+
+    fn path(tag: &str) -> String {
+        format!("./clans/{}.json", tag.to_uppercase())
+    }
+
+    pub fn exists(tag: &str) -> bool {
+        Path::new(&Self::path(tag)).exists()
+    }
+
+    // clans only exist once they've been created
+    pub fn get(tag: &str) -> Option<Self> {
+        if !valid_tag(tag) {
+            return None;
+        }
+        let data = fs::read_to_string(Self::path(tag)).ok()?;
+        let file: ClanFile = serde_json::from_str(data.as_str()).unwrap_or_else(|_| panic!("failed to deserialize clan data with tag {}", tag));
+        Some(Self { tag: file.tag.clone(), file })
+    }
+
+    pub fn all() -> Vec<Self> {
+        let Ok(paths) = fs::read_dir("./clans") else {
+            return Vec::new();
+        };
+
+        paths.flatten()
+            .filter_map(|path| Self::get(&path.file_name().into_string().unwrap_or_default().replace(".json", "")))
+            .collect()
+    }
+
+    pub fn create(tag: &str, name: &str, owner: &UserId) -> Result<Self, String> {
+        if !valid_tag(tag) {
+            return Err(format!("Tags must be {} to {} letters or numbers", TAG_LENGTH.start(), TAG_LENGTH.end()));
+        }
+        if !NAME_LENGTH.contains(&name.chars().count()) {
+            return Err(format!("Clan names must be {} to {} characters", NAME_LENGTH.start(), NAME_LENGTH.end()));
+        }
+        if Self::exists(tag) {
+            return Err(format!("[{}] is already taken", tag.to_uppercase()));
+        }
+        let tag = tag.to_uppercase();
+        let clan = Self { tag: tag.clone(), file: ClanFile::new(&tag, name, owner) };
+        clan.update();
+        Ok(clan)
+    }
+
+    pub fn disband(&self) {
+        if let Err(e) = fs::remove_file(Self::path(&self.tag)) {
+            hey!("Failed to remove clan {}: {}", self.tag, e);
+        }
+    }
+
+    fn reload(&mut self) {
+        if let Some(clan) = Self::get(&self.tag) {
+            *self = clan;
+        }
+    }
+
+    fn update(&self) {
+        let Ok(mut file) = OpenOptions::new()
+            .read(false)
+            .write(true)
+            .create(true)
+            .append(false)
+            .truncate(true)
+            .open(Self::path(&self.tag)) else {
+            hey!("Failed to get file for clan data: {}", &self.tag);
+            return;
+        };
+
+        let Ok(data) = serde_json::to_string(&self.file) else {
+            hey!("Failed to serialize clan data: {}", &self.tag);
+            return;
+        };
+
+        if let Err(e) = write!(file, "{}", data) {
+            hey!("Failed to write to file for clan {}: {}", &self.tag, e);
+        }
+    }
+
+    // runs a change on a fresh copy of the clan, it is only saved if it worked
+    pub fn change<T>(&mut self, change: impl FnOnce(&mut ClanFile) -> Result<T, String>) -> Result<T, String> {
+        self.reload();
+        let result = change(&mut self.file)?;
+        self.update();
+        Ok(result)
+    }
+
+    pub fn add_war_win(&mut self, pot: u64) {
+        self.reload();
+        self.file.wars_won += 1;
+        self.file.bank = self.file.bank.saturating_add(pot);
+        self.update();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranks_control_who_can_do_what() {
+        let (owner, officer, member, outsider) = (UserId::new(1), UserId::new(2), UserId::new(3), UserId::new(4));
+        let mut clan = ClanFile::new("APE", "Apes", &owner);
+
+        assert!(clan.accept(&officer).is_err());
+        clan.invite(&owner, &officer).unwrap();
+        clan.accept(&officer).unwrap();
+        assert_eq!(clan.promote(&owner, &officer), Ok(ClanRank::Officer));
+        clan.invite(&officer, &member).unwrap();
+        clan.accept(&member).unwrap();
+        assert!(clan.invite(&member, &outsider).is_err());
+
+        // officers can kick members but not each other or the owner
+        assert!(clan.kick(&officer, &owner).is_err());
+        assert!(clan.promote(&officer, &member).is_err());
+
+        clan.deposit(&member, 500).unwrap();
+        assert!(clan.withdraw(&member, 100).is_err());
+        assert_eq!(clan.withdraw(&officer, 100), Ok(400));
+        assert!(clan.withdraw(&officer, 1000).is_err());
+        clan.set_withdraw_rank(&owner, ClanRank::Member).unwrap();
+        assert_eq!(clan.withdraw(&member, 400), Ok(0));
+
+        assert!(clan.leave(&owner).is_err());
+        clan.kick(&officer, &member).unwrap();
+        assert_eq!(clan.rank(&member), None);
+
+        // promoting an officer hands over the clan
+        assert_eq!(clan.promote(&owner, &officer), Ok(ClanRank::Owner));
+        assert_eq!(clan.rank(&owner), Some(ClanRank::Officer));
+        assert_eq!(clan.leave(&owner), Ok(None));
+        assert_eq!(clan.leave(&officer), Ok(Some(0)));
+    }
+
+    #[test]
+    fn disbanding_pays_the_bank_to_the_owner() {
+        let (owner, member) = (UserId::new(1), UserId::new(2));
+        let mut clan = ClanFile::new("APE", "Apes", &owner);
+        clan.invite(&owner, &member).unwrap();
+        clan.accept(&member).unwrap();
+        clan.deposit(&member, 700).unwrap();
+
+        assert_eq!(clan.leave(&member), Ok(None));
+        assert_eq!(clan.bank, 700);
+        assert_eq!(clan.leave(&owner), Ok(Some(700)));
+        assert_eq!(clan.bank, 0);
+    }
+
+    #[test]
+    fn tags_are_short_and_plain() {
+        assert!(valid_tag("APE"));
+        assert!(valid_tag("b4n"));
+        assert!(!valid_tag("A"));
+        assert!(!valid_tag("../x"));
+        assert!(!valid_tag("TOOLONG"));
+        assert!(progress_score(1, 1, 1) > progress_score(100, 10, 0));
+    }
+}
@@ -0,0 +1,67 @@
+use serenity::all::{Colour, CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedFooter,
+                    CreateInteractionResponse, CreateInteractionResponseMessage, ResolvedOption, ResolvedValue, Timestamp, UserId};
+use crate::{achievements, command_response, nay};
+use crate::achievements::Achievement;
+use crate::userfile::UserValues;
+
+pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &CommandInteraction, user: &UserId) {
+    let mut userfile = UserValues::get(user);
+
+    // picking a title, `none` takes it off
+    if let Some(ResolvedOption { value: ResolvedValue::String(title), .. }) = options.first() {
+        let title = if title.eq_ignore_ascii_case("none") { None } else { Some(title.to_string()) };
+        if !userfile.set_title(title.clone()) {
+            command_response(ctx, command, "You haven't earned that title yet!").await;
+            return;
+        }
+        command_response(ctx, command, match title {
+            Some(title) => format!("You are now known as *{}*", title),
+            None => "Your title has been removed".to_string(),
+        }).await;
+        return;
+    }
+
+    let progress = userfile.get_achievements();
+    let all = Achievement::all();
+
+    let list = all.iter().map(|a| {
+        if progress.is_unlocked(&a.id) {
+            format!(":white_check_mark: **{}** - {}", a.name, a.description)
+        } else {
+            format!(":lock: **{}** - {} ({}/{})", a.name, a.description, progress.get(&a.stat).min(a.goal), a.goal)
+        }
+    }).collect::<Vec<String>>().join("\n");
+
+    let titles = if progress.titles.is_empty() {
+        "None yet".to_string()
+    } else {
+        progress.titles.join(", ")
+    };
+
+    let embed = CreateEmbed::new()
+        .title(format!("Achievements ({}/{})", progress.unlocked.len(), all.len()))
+        .description(list)
+        .color(Colour::GOLD)
+        .field("Titles", titles, false)
+        .field("Showing", progress.title.unwrap_or("No title".to_string()), true)
+        .footer(CreateEmbedFooter::new("Pick a title with /achievements title"))
+        .timestamp(Timestamp::now());
+
+    let msg = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+        .embed(embed));
+
+    if let Err(e) = command.create_response(&ctx.http, msg).await {
+        nay!("Failed to respond to command: {}", e);
+    }
+
+    achievements::announce(&ctx.http, *user, command.channel_id).await;
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("achievements")
+        .description("See your achievements or pick a title you've earned")
+        .add_option(CreateCommandOption::new(CommandOptionType::String, "title",
+                                             "A title to show on your /info, `none` to remove it")
+            .required(false))
+        .dm_permission(true)
+}
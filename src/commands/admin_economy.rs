@@ -0,0 +1,235 @@
+use serenity::all::{Colour, CommandInteraction, CommandOptionType, Context, CreateAttachment, CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedFooter,
+                    CreateInteractionResponse, CreateInteractionResponseMessage, GuildId, Mentionable, Permissions, ResolvedOption, ResolvedValue, RoleId, Timestamp, UserId};
+use crate::{command_response, is_supreme_overlord, nay};
+use crate::economy::{audit, read_audit, Adjustment, EconomyField};
+use crate::guildfile::GuildSettings;
+use crate::inventory::item::InventoryItem;
+use crate::inventory::minion::{Minion, MinionType};
+use crate::inventory::super_drill::MAX_SUPER_DRILL_TIER;
+use crate::userfile::UserValues;
+
+const DEFAULT_POTION_HEALTH: u32 = 50;
+const LOG_ENTRIES: usize = 15;
+
+fn is_administrator(command: &CommandInteraction) -> bool {
+    command.member.as_ref()
+        .and_then(|member| member.permissions)
+        .is_some_and(|perms| perms.contains(Permissions::ADMINISTRATOR))
+}
+
+// administrators and members with the guild's admin role
+fn is_economy_admin(command: &CommandInteraction, guild_file: &mut GuildSettings) -> bool {
+    if is_administrator(command) {
+        return true;
+    }
+    let Some(role) = guild_file.get_admin_role() else {
+        return false;
+    };
+    command.member.as_ref().is_some_and(|member| member.roles.contains(&RoleId::new(role)))
+}
+
+fn item_from_name(name: &str, health: u32) -> Option<InventoryItem> {
+    match name {
+        "healing_potion" => Some(InventoryItem::HealingPotion { health }),
+        "streak_freeze" => Some(InventoryItem::StreakFreeze),
+        "worker_minion" => Some(InventoryItem::Minion(Minion::new(MinionType::Worker))),
+        "speedy_minion" => Some(InventoryItem::Minion(Minion::new(MinionType::Speedy))),
+        "hoarder_minion" => Some(InventoryItem::Minion(Minion::new(MinionType::Hoarder))),
+        "alchemist_minion" => Some(InventoryItem::Minion(Minion::new(MinionType::Alchemist))),
+        _ => None,
+    }
+}
+
+pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &CommandInteraction, guild: &GuildId, admin: &UserId) {
+    let Some(ResolvedOption { name: subcommand, value: ResolvedValue::SubCommand(options), .. }) = options.first() else {
+        command_response(ctx, command, "Me confused, try `/admin_economy log`").await;
+        return;
+    };
+
+    let mut target = None;
+    let mut field = None;
+    let mut amount = None;
+    let mut item = None;
+    let mut role = None;
+    let mut confirm = false;
+    for option in options {
+        match option {
+            ResolvedOption { name: "user", value: ResolvedValue::User(val, ..), .. } => target = Some(val.id),
+            ResolvedOption { name: "field", value: ResolvedValue::String(val), .. } => field = EconomyField::from_name(val),
+            ResolvedOption { name: "amount" | "slot" | "tier" | "health", value: ResolvedValue::Integer(val), .. } => amount = Some(*val),
+            ResolvedOption { name: "item", value: ResolvedValue::String(val), .. } => item = Some(*val),
+            ResolvedOption { name: "role", value: ResolvedValue::Role(val), .. } => role = Some(val.id),
+            ResolvedOption { name: "confirm", value: ResolvedValue::Boolean(val), .. } => confirm = *val,
+            _ => {}
+        }
+    }
+
+    let now = Timestamp::now().unix_timestamp();
+    let mut guild_file = GuildSettings::get(guild);
+
+    // only administrators can pick who else sees the log, and user files are shared
+    // by every server so only the bot owner can change them
+    let (allowed, denied) = match *subcommand {
+        "role" => (is_administrator(command), "You need to be a server administrator to do that!"),
+        "log" => (is_economy_admin(command, &mut guild_file), "You need the server's admin role to do that!"),
+        _ => (is_supreme_overlord(*admin), "Users' bananas and progress are shared by every server, only the bot owner can change them!"),
+    };
+    if !allowed {
+        audit(guild, admin, target.as_ref(), format!("tried to use `{}`", subcommand), false, now);
+        command_response(ctx, command, denied).await;
+        return;
+    }
+
+    if *subcommand == "log" {
+        let entries = read_audit(guild).into_iter()
+            .filter(|e| target.is_none_or(|t| e.target == Some(t.get()) || e.admin == t.get()))
+            .collect::<Vec<_>>();
+        let recent = if entries.is_empty() {
+            "Nothing has been logged yet".to_string()
+        } else {
+            entries.iter().rev().take(LOG_ENTRIES).map(|e| e.describe()).collect::<Vec<String>>().join("\n")
+        };
+        let full = entries.iter()
+            .map(|e| format!("{} | {} | {} | {} | {}", e.time, e.admin, e.target.map(|t| t.to_string()).unwrap_or("-".to_string()),
+                             if e.allowed { "allowed" } else { "denied" }, e.action))
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        let embed = CreateEmbed::new()
+            .title("Economy Audit Log")
+            .description(recent)
+            .color(Colour::DARK_RED)
+            .footer(CreateEmbedFooter::new("Every logged action is in the attached file"))
+            .timestamp(Timestamp::now());
+        let msg = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+            .embed(embed)
+            .add_file(CreateAttachment::bytes(full.into_bytes(), format!("audit_{}.txt", guild.get())))
+            .ephemeral(true));
+        if let Err(e) = command.create_response(&ctx.http, msg).await {
+            nay!("Failed to respond to command: {}", e);
+        }
+        return;
+    }
+
+    if *subcommand == "role" {
+        guild_file.set_admin_role(role.map(|r| r.get()));
+        let action = match role {
+            Some(role) => format!("set the admin role to {}", role.mention()),
+            None => "cleared the admin role".to_string(),
+        };
+        audit(guild, admin, None, action.clone(), true, now);
+        command_response(ctx, command, format!("You {}", action)).await;
+        return;
+    }
+
+    let Some(target) = target else {
+        command_response(ctx, command, "Me confused, You must pick a user").await;
+        return;
+    };
+    let mut userfile = UserValues::get(&target);
+
+    let action = match *subcommand {
+        "give" | "take" | "set" => {
+            let (Some(field), Some(amount)) = (field, amount.filter(|a| *a >= 0)) else {
+                command_response(ctx, command, "Me confused, You must pick what to change and a positive amount").await;
+                return;
+            };
+            let adjustment = match *subcommand {
+                "give" => Adjustment::Give,
+                "take" => Adjustment::Take,
+                _ => Adjustment::Set,
+            };
+            let (old, new) = userfile.adjust_economy(field, adjustment, amount as u64);
+            format!("{} {} {} ({} -> {})", subcommand, amount, field, old, new)
+        }
+        "give_item" => {
+            let health = amount.filter(|h| *h > 0).map(|h| h as u32).unwrap_or(DEFAULT_POTION_HEALTH);
+            let Some(item) = item.and_then(|name| item_from_name(name, health)) else {
+                command_response(ctx, command, "Me confused, That isn't an item").await;
+                return;
+            };
+            userfile.add_item(item.clone());
+            format!("gave a {}", item)
+        }
+        "take_item" => {
+            let items = userfile.get_items();
+            let Some((slot, item)) = amount.and_then(|s| (s as usize).checked_sub(1)).and_then(|s| items.get(s).map(|item| (s, item))) else {
+                command_response(ctx, command, "Invalid item slot!").await;
+                return;
+            };
+            userfile.remove_item_index(slot);
+            format!("took the {} in slot {}", item, slot + 1)
+        }
+        "drill" => {
+            let Some(tier) = amount.filter(|t| (1..=MAX_SUPER_DRILL_TIER as i64).contains(t)) else {
+                command_response(ctx, command, format!("Super Drill tiers go from 1 to {}", MAX_SUPER_DRILL_TIER)).await;
+                return;
+            };
+            let old = userfile.get_super_drill_tier();
+            userfile.set_super_drill_tier(tier as u8);
+            format!("set the super drill tier ({} -> {})", old, tier)
+        }
+        "reset" => {
+            if !confirm {
+                command_response(ctx, command, format!("This wipes everything {} has, run it again with `confirm` to go ahead", target.mention())).await;
+                return;
+            }
+            userfile.reset();
+            "reset the user".to_string()
+        }
+        _ => {
+            command_response(ctx, command, "Me confused, try `/admin_economy log`").await;
+            return;
+        }
+    };
+
+    audit(guild, admin, Some(&target), action.clone(), true, now);
+    command_response(ctx, command, format!("{}: {}", target.mention(), action)).await;
+}
+
+pub fn register() -> CreateCommand {
+    let user = || CreateCommandOption::new(CommandOptionType::User, "user", "The user to change").required(true);
+    let field = || {
+        let mut field = CreateCommandOption::new(CommandOptionType::String, "field", "What to change").required(true);
+        for (name, value) in [("bananas", "bananas"), ("super nanners", "super_nanners"), ("level", "level"), ("prestige", "prestige"), ("ascension", "ascension")] {
+            field = field.add_string_choice(name, value);
+        }
+        field
+    };
+    let amount = || CreateCommandOption::new(CommandOptionType::Integer, "amount", "How much").required(true);
+    let adjust = |name: &str, description: &str| CreateCommandOption::new(CommandOptionType::SubCommand, name, description)
+        .add_sub_option(user())
+        .add_sub_option(field())
+        .add_sub_option(amount());
+
+    let mut item = CreateCommandOption::new(CommandOptionType::String, "item", "The item to give").required(true);
+    for (name, value) in [("healing potion", "healing_potion"), ("streak freeze", "streak_freeze"), ("worker minion", "worker_minion"),
+                          ("speedy minion", "speedy_minion"), ("hoarder minion", "hoarder_minion"), ("alchemist minion", "alchemist_minion")] {
+        item = item.add_string_choice(name, value);
+    }
+
+    CreateCommand::new("admin_economy")
+        .description("Change users' bananas, progress and items (bot owner only), every change is logged")
+        .add_option(adjust("give", "Add to a user's bananas, super nanners, level, prestige or ascension"))
+        .add_option(adjust("take", "Take from a user's bananas, super nanners, level, prestige or ascension"))
+        .add_option(adjust("set", "Set a user's bananas, super nanners, level, prestige or ascension"))
+        .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "give_item", "Give a user an item")
+            .add_sub_option(user())
+            .add_sub_option(item)
+            .add_sub_option(CreateCommandOption::new(CommandOptionType::Integer, "health", "How much a healing potion heals (default 50)").required(false)))
+        .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "take_item", "Take an item from a user")
+            .add_sub_option(user())
+            .add_sub_option(CreateCommandOption::new(CommandOptionType::Integer, "slot", "The item's inventory slot").required(true)))
+        .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "drill", "Give a user a super drill or change its tier")
+            .add_sub_option(user())
+            .add_sub_option(CreateCommandOption::new(CommandOptionType::Integer, "tier", "The drill's tier").required(true)))
+        .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "reset", "Wipe a user back to a fresh start")
+            .add_sub_option(user())
+            .add_sub_option(CreateCommandOption::new(CommandOptionType::Boolean, "confirm", "Confirm the reset").required(false)))
+        .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "role", "Choose the role that can view the audit log (administrators only)")
+            .add_sub_option(CreateCommandOption::new(CommandOptionType::Role, "role", "The admin role, leave out to clear it").required(false)))
+        .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "log", "View the audit log")
+            .add_sub_option(CreateCommandOption::new(CommandOptionType::User, "user", "Only show actions by or on this user").required(false)))
+        // not limited to administrators so the admin role can see it, run checks permissions
+        .dm_permission(false)
+}
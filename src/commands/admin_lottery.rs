@@ -0,0 +1,47 @@
+use serenity::all::{CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption, GuildId, Mentionable, Permissions, ResolvedOption, ResolvedValue, Timestamp};
+use crate::command_response;
+use crate::guildfile::GuildSettings;
+use crate::lottery::DEFAULT_DRAW_INTERVAL_DAYS;
+
+pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &CommandInteraction, guild: &GuildId) {
+    let mut channel = None;
+    let mut days = DEFAULT_DRAW_INTERVAL_DAYS;
+
+    for option in options {
+        match option {
+            ResolvedOption { name: "channel", value: ResolvedValue::Channel(val), .. } => channel = Some(val.id),
+            ResolvedOption { name: "days", value: ResolvedValue::Integer(val), .. } => days = *val,
+            _ => {}
+        }
+    }
+
+    let Some(channel) = channel else {
+        command_response(ctx, command, "Me confused, You must specify a channel").await;
+        return;
+    };
+
+    if !(1..=30).contains(&days) {
+        command_response(ctx, command, "Draws must be between 1 and 30 days apart").await;
+        return;
+    }
+
+    let mut guild_file = GuildSettings::get(guild);
+    guild_file.setup_lottery(channel.get(), days, Timestamp::now().unix_timestamp());
+    let lottery = guild_file.get_lottery();
+
+    command_response(ctx, command, format!("Lottery draws are announced in {} every {} day(s), the next is <t:{}:R>",
+                                           channel.mention(), days, lottery.next_draw)).await;
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("admin_lottery")
+        .description("Set up the server lottery and where its draws are announced")
+        .add_option(CreateCommandOption::new(CommandOptionType::Channel, "channel",
+                                             "The channel draws are announced in")
+            .required(true))
+        .add_option(CreateCommandOption::new(CommandOptionType::Integer, "days",
+                                             "Days between draws (default 7)")
+            .required(false))
+        .default_member_permissions(Permissions::ADMINISTRATOR)
+        .dm_permission(false)
+}
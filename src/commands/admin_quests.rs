@@ -0,0 +1,95 @@
+use serenity::all::{CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption, GuildId, Permissions, ResolvedOption, ResolvedValue};
+use crate::command_response;
+use crate::guildfile::GuildSettings;
+use crate::quests::QuestTemplate;
+
+pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &CommandInteraction, guild: &GuildId) {
+    let mut reset_hour = None;
+    let mut quest = None;
+    let mut enabled = None;
+    let mut reward = None;
+
+    for option in options {
+        match option {
+            ResolvedOption { name: "reset_hour", value: ResolvedValue::Integer(val), .. } => reset_hour = Some(*val),
+            ResolvedOption { name: "quest", value: ResolvedValue::String(val), .. } => quest = Some(val.to_lowercase()),
+            ResolvedOption { name: "enabled", value: ResolvedValue::Boolean(val), .. } => enabled = Some(*val),
+            ResolvedOption { name: "reward", value: ResolvedValue::Integer(val), .. } => reward = Some(*val),
+            _ => {}
+        }
+    }
+
+    let mut guild_file = GuildSettings::get(guild);
+    let templates = QuestTemplate::all();
+    let mut changes = Vec::new();
+
+    if let Some(hour) = reset_hour {
+        if !(0..24).contains(&hour) {
+            command_response(ctx, command, "The reset hour must be between 0 and 23").await;
+            return;
+        }
+        guild_file.set_quest_reset_hour(hour as u8);
+        changes.push(format!("Quest boards now refresh at {:02}:00 UTC", hour));
+    }
+
+    if enabled.is_some() || reward.is_some() {
+        let Some(id) = quest else {
+            command_response(ctx, command, "Me confused, You must pick a quest to change").await;
+            return;
+        };
+        let Some(template) = templates.iter().find(|q| q.id == id) else {
+            command_response(ctx, command, format!("There is no quest called `{}`", id)).await;
+            return;
+        };
+        if let Some(enabled) = enabled {
+            guild_file.set_quest_enabled(&id, enabled);
+            changes.push(format!("`{}` is now {}", id, if enabled { "enabled" } else { "disabled" }));
+        }
+        if let Some(reward) = reward {
+            if reward < 0 {
+                command_response(ctx, command, "Rewards can't be negative").await;
+                return;
+            }
+            if reward as u64 > template.max_reward() {
+                command_response(ctx, command, format!("`{}` can reward at most {}:banana:", id, template.max_reward())).await;
+                return;
+            }
+            guild_file.set_quest_reward(&id, reward as u64);
+            changes.push(format!("`{}` now rewards {}:banana:", id, reward));
+        }
+    }
+
+    // without changes the pool is listed
+    let settings = guild_file.get_quest_settings();
+    if changes.is_empty() {
+        let pool = templates.iter().map(|q| {
+            let bananas = settings.rewards.get(&q.id).copied().unwrap_or(q.bananas).min(q.max_reward());
+            let status = if settings.disabled.contains(&q.id) { ":x:" } else { ":white_check_mark:" };
+            format!("{} `{}` {} - {}:banana:", status, q.id, q.description, bananas)
+        }).collect::<Vec<String>>().join("\n");
+        changes.push(format!("Quest boards refresh at {:02}:00 UTC\n{}", settings.reset_hour, pool));
+    } else {
+        changes.push("Boards already drawn keep their quests until they refresh".to_string());
+    }
+
+    command_response(ctx, command, changes.join("\n")).await;
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("admin_quests")
+        .description("Configure the quest pool, rewards and reset time, run without options to see the pool")
+        .add_option(CreateCommandOption::new(CommandOptionType::Integer, "reset_hour",
+                                             "The hour (UTC) quest boards refresh")
+            .required(false))
+        .add_option(CreateCommandOption::new(CommandOptionType::String, "quest",
+                                             "The id of the quest to change")
+            .required(false))
+        .add_option(CreateCommandOption::new(CommandOptionType::Boolean, "enabled",
+                                             "Whether the quest can be drawn")
+            .required(false))
+        .add_option(CreateCommandOption::new(CommandOptionType::Integer, "reward",
+                                             "Bananas the quest rewards, up to 5 times its default")
+            .required(false))
+        .default_member_permissions(Permissions::ADMINISTRATOR)
+        .dm_permission(false)
+}
@@ -0,0 +1,29 @@
+use serenity::all::{CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption, GuildId, Permissions, ResolvedOption, ResolvedValue};
+use crate::command_response;
+use crate::games::duel::MAX_RAKE_PERCENT;
+use crate::guildfile::GuildSettings;
+
+pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &CommandInteraction, guild: &GuildId) {
+    let Some(ResolvedOption { value: ResolvedValue::Integer(percent), .. }) = options.first() else {
+        command_response(ctx, command, "Me confused, You must specify a percent").await;
+        return;
+    };
+
+    if !(0..=MAX_RAKE_PERCENT as i64).contains(percent) {
+        command_response(ctx, command, format!("The rake must be between 0 and {}%", MAX_RAKE_PERCENT)).await;
+        return;
+    }
+
+    GuildSettings::get(guild).set_duel_rake(*percent as u8);
+    command_response(ctx, command, format!("The house now keeps {}% of every duel pot", percent)).await;
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("admin_rake")
+        .description("Set the percent of every duel pot the house keeps")
+        .add_option(CreateCommandOption::new(CommandOptionType::Integer, "percent",
+                                             "0 turns the rake off")
+            .required(true))
+        .default_member_permissions(Permissions::ADMINISTRATOR)
+        .dm_permission(false)
+}
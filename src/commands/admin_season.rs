@@ -0,0 +1,86 @@
+use serenity::all::{CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption, GuildId, Permissions, ResolvedOption, ResolvedValue, Timestamp};
+use crate::command_response;
+use crate::guildfile::GuildSettings;
+use crate::seasons::{SeasonField, MIN_SEASON_DAYS};
+
+pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &CommandInteraction, guild: &GuildId) {
+    let mut channel = None;
+    let mut days = None;
+    let mut reset = None;
+    let mut keep = None;
+    let mut end_now = false;
+
+    for option in options {
+        match option {
+            ResolvedOption { name: "channel", value: ResolvedValue::Channel(val), .. } => channel = Some(val.id.get()),
+            ResolvedOption { name: "days", value: ResolvedValue::Integer(val), .. } => days = Some(*val),
+            ResolvedOption { name: "reset", value: ResolvedValue::String(val), .. } => reset = Some(*val),
+            ResolvedOption { name: "keep_percent", value: ResolvedValue::Integer(val), .. } => keep = Some(*val),
+            ResolvedOption { name: "end_now", value: ResolvedValue::Boolean(val), .. } => end_now = *val,
+            _ => {}
+        }
+    }
+
+    if days.is_some_and(|d| !(MIN_SEASON_DAYS..=365).contains(&d)) {
+        command_response(ctx, command, format!("Seasons must last between {} and 365 days", MIN_SEASON_DAYS)).await;
+        return;
+    }
+
+    if keep.is_some_and(|k| !(0..=100).contains(&k)) {
+        command_response(ctx, command, "The kept percent must be between 0 and 100").await;
+        return;
+    }
+
+    let reset = match reset.map(SeasonField::parse_list) {
+        Some(Err(e)) => {
+            command_response(ctx, command, e).await;
+            return;
+        }
+        Some(Ok(fields)) => Some(fields),
+        None => None,
+    };
+
+    let mut guild_file = GuildSettings::get(guild);
+    let now = Timestamp::now().unix_timestamp();
+    guild_file.setup_seasons(channel, days, reset, keep.map(|k| k as u8), now);
+    let ended = end_now && guild_file.end_season_now(now);
+
+    let seasons = guild_file.get_seasons();
+    let reset = seasons.reset.iter().map(|f| f.to_string()).collect::<Vec<String>>().join(", ");
+    let mut message = format!("Season {} ends <t:{}:R>, seasons last {} day(s)\nAt the end {} keep {}% of their season standing, global progress is never reset",
+                              seasons.number, seasons.ends, seasons.length_days,
+                              if reset.is_empty() { "nothing is reset and all".to_string() } else { reset }, seasons.keep_percent);
+    match seasons.channel {
+        Some(channel) => message.push_str(&format!("\nResults are announced in <#{}>", channel)),
+        None => message.push_str("\nResults aren't announced, set a channel to announce them"),
+    }
+    if ended {
+        message.push_str("\nThe season will end within a minute");
+    } else if end_now {
+        message.push_str(&format!("\nSeasons run for at least {} days, this one can end <t:{}:R>", MIN_SEASON_DAYS, seasons.earliest_end()));
+    }
+
+    command_response(ctx, command, message).await;
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("admin_season")
+        .description("Configure seasons, the first season starts when this is first used")
+        .add_option(CreateCommandOption::new(CommandOptionType::Channel, "channel",
+                                             "The channel season results are announced in")
+            .required(false))
+        .add_option(CreateCommandOption::new(CommandOptionType::Integer, "days",
+                                             "How many days a season lasts (default 30, at least 7)")
+            .required(false))
+        .add_option(CreateCommandOption::new(CommandOptionType::String, "reset",
+                                             "What is soft reset at the end, like `bananas, level, prestige` or `none`")
+            .required(false))
+        .add_option(CreateCommandOption::new(CommandOptionType::Integer, "keep_percent",
+                                             "How much of each reset field is kept (default 10)")
+            .required(false))
+        .add_option(CreateCommandOption::new(CommandOptionType::Boolean, "end_now",
+                                             "End the current season early, once it has run for 7 days")
+            .required(false))
+        .default_member_permissions(Permissions::ADMINISTRATOR)
+        .dm_permission(false)
+}
@@ -1,7 +1,9 @@
 use serenity::all::{Colour, CommandInteraction, Context, CreateAttachment, CreateCommand, CreateEmbed, CreateInteractionResponse, CreateInteractionResponseMessage, UserId};
 use serenity::builder::CreateEmbedFooter;
 use serenity::model::Timestamp;
-use crate::{command_response, nay};
+use crate::{achievements, command_response, milestones, nay};
+use crate::achievements::Stat;
+use crate::perks::POINTS_PER_ASCENSION;
 use crate::userfile::UserValues;
 
 pub async fn run(ctx: &Context, command: &CommandInteraction, user: &UserId) {
@@ -14,8 +16,10 @@ pub async fn run(ctx: &Context, command: &CommandInteraction, user: &UserId) {
     }
 
     userfile.ascend();
+    let (reached, missed) = userfile.claim_milestones();
+
     // show levelup embed
-    let embed = CreateEmbed::new()
+    let mut embed = CreateEmbed::new()
         .title("ASCENSION! :zap: :zap: :zap:")
         .description(format!("Congratulations, {}!", user.to_user(ctx).await.unwrap().global_name.unwrap()))
         .thumbnail("attachment://monkey.png")
@@ -27,10 +31,17 @@ pub async fn run(ctx: &Context, command: &CommandInteraction, user: &UserId) {
                 ("Level:", format!("{}:banana:", userfile.get_level()), true),
                 ("Next Level:", format!("{}:banana:", userfile.levelup_cost()), true),
                 ("Bananas:", format!("{}:banana:", userfile.get_bananas()), true),
+                ("Perk Points:", format!("+{} (spend them with `/perks`)", POINTS_PER_ASCENSION), true),
             ]
         )
         .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"))
         .timestamp(Timestamp::now());
+    if !reached.is_empty() {
+        embed = embed.field("Milestones Reached:", milestones::summary(&reached), false);
+    }
+    if !missed.is_empty() {
+        embed = embed.field("Milestone Items:", milestones::missed_summary(&missed), false);
+    }
 
     let msg = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
         .embed(embed)
@@ -39,6 +50,10 @@ pub async fn run(ctx: &Context, command: &CommandInteraction, user: &UserId) {
     if let Err(e) = command.create_response(&ctx.http, msg).await {
         nay!("Failed to respond to command: {}", e);
     }
+
+    let ascension = userfile.get_ascension();
+    userfile.set_progress(Stat::Ascension, ascension as u64);
+    achievements::announce(&ctx.http, *user, command.channel_id).await;
 }
 
 pub fn register() -> CreateCommand {
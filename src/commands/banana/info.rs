@@ -1,15 +1,39 @@
 use serenity::all::{Colour, CommandInteraction, Context, CreateAttachment, CreateCommand, CreateEmbed,
                     CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage, Timestamp, UserId};
 use crate::nay;
+use crate::achievements::Achievement;
+use crate::rewards::RewardKind;
 use crate::userfile::UserValues;
 
 pub async fn run(ctx: &Context, command: &CommandInteraction, user: &UserId) {
     let mut userfile = UserValues::get(user);
+    let streaks = userfile.get_streaks();
+    let achievements = userfile.get_achievements();
+    let next_milestone = match userfile.next_milestone() {
+        Some(milestone) => format!("**{}** at {}\n{}", milestone.name, milestone.requirement(), milestone.describe()),
+        None => "Every milestone reached!".to_string(),
+    };
+    let now = Timestamp::now().unix_timestamp();
 
-    let embed = CreateEmbed::new()
+    // claims are ready once their time has passed
+    let next_claim = |kind: RewardKind| {
+        let streak = streaks.get(kind);
+        let next = streak.next_claim(kind);
+        if next <= now {
+            format!("Ready! ({} :fire:)", streak.count)
+        } else {
+            format!("<t:{}:R> ({} :fire:)", next, streak.count)
+        }
+    };
+
+    let badges = userfile.get_badges();
+    let mut embed = CreateEmbed::new()
         .title(format!("{}'s Info", user.to_user(ctx).await.unwrap().global_name.unwrap()))
         .color(Colour::DARK_TEAL)
-        .description("View your stats!")
+        .description(match &achievements.title {
+            Some(title) => format!("*{}*", title),
+            None => "View your stats!".to_string(),
+        })
         .thumbnail("attachment://george.png")
         .fields(
             vec![
@@ -29,10 +53,19 @@ pub async fn run(ctx: &Context, command: &CommandInteraction, user: &UserId) {
                 },
                 ("Ascension:", format!("{}", userfile.get_ascension()), true),
                 ("Can Ascend:", format!("{}", if userfile.can_ascend() { "Yes!" } else { "No (prestige 10 + 1mil:banana:)" }), true),
+                ("Super Drill:", if let Some(drill) = userfile.get_super_drill() { format!("Tier {}", drill.tier) } else { "None".to_string() }, true),
+                ("Drill Parts:", format!("{}:gear:", userfile.get_drill_parts()), true),
+                ("Next Daily:", next_claim(RewardKind::Daily), true),
+                ("Next Weekly:", next_claim(RewardKind::Weekly), true),
+                ("Achievements:", format!("{}/{}", achievements.unlocked.len(), Achievement::all().len()), true),
             ]
         )
+        .field("Next Milestone:", next_milestone, false)
         .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"))
         .timestamp(Timestamp::now());
+    if !badges.is_empty() {
+        embed = embed.field("Badges:", badges.iter().map(|b| format!(":medal: {}", b)).collect::<Vec<String>>().join("\n"), false);
+    }
 
     let msg = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
         .add_file(CreateAttachment::path("./images/george.png").await.unwrap())
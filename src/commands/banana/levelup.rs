@@ -1,7 +1,8 @@
 use serenity::all::{Colour, CommandInteraction, CommandOptionType, Context, CreateAttachment, CreateCommand, CreateCommandOption, CreateEmbed, CreateInteractionResponse, CreateInteractionResponseMessage, ResolvedOption, ResolvedValue, UserId};
 use serenity::builder::CreateEmbedFooter;
 use serenity::model::Timestamp;
-use crate::{command_response, nay};
+use crate::{achievements, command_response, milestones, nay};
+use crate::achievements::Stat;
 use crate::userfile::UserValues;
 
 pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &CommandInteraction, user: &UserId) {
@@ -33,8 +34,10 @@ pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &Comman
         userfile.levelup()
     }
 
+    let (reached, missed) = userfile.claim_milestones();
+
     // show levelup embed
-    let embed = CreateEmbed::new()
+    let mut embed = CreateEmbed::new()
         .title("Level Up!")
         .description(format!("Congratulations, {}!", user.to_user(ctx).await.unwrap().global_name.unwrap()))
         .thumbnail("attachment://george.png")
@@ -58,6 +61,12 @@ pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &Comman
         )
         .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"))
         .timestamp(Timestamp::now());
+    if !reached.is_empty() {
+        embed = embed.field("Milestones Reached:", milestones::summary(&reached), false);
+    }
+    if !missed.is_empty() {
+        embed = embed.field("Milestone Items:", milestones::missed_summary(&missed), false);
+    }
 
     let msg = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
         .embed(embed)
@@ -66,6 +75,10 @@ pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &Comman
     if let Err(e) = command.create_response(&ctx.http, msg).await {
         nay!("Failed to respond to command: {}", e);
     }
+
+    let level = userfile.get_level();
+    userfile.set_progress(Stat::Level, level as u64);
+    achievements::announce(&ctx.http, *user, command.channel_id).await;
 }
 
 pub fn register() -> CreateCommand {
@@ -3,4 +3,4 @@ pub mod levelup;
 pub mod prestige;
 pub mod leaderboard;
 pub mod pay;
-pub(crate) mod ascend;
\ No newline at end of file
+pub mod ascend;
\ No newline at end of file
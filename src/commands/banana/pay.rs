@@ -1,6 +1,7 @@
 use serenity::all::{CommandInteraction, CommandOptionType, Context, CreateCommand, ResolvedOption, ResolvedValue, UserId};
 use serenity::builder::CreateCommandOption;
 use crate::{command_response, command_response_loud};
+use crate::bets::parse_amount;
 use crate::userfile::UserValues;
 
 pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &CommandInteraction, user: &UserId) {
@@ -18,23 +19,12 @@ pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &Comman
 
     let mut userfile = UserValues::get(user);
 
-    // parse out `k` `m` and all
-    let raw_amt = raw_amt.to_lowercase();
-    let amt = if raw_amt == "all" {
-        userfile.get_bananas()
-    } else if raw_amt == "half" {
-        userfile.get_bananas() / 2
-    } else if raw_amt.ends_with("k") {
-        raw_amt[..raw_amt.len() - 1].parse::<u64>().unwrap_or(5) * 1000
-    } else if raw_amt.ends_with("m") {
-        raw_amt[..raw_amt.len() - 1].parse::<u64>().unwrap_or(5) * 1000000
-    } else {
-        let Ok(parse) = raw_amt.parse::<u64>() else {
-            // error message
-            command_response(ctx, command, "Me confused, You must bet a number of bananas").await;
+    let amt = match parse_amount(&mut userfile, raw_amt) {
+        Ok(amt) => amt,
+        Err(e) => {
+            command_response(ctx, command, e).await;
             return;
-        };
-        parse
+        }
     };
 
     // check if the user has enough to pay
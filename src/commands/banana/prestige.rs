@@ -1,7 +1,7 @@
 use serenity::all::{Colour, CommandInteraction, Context, CreateAttachment, CreateCommand, CreateEmbed, CreateInteractionResponse, CreateInteractionResponseMessage, UserId};
 use serenity::builder::CreateEmbedFooter;
 use serenity::model::Timestamp;
-use crate::{command_response, nay};
+use crate::{command_response, milestones, nay};
 use crate::userfile::UserValues;
 
 pub async fn run(ctx: &Context, command: &CommandInteraction, user: &UserId) {
@@ -14,8 +14,10 @@ pub async fn run(ctx: &Context, command: &CommandInteraction, user: &UserId) {
     }
 
     userfile.prestige();
+    let (reached, missed) = userfile.claim_milestones();
+
     // show levelup embed
-    let embed = CreateEmbed::new()
+    let mut embed = CreateEmbed::new()
         .title("PRESTIGE! :tada: :tada: :tada:")
         .description(format!("Congratulations, {}!", user.to_user(ctx).await.unwrap().global_name.unwrap()))
         .thumbnail("attachment://monkey.png")
@@ -31,6 +33,12 @@ pub async fn run(ctx: &Context, command: &CommandInteraction, user: &UserId) {
         )
         .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"))
         .timestamp(Timestamp::now());
+    if !reached.is_empty() {
+        embed = embed.field("Milestones Reached:", milestones::summary(&reached), false);
+    }
+    if !missed.is_empty() {
+        embed = embed.field("Milestone Items:", milestones::missed_summary(&missed), false);
+    }
 
     let msg = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
         .embed(embed)
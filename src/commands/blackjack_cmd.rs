@@ -1,7 +1,8 @@
 use serenity::all::{Colour, CommandInteraction, CommandOptionType, Context, CreateAttachment, CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage, ResolvedOption, ResolvedValue, UserId};
-use crate::games::blackjack::BlackJack;
-use crate::{command_response, GAMES, MINING, nay};
+use crate::games::blackjack::{natural_payout, BlackJack, HandResult};
+use crate::{achievements, command_response, GAMES, MINING, nay};
 use crate::games::{Games, GameHandler};
+use crate::bets::parse_bet_amount;
 use crate::userfile::UserValues;
 
 pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &CommandInteraction, user: &UserId) {
@@ -20,38 +21,14 @@ pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &Comman
 
     let mut userfile = UserValues::get(user);
 
-    // parse out `k` `m` and all
-    let raw_amt = raw_amt.to_lowercase();
-    let amt = if raw_amt == "all" {
-        userfile.get_bananas()
-    } else if raw_amt == "half" {
-        userfile.get_bananas() / 2
-    } else if raw_amt.ends_with("k") {
-        raw_amt[..raw_amt.len() - 1].parse::<u64>().unwrap_or(5) * 1000
-    } else if raw_amt.ends_with("m") {
-        raw_amt[..raw_amt.len() - 1].parse::<u64>().unwrap_or(5) * 1000000
-    } else {
-        let Ok(parse) = raw_amt.parse::<u64>() else {
-            // error message
-            command_response(ctx, command, "Me confused, You must bet a number of bananas").await;
+    let amt = match parse_bet_amount(&mut userfile, raw_amt) {
+        Ok(amt) => amt,
+        Err(e) => {
+            command_response(ctx, command, e).await;
             return;
-        };
-        parse
+        }
     };
 
-    if amt < 5 {
-        // error message
-        command_response(ctx, command, "You must bet at least 5 bananas").await;
-        return;
-    }
-
-    // check if the user has enough bananas
-    if userfile.get_bananas() < amt {
-        // error message
-        command_response(ctx, command, "You too poor!").await;
-        return;
-    }
-
     // if the user is already in a game
     if GAMES.lock().await.get_player_game(user).is_some() {
         // error message
@@ -63,7 +40,10 @@ pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &Comman
     userfile.remove_bananas(amt);
 
     // create the blackjack game
-    let mut game = BlackJack::new(amt);
+    let fair = userfile.next_fair_round();
+    let mut rng = fair.as_ref().map(|round| round.rng()).unwrap_or_default();
+    let mut game = BlackJack::new(amt, &mut rng);
+    game.fair = fair;
     game.deal();
 
     if game.player_blackjack {
@@ -78,6 +58,12 @@ pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &Comman
             .field("You won!", "Go you!", false)
             .footer(CreateEmbedFooter::new(format!("George Advice: {}", game.give_help())));
 
+        // the game is already over so the seeds can be revealed
+        let embed = match &game.fair {
+            Some(round) => embed.field("Provably Fair", round.reveal(), false),
+            None => embed,
+        };
+
         let msg = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
             .embed(embed)
             .add_file(CreateAttachment::path("./images/monkey.png").await.unwrap()));
@@ -88,7 +74,9 @@ pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &Comman
             nay!("Failed to send bj message: {}", e);
         }
         // add the bet back and 2.5 times
-        userfile.add_bananas(amt + (amt as f64 * 2.5) as u64);
+        userfile.add_bananas(natural_payout(amt));
+        HandResult::Blackjack.track(&mut userfile);
+        achievements::announce(&ctx.http, *user, command.channel_id).await;
 
         return;
     }
@@ -105,6 +93,11 @@ pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &Comman
             .field("Would you like insurance?", "`yes` or `no`", false)
             .footer(CreateEmbedFooter::new(format!("George Advice: {}", game.give_help())));
 
+        let embed = match &game.fair {
+            Some(round) => embed.field("Provably Fair", round.commitment(), false),
+            None => embed,
+        };
+
         let msg = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
             .embed(embed)
             .add_file(CreateAttachment::path("./images/monkey.png").await.unwrap()));
@@ -115,7 +108,7 @@ pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &Comman
             nay!("Failed to send bj message: {}", e);
         }
 
-        GAMES.lock().await.insert(GameHandler::new(user.clone(), Games::BlackJack(game)));
+        GAMES.lock().await.insert(GameHandler::new(user.clone(), Games::BlackJack(game), rng));
         return;
     }
 
@@ -131,6 +124,11 @@ pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &Comman
         .field("Options", format!("{}", game.give_options()), false)
         .footer(CreateEmbedFooter::new(format!("George Advice: {}", game.give_help())));
 
+    let embed = match &game.fair {
+        Some(round) => embed.field("Provably Fair", round.commitment(), false),
+        None => embed,
+    };
+
     let msg = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
                                                      .embed(embed)
         .add_file(CreateAttachment::path("./images/monkey.png").await.unwrap()));
@@ -141,7 +139,7 @@ pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &Comman
         nay!("Failed to send bj message: {}", e);
     }
 
-    GAMES.lock().await.insert(GameHandler::new(user.clone(), Games::BlackJack(game)));
+    GAMES.lock().await.insert(GameHandler::new(user.clone(), Games::BlackJack(game), rng));
 }
 
 pub fn register() -> CreateCommand {
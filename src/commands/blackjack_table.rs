@@ -0,0 +1,90 @@
+use serenity::all::{CommandInteraction, CommandOptionType, Context, CreateAttachment, CreateCommand, CreateCommandOption, CreateInteractionResponse, CreateInteractionResponseMessage, ResolvedOption, ResolvedValue, UserId};
+use crate::{command_response, GAMES, MINING, nay};
+use crate::games::{GameHandler, Games};
+use crate::games::blackjack_table::{BlackjackTable, TableRules, MAX_DECKS, MAX_SPLITS};
+use crate::games::rng::GameRng;
+
+const DEFAULT_MAX_PLAYERS: i64 = 5;
+const MAX_TABLE_PLAYERS: i64 = 7;
+
+pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &CommandInteraction, sender: &UserId) {
+    let mut rules = TableRules::default();
+    let mut max_players = DEFAULT_MAX_PLAYERS;
+
+    for option in options {
+        match option {
+            ResolvedOption { name: "decks", value: ResolvedValue::Integer(val), .. } => {
+                if !(1..=MAX_DECKS as i64).contains(val) {
+                    command_response(ctx, command, format!("A shoe must have between 1 and {} decks!", MAX_DECKS)).await;
+                    return;
+                }
+                rules.decks = *val as u8;
+            }
+            ResolvedOption { name: "max_splits", value: ResolvedValue::Integer(val), .. } => {
+                if !(0..=MAX_SPLITS as i64).contains(val) {
+                    command_response(ctx, command, format!("Max splits must be between 0 and {}!", MAX_SPLITS)).await;
+                    return;
+                }
+                rules.max_splits = *val as u8;
+            }
+            ResolvedOption { name: "min_bet", value: ResolvedValue::Integer(val), .. } => {
+                if *val < 5 {
+                    command_response(ctx, command, "The minimum bet must be at least 5 bananas!").await;
+                    return;
+                }
+                rules.min_bet = *val as u64;
+            }
+            ResolvedOption { name: "max_players", value: ResolvedValue::Integer(val), .. } => max_players = *val,
+            ResolvedOption { name: "hit_soft_17", value: ResolvedValue::Boolean(val), .. } => rules.dealer_hits_soft_17 = *val,
+            ResolvedOption { name: "late_surrender", value: ResolvedValue::Boolean(val), .. } => rules.late_surrender = *val,
+            _ => {}
+        }
+    }
+
+    if !(1..=MAX_TABLE_PLAYERS).contains(&max_players) {
+        command_response(ctx, command, format!("A table can seat between 1 and {} players!", MAX_TABLE_PLAYERS)).await;
+        return;
+    }
+
+    if MINING.lock().await.contains(sender) {
+        command_response(ctx, command, "You are currently mining!").await;
+        return;
+    }
+
+    if GAMES.lock().await.get_player_game(sender).is_some() {
+        command_response(ctx, command, "You are already in a game!").await;
+        return;
+    }
+
+    let mut rng = GameRng::new();
+    let table = BlackjackTable::new(*sender, rules, max_players as usize, &mut rng);
+    let embed = table.craft_embed("George opens a table");
+
+    let code = GAMES.lock().await.insert(GameHandler::new(*sender, Games::BlackjackTable(Box::new(table)), rng));
+
+    let msg = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+        .embed(embed.description(format!("**Game code: `{}`**\nOther users can take a seat with *`/join`*. Everyone types `bet {{amount}}`, then the host types `deal`.", code)))
+        .add_file(CreateAttachment::path("./images/monkey.png").await.unwrap()));
+
+    if let Err(e) = command.create_response(&ctx.http, msg).await {
+        nay!("Failed to send blackjack table message: {}", e);
+    }
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("blackjack_table")
+        .description("Open a blackjack table other players can sit at")
+        .add_option(CreateCommandOption::new(CommandOptionType::Integer,
+                                             "decks", "Decks in the shoe (default 6)").required(false))
+        .add_option(CreateCommandOption::new(CommandOptionType::Boolean,
+                                             "hit_soft_17", "Whether George hits on soft 17 (default no)").required(false))
+        .add_option(CreateCommandOption::new(CommandOptionType::Boolean,
+                                             "late_surrender", "Whether players can surrender (default yes)").required(false))
+        .add_option(CreateCommandOption::new(CommandOptionType::Integer,
+                                             "max_splits", "How many times a hand can be split (default 3)").required(false))
+        .add_option(CreateCommandOption::new(CommandOptionType::Integer,
+                                             "min_bet", "The smallest bet allowed (default 5)").required(false))
+        .add_option(CreateCommandOption::new(CommandOptionType::Integer,
+                                             "max_players", "Max players that can sit (default 5)").required(false))
+        .dm_permission(false)
+}
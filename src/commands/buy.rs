@@ -1,7 +1,8 @@
-use serenity::all::{Colour, CommandInteraction, CommandOptionType, Context, CreateAttachment, CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage, ResolvedOption, ResolvedValue, Timestamp};
-use crate::{command_response, nay};
+use serenity::all::{Colour, CommandInteraction, CommandOptionType, Context, CreateAttachment, CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage, ResolvedOption, ResolvedValue};
+use crate::{achievements, command_response, nay};
+use crate::achievements::Stat;
 use crate::inventory::item::{InventoryItem, WeaponType};
-use crate::inventory::minion::Minion;
+use crate::inventory::minion::{Minion, MinionType};
 use crate::inventory::super_drill::SuperDrill;
 use crate::userfile::UserValues;
 
@@ -15,18 +16,44 @@ pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, cmd: &CommandInt
     // form the item fields
     let mut user_file = UserValues::get(&cmd.user.id);
 
-    // if the user's inventory is full
-    if user_file.file.inventory.is_full() {
+    // if the user's inventory is full (upgrading the super drill doesn't need a slot)
+    if user_file.file.inventory.is_full() && !(*item == 1 && user_file.has_super_drill()) {
         command_response(ctx, &cmd, "Your inventory is full! (use `/discard #` to throw out an item!").await;
         return;
     }
 
     let embed = match item {
-        1 => { // Super Pickaxe
-            if user_file.has_super_drill() {
-                command_response(ctx, &cmd, "You already own this item!").await;
+        1 if user_file.has_super_drill() => { // Super Drill upgrade
+            let drill = user_file.get_super_drill().unwrap();
+            if !drill.can_upgrade() {
+                command_response(ctx, cmd, "Your Super Drill is already max tier!").await;
                 return;
             }
+            let (nanners, parts) = drill.upgrade_cost();
+            if user_file.get_super_nanners() < nanners {
+                command_response(ctx, &cmd, "You don't have enough super nanners!").await;
+                return;
+            }
+            if user_file.get_drill_parts() < parts {
+                command_response(ctx, cmd, "You don't have enough drill parts! Defeat creatures in the mines to find more.").await;
+                return;
+            }
+            // the balances were checked above, but the file may have changed since
+            if !user_file.upgrade_super_drill() {
+                command_response(ctx, cmd, "Your Super Drill couldn't be upgraded, check your balance and try again").await;
+                return;
+            }
+
+            CreateEmbed::new()
+                .title("Upgrade Successful")
+                .description(format!("Your Super Drill is now tier {}!", drill.tier + 1))
+                .thumbnail("attachment://shop.jpeg")
+                .color(Colour::GOLD)
+                .field("Cost", format!("{}:zap: + {}:gear:", nanners, parts), true)
+                .field("Balance", format!("{}:zap: {}:gear:", user_file.get_super_nanners(), user_file.get_drill_parts()), true)
+                .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"))
+        }
+        1 => { // Super Drill
             if user_file.get_super_nanners() < 15 {
                 command_response(ctx, &cmd, "You don't have enough super nanners!").await;
                 return;
@@ -36,7 +63,7 @@ pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, cmd: &CommandInt
 
             CreateEmbed::new()
                 .title("Purchase Successful")
-                .description("You have purchased the Super Pickaxe!")
+                .description("You have purchased the Super Drill!")
                 .thumbnail("attachment://shop.jpeg")
                 .color(Colour::GOLD)
                 .field("Cost", "15:zap:", true)
@@ -48,7 +75,7 @@ pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, cmd: &CommandInt
                 command_response(ctx, &cmd, "You don't have enough super nanners!").await;
                 return;
             }
-            user_file.add_item(InventoryItem::Minion(Minion { level: 1, mining_start: Timestamp::now() }));
+            user_file.add_item(InventoryItem::Minion(Minion::new(MinionType::Worker)));
             user_file.remove_super_nanners(10);
 
             CreateEmbed::new()
@@ -170,6 +197,46 @@ pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, cmd: &CommandInt
                 .field("Balance", format!("{}:zap:", user_file.get_super_nanners()), true)
                 .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"))
         }
+        9..=11 => { // Minion types
+            let (kind, cost) = match item {
+                9 => (MinionType::Speedy, 15),
+                10 => (MinionType::Hoarder, 15),
+                _ => (MinionType::Alchemist, 25),
+            };
+            if user_file.get_super_nanners() < cost {
+                command_response(ctx, cmd, "You don't have enough super nanners!").await;
+                return;
+            }
+            let description = format!("You have purchased a {} Minion! {}", kind, kind.description());
+            user_file.add_item(InventoryItem::Minion(Minion::new(kind)));
+            user_file.remove_super_nanners(cost);
+
+            CreateEmbed::new()
+                .title("Purchase Successful")
+                .description(description)
+                .thumbnail("attachment://shop.jpeg")
+                .color(Colour::GOLD)
+                .field("Cost", format!("{}:zap:", cost), true)
+                .field("Balance", format!("{}:zap:", user_file.get_super_nanners()), true)
+                .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"))
+        }
+        12 => { // Streak Freeze
+            if user_file.get_super_nanners() < 3 {
+                command_response(ctx, cmd, "You don't have enough super nanners!").await;
+                return;
+            }
+            user_file.add_item(InventoryItem::StreakFreeze);
+            user_file.remove_super_nanners(3);
+
+            CreateEmbed::new()
+                .title("Purchase Successful")
+                .description(format!("You have purchased a Streak Freeze! You now have {}, one is used for each day or week you miss.", user_file.count_streak_freezes()))
+                .thumbnail("attachment://shop.jpeg")
+                .color(Colour::GOLD)
+                .field("Cost", "3:zap:", true)
+                .field("Balance", format!("{}:zap:", user_file.get_super_nanners()), true)
+                .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"))
+        }
         27 => { // special weapon - The Throngler for 1:zap:
             if user_file.get_super_nanners() < 1 {
                 command_response(ctx, &cmd, "You don't have enough super nanners!").await;
@@ -205,6 +272,12 @@ pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, cmd: &CommandInt
     if let Err(err) = cmd.create_response(&ctx.http, builder).await {
         nay!("Failed to respond to command: {}", err)
     }
+
+    if matches!(item, 2 | 9..=11) {
+        let minions = user_file.get_minions().len();
+        user_file.set_progress(Stat::MinionsOwned, minions as u64);
+        achievements::announce(&ctx.http, cmd.user.id, cmd.channel_id).await;
+    }
 }
 
 pub fn register() -> CreateCommand {
@@ -0,0 +1,52 @@
+use std::sync::atomic::Ordering::SeqCst;
+use serenity::all::{Colour, CommandInteraction, Context, CreateCommand, CreateEmbed, CreateEmbedFooter, CreateInteractionResponse,
+                    CreateInteractionResponseMessage, Timestamp, UserId};
+use crate::{command_response, nay, SUPERBOOST, SUPERBOOST_MODE};
+use crate::rewards::RewardKind;
+use crate::userfile::UserValues;
+
+pub async fn run(ctx: &Context, command: &CommandInteraction, user: &UserId, kind: RewardKind) {
+    let mut userfile = UserValues::get(user);
+    let boost = if SUPERBOOST_MODE.load(SeqCst) { SUPERBOOST } else { 1 };
+
+    let outcome = match userfile.claim_reward(kind, Timestamp::now().unix_timestamp(), boost) {
+        Ok(outcome) => outcome,
+        Err(next) => {
+            command_response(ctx, command, format!("You already claimed your {} reward! Come back <t:{}:R>", kind.to_string().to_lowercase(), next)).await;
+            return;
+        }
+    };
+
+    let mut embed = CreateEmbed::new()
+        .title(format!("{} Reward", kind))
+        .description(format!("You claimed {}:banana:!", outcome.reward))
+        .color(Colour::GOLD)
+        .field("Streak", format!("{} :fire:", outcome.streak), true)
+        .field("Next claim", format!("<t:{}:R>", Timestamp::now().unix_timestamp() + kind.period()), true)
+        .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"));
+
+    if outcome.freezes_used > 0 {
+        embed = embed.field("Streak saved", format!("{} streak freeze(s) melted to keep your streak going", outcome.freezes_used), false);
+    } else if outcome.lost > 1 {
+        embed = embed.field("Streak lost", format!("You missed a claim and lost your {} streak, buy streak freezes in the `/shop` to save it next time", outcome.lost), false);
+    }
+
+    let msg = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+        .embed(embed));
+
+    if let Err(e) = command.create_response(&ctx.http, msg).await {
+        nay!("Failed to respond to command: {}", e);
+    }
+}
+
+pub fn register_daily() -> CreateCommand {
+    CreateCommand::new("daily")
+        .description("Claim your daily bananas, claim every day to build a streak")
+        .dm_permission(true)
+}
+
+pub fn register_weekly() -> CreateCommand {
+    CreateCommand::new("weekly")
+        .description("Claim your weekly bananas, claim every week to build a streak")
+        .dm_permission(true)
+}
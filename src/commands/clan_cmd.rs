@@ -0,0 +1,295 @@
+use serenity::all::{Colour, CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedFooter,
+                    CreateInteractionResponse, CreateInteractionResponseMessage, Mentionable, ResolvedOption, ResolvedValue, Timestamp, UserId};
+use crate::{command_response, command_response_loud, nay, notifications};
+use crate::clans::{progress_score, Clan, ClanRank, CREATE_COST, MAX_MEMBERS};
+use crate::notifications::NotificationEvent;
+use crate::userfile::UserValues;
+
+fn score(clan: &Clan) -> u64 {
+    clan.file.members.iter()
+        .map(|id| {
+            let mut member = UserValues::get(&UserId::new(*id));
+            progress_score(member.get_level(), member.get_prestige(), member.get_ascension())
+        })
+        .sum()
+}
+
+async fn respond(ctx: &Context, command: &CommandInteraction, embed: CreateEmbed) {
+    let msg = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+        .embed(embed.footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©")).timestamp(Timestamp::now())));
+
+    if let Err(e) = command.create_response(&ctx.http, msg).await {
+        nay!("Failed to respond to command: {}", e);
+    }
+}
+
+fn info_embed(clan: &Clan) -> CreateEmbed {
+    let file = &clan.file;
+    let list = |ids: Vec<u64>| if ids.is_empty() {
+        "None".to_string()
+    } else {
+        ids.iter().map(|id| UserId::new(*id).mention().to_string()).collect::<Vec<String>>().join(", ")
+    };
+    let members = file.members.iter().copied().filter(|id| *id != file.owner && !file.officers.contains(id)).collect();
+
+    CreateEmbed::new()
+        .title(format!("[{}] {}", file.tag, file.name))
+        .color(Colour::DARK_GREEN)
+        .fields(vec![
+            ("Owner", UserId::new(file.owner).mention().to_string(), true),
+            ("Bank", format!("{}:banana:", file.bank), true),
+            ("Wars Won", file.wars_won.to_string(), true),
+            ("Progress", score(clan).to_string(), true),
+            ("Withdrawals", format!("{}s and up", file.withdraw_rank), true),
+            ("Size", format!("{}/{}", file.members.len(), MAX_MEMBERS), true),
+            ("Officers", list(file.officers.clone()), false),
+            ("Members", list(members), false),
+        ])
+}
+
+pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &CommandInteraction, user: &UserId) {
+    let Some(ResolvedOption { name: subcommand, value: ResolvedValue::SubCommand(options), .. }) = options.first() else {
+        command_response(ctx, command, "Me confused, try `/clan info`").await;
+        return;
+    };
+
+    let mut tag = None;
+    let mut name = None;
+    let mut target = None;
+    let mut amount = None;
+    let mut rank = None;
+    for option in options {
+        match option {
+            ResolvedOption { name: "tag", value: ResolvedValue::String(val), .. } => tag = Some(val.to_uppercase()),
+            ResolvedOption { name: "name", value: ResolvedValue::String(val), .. } => name = Some(val.trim().to_string()),
+            ResolvedOption { name: "user", value: ResolvedValue::User(val, ..), .. } => target = Some(val.id),
+            ResolvedOption { name: "amount", value: ResolvedValue::Integer(val), .. } => amount = Some(*val),
+            ResolvedOption { name: "rank", value: ResolvedValue::String(val), .. } => rank = ClanRank::from_name(val),
+            _ => {}
+        }
+    }
+
+    let mut userfile = UserValues::get(user);
+    let own_clan = userfile.get_clan().and_then(|tag| Clan::get(&tag));
+
+    // commands that don't need the user to be in a clan
+    match *subcommand {
+        "create" => {
+            let (Some(tag), Some(name)) = (tag, name) else {
+                command_response(ctx, command, "Me confused, Your clan needs a tag and a name").await;
+                return;
+            };
+            if own_clan.is_some() {
+                command_response(ctx, command, "Leave your clan before starting a new one").await;
+                return;
+            }
+            if userfile.get_bananas() < CREATE_COST {
+                command_response(ctx, command, format!("Starting a clan costs {}:banana:", CREATE_COST)).await;
+                return;
+            }
+            let clan = match Clan::create(&tag, &name, user) {
+                Ok(clan) => clan,
+                Err(e) => {
+                    command_response(ctx, command, e).await;
+                    return;
+                }
+            };
+            userfile.remove_bananas(CREATE_COST);
+            userfile.set_clan(Some(clan.tag.clone()));
+            command_response_loud(ctx, command, format!("{} founded **[{}] {}**! Invite others with `/clan invite`", user.mention(), clan.tag, clan.file.name)).await;
+            return;
+        }
+        "join" => {
+            let Some(mut clan) = tag.and_then(|tag| Clan::get(&tag)) else {
+                command_response(ctx, command, "There is no clan with that tag").await;
+                return;
+            };
+            if own_clan.is_some() {
+                command_response(ctx, command, "Leave your clan before joining another").await;
+                return;
+            }
+            if let Err(e) = clan.change(|file| file.accept(user)) {
+                command_response(ctx, command, e).await;
+                return;
+            }
+            userfile.set_clan(Some(clan.tag.clone()));
+            command_response_loud(ctx, command, format!("{} has joined **[{}] {}**!", user.mention(), clan.tag, clan.file.name)).await;
+            return;
+        }
+        "info" if tag.is_some() => {
+            let Some(clan) = tag.and_then(|tag| Clan::get(&tag)) else {
+                command_response(ctx, command, "There is no clan with that tag").await;
+                return;
+            };
+            respond(ctx, command, info_embed(&clan)).await;
+            return;
+        }
+        "leaderboard" => {
+            let mut clans = Clan::all().into_iter().map(|clan| (score(&clan), clan)).collect::<Vec<(u64, Clan)>>();
+            clans.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+            let ranking = if clans.is_empty() {
+                "No clans have been founded yet".to_string()
+            } else {
+                clans.iter().take(10).enumerate()
+                    .map(|(i, (score, clan))| format!("**{}.** [{}] {} - `{}` progress, {} member(s), {} war(s) won",
+                                                      i + 1, clan.tag, clan.file.name, score, clan.file.members.len(), clan.file.wars_won))
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            };
+            respond(ctx, command, CreateEmbed::new()
+                .title("Top Clans")
+                .description(ranking)
+                .color(Colour::GOLD)).await;
+            return;
+        }
+        _ => {}
+    }
+
+    let Some(mut clan) = own_clan else {
+        command_response(ctx, command, "You aren't in a clan! Start one with `/clan create` or accept an invite with `/clan join`").await;
+        return;
+    };
+
+    let message = match *subcommand {
+        "info" => {
+            respond(ctx, command, info_embed(&clan)).await;
+            return;
+        }
+        "invite" => {
+            let Some(target) = target else {
+                command_response(ctx, command, "Me confused, Pick someone to invite").await;
+                return;
+            };
+            if UserValues::get(&target).get_clan().is_some() {
+                command_response(ctx, command, "They are already in a clan").await;
+                return;
+            }
+            clan.change(|file| file.invite(user, &target)).map(|_| {
+                format!("{} has been invited to **[{}] {}**! Accept with `/clan join {}`", target.mention(), clan.tag, clan.file.name, clan.tag)
+            })
+        }
+        "leave" => {
+            clan.change(|file| file.leave(user)).map(|disband| {
+                userfile.set_clan(None);
+                if let Some(bank) = disband {
+                    // the bank is saved as empty before the file goes, so it can't be paid twice
+                    userfile.add_bananas(bank);
+                    clan.disband();
+                    format!("**[{}] {}** has been disbanded, the {}:banana: left in the bank went to {}", clan.tag, clan.file.name, bank, user.mention())
+                } else {
+                    format!("{} has left **[{}] {}**", user.mention(), clan.tag, clan.file.name)
+                }
+            })
+        }
+        "kick" => {
+            let Some(target) = target else {
+                command_response(ctx, command, "Me confused, Pick someone to kick").await;
+                return;
+            };
+            clan.change(|file| file.kick(user, &target)).map(|_| {
+                UserValues::get(&target).set_clan(None);
+                format!("{} has been kicked from **[{}]**", target.mention(), clan.tag)
+            })
+        }
+        "promote" | "demote" => {
+            let Some(target) = target else {
+                command_response(ctx, command, "Me confused, Pick a clan member").await;
+                return;
+            };
+            let result = if *subcommand == "promote" {
+                clan.change(|file| file.promote(user, &target))
+            } else {
+                clan.change(|file| file.demote(user, &target))
+            };
+            result.map(|rank| {
+                let rank = match rank {
+                    ClanRank::Owner => "the Owner",
+                    ClanRank::Officer => "an Officer",
+                    ClanRank::Member => "a Member",
+                };
+                format!("{} is now {} of **[{}]**", target.mention(), rank, clan.tag)
+            })
+        }
+        "deposit" => {
+            let Some(amount) = amount.filter(|a| *a > 0).map(|a| a as u64) else {
+                command_response(ctx, command, "Me confused, You must deposit a number of bananas").await;
+                return;
+            };
+            if userfile.get_bananas() < amount {
+                command_response(ctx, command, "You too poor!").await;
+                return;
+            }
+            clan.change(|file| file.deposit(user, amount)).map(|bank| {
+                userfile.remove_bananas(amount);
+                format!("{} deposited {}:banana:, the bank now holds {}:banana:", user.mention(), amount, bank)
+            })
+        }
+        "withdraw" => {
+            let Some(amount) = amount.filter(|a| *a > 0).map(|a| a as u64) else {
+                command_response(ctx, command, "Me confused, You must withdraw a number of bananas").await;
+                return;
+            };
+            clan.change(|file| file.withdraw(user, amount)).map(|bank| {
+                userfile.add_bananas(amount);
+                format!("{} withdrew {}:banana:, the bank now holds {}:banana:", user.mention(), amount, bank)
+            })
+        }
+        "permissions" => {
+            let Some(rank) = rank else {
+                command_response(ctx, command, "Me confused, Pick the lowest rank that can withdraw").await;
+                return;
+            };
+            clan.change(|file| file.set_withdraw_rank(user, rank))
+                .map(|_| format!("{}s and up can now withdraw from the **[{}]** bank", rank, clan.tag))
+        }
+        _ => Err("Me confused, try `/clan info`".to_string()),
+    };
+
+    match message {
+        Ok(message) => {
+            command_response_loud(ctx, command, message).await;
+            if let Some(target) = target.filter(|_| *subcommand == "invite") {
+                notifications::notify(target, NotificationEvent::ClanInvite,
+                                      format!("{} has invited you to join [{}] {}! Accept with `/clan join {}`", user.mention(), clan.tag, clan.file.name, clan.tag),
+                                      Some(command.channel_id)).await;
+            }
+        }
+        Err(e) => command_response(ctx, command, e).await,
+    }
+}
+
+pub fn register() -> CreateCommand {
+    let user = |description: &str| CreateCommandOption::new(CommandOptionType::User, "user", description).required(true);
+    let amount = |description: &str| CreateCommandOption::new(CommandOptionType::Integer, "amount", description).required(true);
+
+    CreateCommand::new("clan")
+        .description("Team up with other players in a clan")
+        .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "create", "Found a new clan")
+            .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "tag", "A short tag for the clan, like APE").required(true))
+            .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "name", "The clan's name").required(true)))
+        .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "info", "View a clan")
+            .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "tag", "The clan to view (default yours)").required(false)))
+        .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "invite", "Invite someone to your clan")
+            .add_sub_option(user("The player to invite")))
+        .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "join", "Accept an invite to a clan")
+            .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "tag", "The clan's tag").required(true)))
+        .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "leave", "Leave your clan"))
+        .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "kick", "Kick someone from your clan")
+            .add_sub_option(user("The member to kick")))
+        .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "promote", "Make a member an officer, or hand an officer the clan")
+            .add_sub_option(user("The member to promote")))
+        .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "demote", "Make an officer a member again")
+            .add_sub_option(user("The officer to demote")))
+        .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "deposit", "Put bananas in the clan bank")
+            .add_sub_option(amount("Bananas to deposit")))
+        .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "withdraw", "Take bananas out of the clan bank")
+            .add_sub_option(amount("Bananas to withdraw")))
+        .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "permissions", "Choose who can withdraw from the bank")
+            .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "rank", "The lowest rank that can withdraw")
+                .add_string_choice("member", "member")
+                .add_string_choice("officer", "officer")
+                .add_string_choice("owner", "owner")
+                .required(true)))
+        .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "leaderboard", "See the top clans"))
+        .dm_permission(true)
+}
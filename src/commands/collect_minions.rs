@@ -1,5 +1,6 @@
 use serenity::all::{CommandInteraction, Context, CreateCommand};
-use crate::command_response;
+use crate::{command_response, SLUDGE_BANANA_WORTH};
+use crate::achievements::{self, Stat};
 use crate::userfile::UserValues;
 
 pub async fn run(ctx: &Context, cmd: &CommandInteraction) {
@@ -13,12 +14,20 @@ pub async fn run(ctx: &Context, cmd: &CommandInteraction) {
         return;
     }
 
-    let total_sludge: u64 = user_file.collect_minions();
-    let bananas = total_sludge * 250;
+    let (total_sludge, nanners) = user_file.collect_minions();
+    let bananas = total_sludge * SLUDGE_BANANA_WORTH;
 
     user_file.add_bananas(bananas);
+    user_file.add_progress(Stat::SludgeCollected, total_sludge);
 
-    command_response(&ctx, &cmd, format!("You collected {} sludge from your minions totalling {}:banana:", total_sludge, bananas)).await;
+    if nanners > 0 {
+        command_response(ctx, cmd, format!("You collected {} sludge from your minions totalling {}:banana:, and {}:zap: from your alchemists!",
+                                           total_sludge, bananas, nanners)).await;
+    } else {
+        command_response(&ctx, &cmd, format!("You collected {} sludge from your minions totalling {}:banana:", total_sludge, bananas)).await;
+    }
+
+    achievements::announce(&ctx.http, cmd.user.id, cmd.channel_id).await;
 }
 
 pub fn register() -> CreateCommand {
@@ -0,0 +1,197 @@
+use std::sync::Arc;
+use std::sync::atomic::Ordering::SeqCst;
+use serenity::all::{ButtonStyle, ChannelId, CommandInteraction, CommandOptionType, ComponentInteraction, Context, CreateActionRow, CreateButton, CreateCommand, CreateCommandOption, CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, EditMessage, Http, Mentionable, ResolvedOption, ResolvedValue, Timestamp, UserId};
+use crate::{command_response, GAMES, MINING, nay, say, SUPERBOOST_MODE};
+use crate::games::{GameCode, GameHandler, Games};
+use crate::games::crash::Crash;
+use crate::games::rng::GameRng;
+use crate::bets::parse_bet_amount;
+use crate::userfile::UserValues;
+
+const BETTING_WINDOW_SECS: i64 = 20;
+// discord rate limits message edits, so the multiplier is redrawn every couple of seconds
+const TICK_MILLIS: u64 = 2000;
+// buttons carry the game code after this prefix
+pub const CASH_OUT_ID: &str = "crash_cashout:";
+
+fn cash_out_button(code: GameCode) -> CreateActionRow {
+    CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("{}{}", CASH_OUT_ID, code))
+            .label("Cash out")
+            .style(ButtonStyle::Success)
+    ])
+}
+
+// waits out the betting window then climbs the multiplier until it crashes
+pub async fn run_round(http: Arc<Http>, code: GameCode) {
+    tokio::time::sleep(tokio::time::Duration::from_secs(BETTING_WINDOW_SECS as u64)).await;
+
+    let (channel, embed) = {
+        let mut lock = GAMES.lock().await;
+        let Some(GameHandler { game: Games::Crash(crash), .. }) = lock.get_game(code) else {
+            return;
+        };
+        crash.start();
+        (crash.channel, crash.craft_embed("Cash out before it crashes!"))
+    };
+
+    let mut message = match channel.send_message(&http, CreateMessage::new().embed(embed).components(vec![cash_out_button(code)])).await {
+        Ok(message) => message,
+        Err(e) => {
+            nay!("Failed to send crash message: {}", e);
+            // nobody can cash out without the message, so the round is called off
+            let mut lock = GAMES.lock().await;
+            if let Some(GameHandler { game: Games::Crash(crash), .. }) = lock.get_game(code) {
+                for rider in &crash.riders {
+                    UserValues::get(&rider.user).add_bananas(rider.bet);
+                }
+                say!("Crash round {} called off, {} rider(s) refunded", code, crash.riders.len());
+            }
+            lock.end_game(code);
+            return;
+        }
+    };
+
+    loop {
+        tokio::time::sleep(tokio::time::Duration::from_millis(TICK_MILLIS)).await;
+
+        let (embed, crashed) = {
+            let mut lock = GAMES.lock().await;
+            let Some(GameHandler { game: Games::Crash(crash), .. }) = lock.get_game(code) else {
+                return;
+            };
+            if crash.tick() {
+                say!("Crash round {} {}", code, crash.summary());
+                let embed = crash.craft_embed("Everyone still riding lost their bet");
+                lock.end_game(code);
+                (embed, true)
+            } else {
+                (crash.craft_embed("Cash out before it crashes!"), false)
+            }
+        };
+
+        let edit = if crashed {
+            EditMessage::new().embed(embed).components(vec![])
+        } else {
+            EditMessage::new().embed(embed)
+        };
+        if let Err(e) = message.edit(&http, edit).await {
+            nay!("Failed to update crash message: {}", e);
+        }
+        if crashed {
+            return;
+        }
+    }
+}
+
+// the cash out button, pays the rider at the multiplier when they pressed it
+pub async fn cash_out(ctx: &Context, component: &ComponentInteraction) {
+    let Ok(code) = component.data.custom_id.trim_start_matches(CASH_OUT_ID).parse::<GameCode>() else {
+        return;
+    };
+    let user = component.user.id;
+
+    let response = {
+        let mut lock = GAMES.lock().await;
+        match lock.get_game(code) {
+            Some(GameHandler { game: Games::Crash(crash), .. }) => match crash.cash_out(&user) {
+                Ok((multiplier, bet)) => {
+                    let returned = Crash::payout(bet, multiplier, SUPERBOOST_MODE.load(SeqCst));
+                    UserValues::get(&user).add_bananas(returned);
+                    format!("You cashed out at {:.2}x for {}:banana:", multiplier, returned)
+                }
+                Err(e) => e.to_string(),
+            },
+            _ => "That round is over!".to_string(),
+        }
+    };
+
+    let msg = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+        .content(response)
+        .ephemeral(true));
+
+    if let Err(e) = component.create_response(&ctx.http, msg).await {
+        nay!("Failed to respond to crash cash out: {}", e);
+    }
+}
+
+pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &CommandInteraction, channel: &ChannelId, user: &UserId) {
+    let Some(ResolvedOption { value: ResolvedValue::String(raw_amt), .. }) = options.first() else {
+        command_response(ctx, command, "Me confused, You must bet a number of bananas").await;
+        return;
+    };
+
+    if MINING.lock().await.contains(user) {
+        command_response(ctx, command, "You are currently mining!").await;
+        return;
+    }
+
+    let mut userfile = UserValues::get(user);
+
+    let amt = match parse_bet_amount(&mut userfile, raw_amt) {
+        Ok(amt) => amt,
+        Err(e) => {
+            command_response(ctx, command, e).await;
+            return;
+        }
+    };
+
+    let (embed, new_round) = {
+        let mut lock = GAMES.lock().await;
+
+        // everyone in the channel rides the same round while it takes bets
+        let open = lock.games.iter()
+            .find(|(_, g)| matches!(&g.game, Games::Crash(c) if c.channel == *channel && c.can_bet()))
+            .map(|(code, _)| *code);
+
+        if lock.get_player_game(user).is_some() {
+            drop(lock);
+            command_response(ctx, command, "You are already in a game!").await;
+            return;
+        }
+
+        userfile.remove_bananas(amt);
+        let message = format!("{} bets {}:banana:", user.mention(), amt);
+
+        match open {
+            Some(code) => {
+                let game = lock.get_game(code).unwrap();
+                game.add_player(*user);
+                let Games::Crash(crash) = &mut game.game else {
+                    return;
+                };
+                crash.add_rider(*user, amt);
+                (crash.craft_embed(message), None)
+            }
+            None => {
+                let closes_at = Timestamp::now().unix_timestamp() + BETTING_WINDOW_SECS;
+                let mut rng = GameRng::new();
+                let mut crash = Crash::new(*channel, closes_at, &mut rng);
+                crash.add_rider(*user, amt);
+                let embed = crash.craft_embed(format!("The rocket takes off soon, use `/crash` to ride along!\n{}", message));
+                let code = lock.insert(GameHandler::new(*user, Games::Crash(crash), rng));
+                (embed, Some(code))
+            }
+        }
+    };
+
+    if let Some(code) = new_round {
+        tokio::spawn(run_round(ctx.http.clone(), code));
+    }
+
+    let msg = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+        .embed(embed));
+
+    if let Err(e) = command.create_response(&ctx.http, msg).await {
+        nay!("Failed to send crash message: {}", e);
+    }
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("crash")
+        .description("Ride a climbing multiplier and cash out before it crashes, everyone in the channel rides together")
+        .add_option(CreateCommandOption::new(CommandOptionType::String, "amount",
+                                             "The amount of bananas you would like to bet")
+            .required(true))
+        .dm_permission(false)
+}
@@ -0,0 +1,220 @@
+use std::sync::Arc;
+use serenity::all::{ButtonStyle, CommandInteraction, CommandOptionType, ComponentInteraction, Context, CreateActionRow, CreateButton, CreateCommand, CreateCommandOption, CreateInteractionResponse, CreateInteractionResponseMessage, EditMessage, Http, Mentionable, Message, ResolvedOption, ResolvedValue, Timestamp, UserId};
+use crate::{command_response, GAMES, MINING, nay, say};
+use crate::games::{GameCode, GameHandler, Games};
+use crate::games::duel::{Duel, DuelKind};
+use crate::games::rng::GameRng;
+use crate::guildfile::GuildSettings;
+use crate::bets::parse_bet_amount;
+use crate::userfile::UserValues;
+
+const CHALLENGE_EXPIRY_SECS: i64 = 60;
+// buttons carry the game code after these prefixes
+pub const ACCEPT_ID: &str = "duel_accept:";
+pub const DECLINE_ID: &str = "duel_decline:";
+
+fn challenge_buttons(code: GameCode) -> CreateActionRow {
+    CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("{}{}", ACCEPT_ID, code))
+            .label("Accept")
+            .style(ButtonStyle::Success),
+        CreateButton::new(format!("{}{}", DECLINE_ID, code))
+            .label("Decline")
+            .style(ButtonStyle::Danger),
+    ])
+}
+
+// closes the challenge if nobody answered it in time
+async fn expire(http: Arc<Http>, code: GameCode, mut message: Message) {
+    tokio::time::sleep(tokio::time::Duration::from_secs(CHALLENGE_EXPIRY_SECS as u64)).await;
+
+    let embed = {
+        let mut lock = GAMES.lock().await;
+        let Some(GameHandler { game: Games::Duel(duel), .. }) = lock.get_game(code) else {
+            return;
+        };
+        let embed = duel.closed_embed(format!("{} didn't answer in time, the challenge expired", duel.opponent.mention()));
+        lock.end_game(code);
+        embed
+    };
+
+    if let Err(e) = message.edit(&http, EditMessage::new().embed(embed).components(vec![])).await {
+        nay!("Failed to expire duel message: {}", e);
+    }
+}
+
+// the accept and decline buttons, either player can call it off but only the opponent can accept
+pub async fn respond(ctx: &Context, component: &ComponentInteraction) {
+    let id = component.data.custom_id.as_str();
+    let accepted = id.starts_with(ACCEPT_ID);
+    let Ok(code) = id.trim_start_matches(ACCEPT_ID).trim_start_matches(DECLINE_ID).parse::<GameCode>() else {
+        return;
+    };
+    let user = component.user.id;
+    let mining = MINING.lock().await.contains(&user);
+
+    let result = {
+        let mut lock = GAMES.lock().await;
+        let busy = lock.get_player_game(&user).is_some_and(|c| c != code);
+
+        match lock.get_game(code) {
+            Some(GameHandler { game: Games::Duel(duel), rng, .. }) => {
+                if user != duel.opponent && (accepted || user != duel.challenger) {
+                    Err("This duel isn't for you!".to_string())
+                } else if !accepted {
+                    let embed = if user == duel.challenger {
+                        duel.closed_embed(format!("{} called off the duel", user.mention()))
+                    } else {
+                        duel.closed_embed(format!("{} declined the duel", user.mention()))
+                    };
+                    lock.end_game(code);
+                    Ok(embed)
+                } else if busy || mining {
+                    Err("You are busy with something else!".to_string())
+                } else {
+                    let mut challenger = UserValues::get(&duel.challenger);
+                    let mut opponent = UserValues::get(&duel.opponent);
+
+                    if opponent.get_bananas() < duel.stake {
+                        Err("You too poor!".to_string())
+                    } else if challenger.get_bananas() < duel.stake {
+                        let embed = duel.closed_embed(format!("{} can't cover the stake anymore, the duel is off", duel.challenger.mention()));
+                        lock.end_game(code);
+                        Ok(embed)
+                    } else {
+                        // both stakes are held until the round is settled
+                        challenger.remove_bananas(duel.stake);
+                        opponent.remove_bananas(duel.stake);
+
+                        let outcome = duel.resolve(rng);
+                        let rake = component.guild_id.map(|g| GuildSettings::get(&g).get_duel_rake()).unwrap_or(0);
+                        // bananas only change hands here, so superboost doesn't apply
+                        let (winnings, rake) = duel.split_pot(rake);
+                        UserValues::get(&outcome.winner).add_bananas(winnings);
+
+                        say!("{} duel: {} beat {} for {} (rake {})", duel.kind, outcome.winner, outcome.loser, winnings, rake);
+                        let embed = duel.result_embed(&outcome, winnings, rake);
+                        lock.end_game(code);
+                        Ok(embed)
+                    }
+                }
+            }
+            _ => Err("That duel is over!".to_string()),
+        }
+    };
+
+    let msg = match result {
+        Ok(embed) => CreateInteractionResponse::UpdateMessage(CreateInteractionResponseMessage::new()
+            .embed(embed)
+            .components(vec![])),
+        Err(e) => CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+            .content(e)
+            .ephemeral(true)),
+    };
+
+    if let Err(e) = component.create_response(&ctx.http, msg).await {
+        nay!("Failed to respond to duel: {}", e);
+    }
+}
+
+pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &CommandInteraction, user: &UserId) {
+    let mut opponent = None;
+    let mut raw_amt = None;
+    let mut kind = None;
+
+    for option in options {
+        match option {
+            ResolvedOption { name: "user", value: ResolvedValue::User(target, _), .. } => opponent = Some(*target),
+            ResolvedOption { name: "amount", value: ResolvedValue::String(val), .. } => raw_amt = Some(val.to_lowercase()),
+            ResolvedOption { name: "game", value: ResolvedValue::String(val), .. } => kind = DuelKind::parse(val),
+            _ => {}
+        }
+    }
+
+    let (Some(opponent), Some(raw_amt), Some(kind)) = (opponent, raw_amt, kind) else {
+        command_response(ctx, command, "Me confused, You must pick someone to duel, a number of bananas and a game").await;
+        return;
+    };
+
+    if opponent.id == *user || opponent.bot {
+        command_response(ctx, command, "You can't duel them!").await;
+        return;
+    }
+
+    if MINING.lock().await.contains(user) {
+        command_response(ctx, command, "You are currently mining!").await;
+        return;
+    }
+
+    let mut userfile = UserValues::get(user);
+
+    let amt = match parse_bet_amount(&mut userfile, &raw_amt) {
+        Ok(amt) => amt,
+        Err(e) => {
+            command_response(ctx, command, e).await;
+            return;
+        }
+    };
+
+    let mut opponent_file = UserValues::get(&opponent.id);
+    if opponent_file.get_bananas() < amt {
+        command_response(ctx, command, "They too poor!").await;
+        return;
+    }
+
+    if amt > opponent_file.max_bet() {
+        command_response(ctx, command, format!("That's over their bet limit of {}:banana:", opponent_file.max_bet())).await;
+        return;
+    }
+
+    let (embed, code) = {
+        let mut lock = GAMES.lock().await;
+        if lock.get_player_game(user).is_some() {
+            drop(lock);
+            command_response(ctx, command, "You are already in a game!").await;
+            return;
+        }
+
+        let expires_at = Timestamp::now().unix_timestamp() + CHALLENGE_EXPIRY_SECS;
+        let duel = Duel::new(*user, opponent.id, kind, amt, command.channel_id, expires_at);
+        let embed = duel.challenge_embed();
+        let code = lock.insert(GameHandler::new(*user, Games::Duel(duel), GameRng::new()));
+        (embed, code)
+    };
+
+    let msg = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+        .content(opponent.id.mention().to_string())
+        .embed(embed)
+        .components(vec![challenge_buttons(code)]));
+
+    if let Err(e) = command.create_response(&ctx.http, msg).await {
+        nay!("Failed to send duel message: {}", e);
+        GAMES.lock().await.end_game(code);
+        return;
+    }
+
+    match command.get_response(&ctx.http).await {
+        Ok(message) => {
+            tokio::spawn(expire(ctx.http.clone(), code, message));
+        }
+        Err(e) => nay!("Failed to get duel message: {}", e),
+    }
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("duel")
+        .description("Challenge another player, both put up the same bananas and the winner takes the pot")
+        .add_option(CreateCommandOption::new(CommandOptionType::User, "user",
+                                             "The player to challenge")
+            .required(true))
+        .add_option(CreateCommandOption::new(CommandOptionType::String, "amount",
+                                             "The amount of bananas each player puts up")
+            .required(true))
+        .add_option(CreateCommandOption::new(CommandOptionType::String, "game",
+                                             "How the duel is decided")
+            .add_string_choice("Coin flip", "coinflip")
+            .add_string_choice("Dice", "dice")
+            .add_string_choice("High card", "highcard")
+            .required(true))
+        .dm_permission(false)
+}
@@ -0,0 +1,80 @@
+use serenity::all::{Colour, CommandInteraction, CommandOptionType, Context, CreateAttachment, CreateCommand, CreateCommandOption, CreateInteractionResponse, CreateInteractionResponseMessage, ResolvedOption, ResolvedValue, UserId};
+use crate::{command_response, GAMES, MINING, nay};
+use crate::games::{GameHandler, Games};
+use crate::games::expedition::Expedition;
+use crate::games::rng::GameRng;
+use crate::mine_data::Mine;
+use crate::userfile::UserValues;
+
+pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &CommandInteraction, sender: &UserId) {
+    if MINING.lock().await.contains(sender) {
+        command_response(ctx, command, "You are currently mining!").await;
+        return;
+    }
+
+    if GAMES.lock().await.get_player_game(sender).is_some() {
+        command_response(ctx, command, "Finish your current game first!").await;
+        return;
+    }
+
+    let mine = Mine::get();
+    let mut user_file = UserValues::get(sender);
+
+    // expeditions default to the tier the user mines at
+    let tier = if let Some(ResolvedOption { value: ResolvedValue::Integer(tier, ..), .. }) = options.first() {
+        let tier = *tier;
+        if tier < 0 || tier > u8::MAX as i64 || !mine.tiers.contains_key(&(tier as u8)) {
+            command_response(ctx, command, "Invalid mine tier!").await;
+            return;
+        }
+        tier as u8
+    } else {
+        user_file.get_mine_tier()
+    };
+
+    let current_tier = mine.get_tier(tier);
+
+    if current_tier.required_super_drill_tier > user_file.get_super_drill_tier() {
+        command_response(ctx, command, format!("You can't go on an expedition here! You need a tier {} Super Drill (`/shop`) to explore tier {}!",
+                                               current_tier.required_super_drill_tier, tier)).await;
+        return;
+    }
+
+    // some tiers stay closed until a milestone unlocks them
+    if let Some(milestone) = user_file.mine_tier_lock(tier) {
+        command_response(ctx, command, format!("You can't go on an expedition here yet! Tier {} unlocks with the **{}** milestone ({})",
+                                               tier, milestone.name, milestone.requirement())).await;
+        return;
+    }
+
+    let Some(config) = current_tier.expedition.clone() else {
+        command_response(ctx, command, format!("There are no expeditions into tier {} yet!", tier)).await;
+        return;
+    };
+
+    let mut expedition = Expedition::new(tier, config, current_tier.creatures.clone(), current_tier.sludge_worth);
+    expedition.health += user_file.get_perks().bonus_health();
+
+    let embed = expedition.craft_embed("Entrance",
+                                       "You stand at the entrance of the mine. The deeper you go the more sludge you will find, \
+                                       but if you die down there your haul is lost!", Colour::DARK_GREEN);
+
+    let msg = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+        .embed(embed)
+        .add_file(CreateAttachment::path(expedition.thumbnail_path()).await.unwrap()));
+
+    if let Err(e) = command.create_response(&ctx.http, msg).await {
+        nay!("Failed to send expedition message: {}", e);
+        return;
+    }
+
+    GAMES.lock().await.insert(GameHandler::new(*sender, Games::Expedition(Box::new(expedition)), GameRng::new()));
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("expedition")
+        .description("Descend deep into the mines for greater rewards")
+        .add_option(CreateCommandOption::new(CommandOptionType::Integer,
+                                             "tier", "The tier you want to explore (defaults to your mining tier)").required(false))
+        .dm_permission(true)
+}
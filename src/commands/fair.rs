@@ -0,0 +1,57 @@
+use serenity::all::{Colour, CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption, CreateEmbed,
+                    CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage, ResolvedOption, ResolvedValue};
+use crate::{command_response, nay};
+use crate::userfile::UserValues;
+
+const MAX_CLIENT_SEED_LENGTH: usize = 64;
+
+pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, cmd: &CommandInteraction) {
+    let mut user_file = UserValues::get(&cmd.user.id);
+
+    for option in options {
+        match option {
+            ResolvedOption { name: "enabled", value: ResolvedValue::Boolean(enabled), .. } => user_file.set_fair_mode(*enabled),
+            ResolvedOption { name: "client_seed", value: ResolvedValue::String(seed), .. } => {
+                let seed = seed.trim();
+                if seed.is_empty() || seed.len() > MAX_CLIENT_SEED_LENGTH {
+                    command_response(ctx, cmd, format!("Your client seed must be 1 to {} characters!", MAX_CLIENT_SEED_LENGTH)).await;
+                    return;
+                }
+                user_file.set_client_seed(seed.to_string());
+            }
+            _ => {}
+        }
+    }
+
+    let fairness = user_file.get_fairness();
+
+    let embed = CreateEmbed::new()
+        .title("Provably Fair")
+        .description("When enabled, `/slots`, `/fiftyfifty` and `/blackjack` are decided by HMAC-SHA256(server seed, `client seed:nonce`).\n\
+                      George commits to the hash of your next server seed before you bet and reveals the seed afterwards, \
+                      so you can check every result with `/verify`.")
+        .color(Colour::GOLD)
+        .field("Enabled", if fairness.enabled { "Yes" } else { "No" }, true)
+        .field("Nonce", format!("{}", fairness.nonce), true)
+        .field("Client Seed", format!("`{}`", fairness.client_seed), false)
+        .field("Next Server Seed Hash", format!("`{}`", fairness.server_seed_hash()), false)
+        .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"));
+
+    let builder = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+        .embed(embed)
+        .ephemeral(true));
+
+    if let Err(err) = cmd.create_response(&ctx.http, builder).await {
+        nay!("Failed to respond to command: {}", err)
+    }
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("fair")
+        .description("View or change your provably fair gambling settings")
+        .add_option(CreateCommandOption::new(CommandOptionType::Boolean, "enabled", "Use provably fair seeds for your bets")
+            .required(false))
+        .add_option(CreateCommandOption::new(CommandOptionType::String, "client_seed", "Your own seed mixed into every bet")
+            .required(false))
+        .dm_permission(false)
+}
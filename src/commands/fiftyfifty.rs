@@ -1,27 +1,41 @@
 use std::sync::atomic::Ordering::SeqCst;
-use rand::{Rng, thread_rng};
+use rand::Rng;
 use serenity::all::{CommandInteraction, Context, CreateCommand, CreateInteractionResponse,
                     CreateInteractionResponseMessage, UserId};
 use serenity::builder::{CreateEmbed, CreateEmbedFooter};
 use serenity::model::Colour;
-use crate::{command_response, hey, SKEPZ_WIN_ALWAYS, SUPERBOOST, SUPERBOOST_MODE};
+use crate::{command_response, hey, say, SKEPZ_WIN_ALWAYS, SUPERBOOST, SUPERBOOST_MODE};
+use crate::games::rng::GameRng;
 use crate::userfile::UserValues;
 
+// 50% chance to win
+pub fn flip(rng: &mut GameRng) -> bool {
+    rng.gen_range(0..2) == 1
+}
+
 pub async fn run(ctx: &Context, command: &CommandInteraction, user: &UserId) {
 
     let mut userfile = UserValues::get(user);
 
-    let amt = userfile.get_bananas();
+    // everything goes on the flip, up to the user's bet limit
+    let max_bet = userfile.max_bet();
+    let limited = userfile.get_bananas() > max_bet;
+    let amt = userfile.get_bananas().min(max_bet);
 
     if amt == 0 {
         command_response(ctx, command, "You have no bananas to bet with!").await;
         return;
     }
 
-    // 50% chance to win
-    let win = thread_rng().gen_range(0..2) == 1;
+    let fair = userfile.next_fair_round();
+    let mut rng = fair.as_ref().map(|round| round.rng()).unwrap_or_default();
+    say!("50/50 for {} (seed {})", user, rng.seed());
 
-    let embed = if win || (SKEPZ_WIN_ALWAYS.load(SeqCst) && (user.get() == 318884828508454912))  {
+    let win = flip(&mut rng);
+    // provably fair flips can't be rigged
+    let rigged = fair.is_none() && SKEPZ_WIN_ALWAYS.load(SeqCst) && (user.get() == 318884828508454912);
+
+    let mut embed = if win || rigged {
         let mut winnings = amt;
         if SUPERBOOST_MODE.load(SeqCst) {
             winnings *= SUPERBOOST;
@@ -44,6 +58,14 @@ pub async fn run(ctx: &Context, command: &CommandInteraction, user: &UserId) {
             .footer(CreateEmbedFooter::new("Me eat good tonight!"))
     };
 
+    if limited {
+        embed = embed.field("Bet Limit", format!("Only {}:banana: went on the flip, reach milestones to raise your limit", max_bet), false);
+    }
+
+    if let Some(round) = fair {
+        embed = embed.field("Provably Fair", round.reveal(), false);
+    }
+
     let response = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
         .add_embed(embed));
 
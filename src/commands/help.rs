@@ -3,7 +3,7 @@ use serenity::builder::CreateEmbed;
 use crate::nay;
 
 pub async fn run(ctx: &Context, cmd: &CommandInteraction) {
-    let mut embed = CreateEmbed::new()
+    let embed = CreateEmbed::new()
         .title("Help")
         .description("Me can do many things!")
         .color(Colour::GOLD)
@@ -12,41 +12,84 @@ pub async fn run(ctx: &Context, cmd: &CommandInteraction) {
             vec![
                 ("/help", "Me show you commands", true),
                 ("/info", "View your stats", true),
+                ("/daily", "Claim daily bananas and keep your streak going", true),
+                ("/weekly", "Claim weekly bananas and keep your streak going", true),
+                ("/achievements", "See your achievements and pick a title", true),
+                ("/quests", "Complete daily quests for bananas", true),
                 ("/inventory", "View your inventory", true),
                 ("/discard", "Discard an item", true),
                 ("/shop", "View the shop", true),
                 ("/buy", "Buy an item from the shop", true),
                 ("/leaderboard", "See no-lifes", true),
+                ("/season", "See who is winning this season and past results", true),
                 ("/levelup", "Buy level with banana", true),
                 ("/prestige", "Prestige at level 100", true),
                 ("/ascend", "Show you have no life at prestige 10", true),
+                ("/perks", "Spend ascension perk points", true),
                 ("/mine", "Work for bananas and more", true),
+                ("/expedition", "Dig deep into the mines, if you dare", true),
                 ("/equip", "Equip a weapon to use in combat", true),
                 ("/unequip", "Unequip an item", true),
-                ("/mine", "Work for bananas and more", true),
                 ("/pay", "Give banana", true),
                 ("/collect_minions", "Collect sludge mined by your minions", true),
+                ("/minions", "See how full your minions are", true),
+                ("/minion upgrade", "Upgrade a minion", true),
+                ("/notifications", "Choose how you get notified", true),
+            ]
+        )
+        .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"));
 
-                ("/pvp", "Start a PvP arena", true),
-                ("/join", "Join a PvP arena", true),
+    // embeds can only hold 25 fields, so games get their own
+    let games = CreateEmbed::new()
+        .title("Games")
+        .color(Colour::GOLD)
+        .fields(
+            vec![
+                ("/pvp", "Start a PvP arena, or a clan war", true),
+                ("/clan", "Found or join a clan, share a bank and climb the clan leaderboard", true),
+                ("/join", "Join a PvP arena, raid or blackjack table", true),
+                ("/raid", "Team up to take down a mine boss", true),
                 ("/blackjack", "Gamble bananas in a game of blackjack", true),
+                ("/blackjack_table", "Open a blackjack table for friends", true),
                 ("/fiftyfifty", "Gamble bananas with a 50% chance", true),
-                ("/slots", "You spin me right round", true),
+                ("/roulette", "Bet on the wheel with everyone in the channel", true),
+                ("/crash", "Cash out before the rising multiplier crashes", true),
+                ("/duel", "Challenge someone to a coin flip, dice or high card duel", true),
+                ("/lottery", "Buy tickets for the server's lottery draw", true),
+                ("/monkey_race", "Bet on a race of monkeys with everyone in the channel", true),
+                ("/slots", "You spin me right round, pick a machine to chase the jackpot", true),
+                ("/fair", "Turn on provably fair gambling", true),
+                ("/verify", "Check a provably fair bet", true),
             ]
         )
         .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"));
 
+    let mut embeds = vec![embed, games];
+
     // if the sender is an admin
     if let Some(member) = cmd.member.as_ref() {
         if let Some(perms) = member.permissions {
             if perms.contains(Permissions::ADMINISTRATOR) {
-                embed = embed.field("/admin_channel", "Add and remove channels from Ape Bot's allowed channels (allowed commands)", true);
+                embeds.push(CreateEmbed::new()
+                    .title("Admin")
+                    .color(Colour::GOLD)
+                    .fields(
+                        vec![
+                            ("/admin_channel", "Add and remove channels from Ape Bot's allowed channels (allowed commands)", true),
+                            ("/admin_rake", "Set the percent of every duel pot the house keeps", true),
+                            ("/admin_lottery", "Set up the server lottery and where its draws are announced", true),
+                            ("/admin_quests", "Change the quest pool, rewards and reset time", true),
+                            ("/admin_season", "Set how long seasons last, what they reset and where results go", true),
+                            ("/admin_economy", "View the audit log, the bot owner can give, take or set users' bananas, progress and items", true),
+                        ]
+                    )
+                    .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©")));
             }
         }
     }
 
     let builder = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
-        .embed(embed)
+        .embeds(embeds)
         .ephemeral(true)
         .add_file(CreateAttachment::path("./images/george.png").await.unwrap()));
 
@@ -1,7 +1,6 @@
 use serenity::all::{Colour, CommandInteraction, Context, CreateAttachment, CreateCommand,
                     CreateEmbed, CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage};
 use crate::inventory::item::InventoryItem;
-use crate::inventory::minion::MINION_BASE_MAX_SLUDGE;
 use crate::nay;
 use crate::userfile::UserValues;
 
@@ -11,6 +10,7 @@ pub async fn run(ctx: &Context, cmd: &CommandInteraction) {
 
     let inv = user_file.get_items();
     let equipped = user_file.get_equiped();
+    let capacity = user_file.get_perks().minion_capacity();
 
     if inv.is_empty() {
         let embed = CreateEmbed::new()
@@ -36,11 +36,11 @@ pub async fn run(ctx: &Context, cmd: &CommandInteraction) {
         let x = x + 1;
         match item {
             InventoryItem::Minion(m) => {
-                if m.is_full() {
-                    items.push((format!("{}: Minion", x), "Full".to_string(), false));
+                if m.is_full(capacity) {
+                    items.push((format!("{}: {}", x, item), "Full".to_string(), false));
                 } else {
-                    items.push((format!("{}: Minion", x),
-                                format!("{}/{}", m.get_sludge_produced(), MINION_BASE_MAX_SLUDGE), true));
+                    items.push((format!("{}: {}", x, item),
+                                format!("{}/{}", m.get_sludge_produced(capacity), m.max_sludge(capacity)), true));
                 }
             }
             InventoryItem::HealingPotion { health: max_effectiveness } => {
@@ -55,6 +55,9 @@ pub async fn run(ctx: &Context, cmd: &CommandInteraction) {
             InventoryItem::Weapon { name, wtype, damage } => {
                 items.push((format!("{}: {} ({})", x, name, wtype), format!("{}-{} damage", damage.start(), damage.end()), true));
             }
+            InventoryItem::StreakFreeze => {
+                items.push((format!("{}: Streak Freeze", x), "Saves a missed day".to_string(), true));
+            }
         }
     }
 
@@ -32,6 +32,11 @@ pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context,
         }
     }
 
+    if GAMES.lock().await.requires_clan(code as GameCode) && user_values.get_clan().is_none() {
+        command_response(ctx, command, "You need to be in a clan to join a clan war!").await;
+        return;
+    }
+
     // ensure the user is not already in a game
     if GAMES.lock().await.get_player_game(user).is_some() {
         // error message
@@ -0,0 +1,119 @@
+use serenity::all::{Colour, CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedFooter,
+                    CreateInteractionResponse, CreateInteractionResponseMessage, GuildId, ResolvedOption, ResolvedValue, UserId};
+use crate::{command_response, nay};
+use crate::games::rng::GameRng;
+use crate::guildfile::GuildSettings;
+use crate::lottery::{format_numbers, Lottery, HIGHEST_NUMBER, MAX_TICKETS_PER_USER, PICKS, PRIZE_TIERS, TICKET_PRICE};
+use crate::userfile::UserValues;
+
+pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &CommandInteraction, guild: &GuildId, user: &UserId) {
+    let mut count = None;
+    let mut numbers = None;
+
+    for option in options {
+        match option {
+            ResolvedOption { name: "tickets", value: ResolvedValue::Integer(val), .. } => count = Some(*val),
+            ResolvedOption { name: "numbers", value: ResolvedValue::String(val), .. } => numbers = Some(*val),
+            _ => {}
+        }
+    }
+
+    let mut guild_settings = GuildSettings::get(guild);
+
+    // buy tickets if any were asked for
+    let bought = if count.is_some() || numbers.is_some() {
+        let tickets = match numbers {
+            Some(spec) => {
+                if count.is_some_and(|c| c != 1) {
+                    command_response(ctx, command, "Picked numbers only fill one ticket, leave them out for quick picks").await;
+                    return;
+                }
+                match Lottery::parse_numbers(spec) {
+                    Ok(numbers) => vec![numbers],
+                    Err(e) => {
+                        command_response(ctx, command, e).await;
+                        return;
+                    }
+                }
+            }
+            None => {
+                let count = count.unwrap_or(1);
+                if !(1..=MAX_TICKETS_PER_USER as i64).contains(&count) {
+                    command_response(ctx, command, format!("You can buy between 1 and {} tickets", MAX_TICKETS_PER_USER)).await;
+                    return;
+                }
+                let mut rng = GameRng::new();
+                (0..count).map(|_| Lottery::quick_pick(&mut rng)).collect()
+            }
+        };
+
+        let mut userfile = UserValues::get(user);
+        let cost = tickets.len() as u64 * TICKET_PRICE;
+        if userfile.get_bananas() < cost {
+            command_response(ctx, command, "You too poor!").await;
+            return;
+        }
+
+        let picked = tickets.iter().map(|t| format_numbers(t)).collect::<Vec<String>>();
+        if let Err(e) = guild_settings.buy_lottery_tickets(user, tickets) {
+            command_response(ctx, command, e).await;
+            return;
+        }
+        userfile.remove_bananas(cost);
+        Some((picked, cost))
+    } else {
+        None
+    };
+
+    let lottery = guild_settings.get_lottery();
+    if !lottery.is_running() {
+        command_response(ctx, command, "The lottery hasn't been set up here yet!").await;
+        return;
+    }
+
+    let tiers = PRIZE_TIERS.iter()
+        .map(|(matched, percent)| format!("Match {}: {}% of the pot", matched, percent))
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    let yours = lottery.user_tickets(user).iter()
+        .map(|t| format_numbers(&t.numbers))
+        .collect::<Vec<String>>();
+
+    let mut embed = CreateEmbed::new()
+        .title("Lottery")
+        .description(format!("Tickets cost {}:banana: and pick {} numbers from 1 to {}", TICKET_PRICE, PICKS, HIGHEST_NUMBER))
+        .color(Colour::GOLD)
+        .field("Pot", format!("{}:banana:", lottery.pot), true)
+        .field("Next draw", format!("<t:{}:R>", lottery.next_draw), true)
+        .field("Tickets sold", lottery.tickets.len().to_string(), true)
+        .field("Prizes", tiers, false)
+        .field(format!("Your tickets ({})", yours.len()),
+               if yours.is_empty() { "None yet, buy some with `/lottery tickets`".to_string() } else { yours.iter().take(20).cloned().collect::<Vec<String>>().join("\n") },
+               false)
+        .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"));
+
+    if let Some((picked, cost)) = bought {
+        embed = embed.field(format!("Bought {} ticket(s) for {}:banana:", picked.len(), cost), picked.iter().take(20).cloned().collect::<Vec<String>>().join("\n"), false);
+    }
+
+    let builder = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+        .embed(embed)
+        .ephemeral(true));
+
+    if let Err(e) = command.create_response(&ctx.http, builder).await {
+        nay!("Failed to send lottery message: {}", e);
+    }
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("lottery")
+        .description("Check the server lottery or buy tickets")
+        .add_option(CreateCommandOption::new(CommandOptionType::Integer, "tickets",
+                                             "How many quick pick tickets to buy")
+            .required(false))
+        .add_option(CreateCommandOption::new(CommandOptionType::String, "numbers",
+                                             "Pick your own numbers for one ticket, like `3 7 12 19`")
+            .required(false))
+        .dm_permission(false)
+}
@@ -1,10 +1,13 @@
 use std::sync::Arc;
-use rand::{Rng, thread_rng};
+use rand::Rng;
 use serenity::all::{ChannelId, Colour, CommandInteraction, CommandOptionType, Context, CreateAttachment, CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedFooter, CreateMessage, Http, Mentionable, ResolvedOption, ResolvedValue, Timestamp, UserId};
-use crate::{command_response, GAMES, MINING, nay};
+use crate::{command_response, GAMES, MINING, nay, notifications};
+use crate::achievements::{self, Stat};
 use crate::games::{GameHandler, Games};
 use crate::games::mine_battle::MineBattle;
+use crate::games::rng::GameRng;
 use crate::mine_data::Mine;
+use crate::notifications::{NotificationEvent, NotifyMode};
 use crate::userfile::UserValues;
 
 const BASE_MINE_TIME: u64 = 60;
@@ -15,15 +18,31 @@ pub async fn finish_mine(http: Arc<Http>, channel: ChannelId, sender: UserId) {
 
     let mut user_file = UserValues::get(&sender);
 
+    // only ping the user in the channel if they want it, otherwise they may want a DM
+    let mode = user_file.get_notification_settings().mode(NotificationEvent::MineComplete);
+    let mention = if mode == NotifyMode::Channel {
+        format!("{}", sender.mention())
+    } else {
+        String::new()
+    };
+    if mode == NotifyMode::Dm {
+        notifications::notify(sender, NotificationEvent::MineComplete,
+                              format!("Your mining trip has finished! See how it went in {}", channel.mention()), Some(channel)).await;
+    }
+
     let mine = Mine::get();
 
     let current_tier = mine.get_tier(user_file.get_mine_tier());
 
+    // a creature battle keeps this so the fight can be replayed from its seed
+    let mut rng = GameRng::new();
+
     // creature (12.5% chance)
-    if thread_rng().gen_range(0..8) == 0 {
-        let creature = current_tier.random_enemy();
+    if rng.gen_range(0..8) == 0 {
+        let creature = current_tier.random_enemy(&mut rng);
 
-        let battle = MineBattle::new(creature.clone(), current_tier.sludge_worth);
+        let mut battle = MineBattle::new(creature.clone(), current_tier.sludge_worth, &mut rng);
+        battle.player.health += user_file.get_perks().bonus_health();
         let thumbnail = creature.thumbnail.clone();
 
         let embed = CreateEmbed::new()
@@ -32,7 +51,7 @@ pub async fn finish_mine(http: Arc<Http>, channel: ChannelId, sender: UserId) {
             .description(format!("A {} has appeared! You must defeat it to continue mining.", battle.enemy.name))
             .fields(
                 vec![
-                    ("Creature Health", format!("{}", battle.enemy_health), true),
+                    ("Creature Health", format!("{}", battle.creature.health), true),
                     ("Your Health", "100".to_string(), true),
                     ("Options: ", "`attack`, `run`, `item {inventory slot #}` or `surrender`".to_string(), false),
                 ]
@@ -41,10 +60,10 @@ pub async fn finish_mine(http: Arc<Http>, channel: ChannelId, sender: UserId) {
             .timestamp(Timestamp::now());
 
         // create the game
-        let game = GameHandler::new(sender.clone(), Games::MineBattle(battle));
+        let game = GameHandler::new(sender.clone(), Games::MineBattle(battle), rng);
 
         let builder = CreateMessage::new()
-            .content(format!("{}", sender.mention()))
+            .content(mention.clone())
             .embed(embed)
             .add_file(CreateAttachment::path(format!("./images/sludge_monsters/{}", thumbnail)).await.unwrap());
 
@@ -59,7 +78,7 @@ pub async fn finish_mine(http: Arc<Http>, channel: ChannelId, sender: UserId) {
     }
 
     // super nanner chance
-    let chance = thread_rng().gen_range(0..100);
+    let chance = rng.gen_range(0..100);
     if current_tier.super_nanner_chance > chance {
         user_file.add_super_nanners(1);
         let embed = CreateEmbed::new()
@@ -70,7 +89,7 @@ pub async fn finish_mine(http: Arc<Http>, channel: ChannelId, sender: UserId) {
             .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"));
 
         let builder = CreateMessage::new()
-            .content(format!("{}", sender.mention()))
+            .content(mention.clone())
             .embed(embed)
             .add_file(CreateAttachment::path("./images/super_nanner.jpeg").await.unwrap());
 
@@ -81,9 +100,9 @@ pub async fn finish_mine(http: Arc<Http>, channel: ChannelId, sender: UserId) {
         return;
     }
 
-    // item drop chance
-    if 8 > thread_rng().gen_range(0..100) {
-        let item = current_tier.drop_table.random_item();
+    // item drop chance, raised by the item find perk
+    if 8 + user_file.get_perks().item_find_chance() > rng.gen_range(0..100) {
+        let item = current_tier.drop_table.random_item(&mut rng);
 
         let embed = if user_file.file.inventory.is_full() {
             CreateEmbed::new()
@@ -104,7 +123,7 @@ pub async fn finish_mine(http: Arc<Http>, channel: ChannelId, sender: UserId) {
         };
 
         let builder = CreateMessage::new()
-            .content(format!("{}", sender.mention()))
+            .content(mention.clone())
             .embed(embed);
 
         if let Err(e) = channel.send_message(&http, builder).await {
@@ -115,7 +134,7 @@ pub async fn finish_mine(http: Arc<Http>, channel: ChannelId, sender: UserId) {
     }
 
     // determine the amount of sludge
-    let sludge = thread_rng().gen_range(0..=10);
+    let sludge = rng.gen_range(0..=10);
 
     if sludge == 0 {
         let embed = CreateEmbed::new()
@@ -129,7 +148,7 @@ pub async fn finish_mine(http: Arc<Http>, channel: ChannelId, sender: UserId) {
             .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"));
 
         let builder = CreateMessage::new()
-            .content(format!("{}", sender.mention()))
+            .content(mention.clone())
             .embed(embed)
             .add_file(CreateAttachment::path("./images/disappointed.jpeg").await.unwrap());
 
@@ -138,7 +157,7 @@ pub async fn finish_mine(http: Arc<Http>, channel: ChannelId, sender: UserId) {
         }
 
         // if let Err(e) = command.edit_response(http, EditInteractionResponse::new()
-        //     .content(format!("{}", sender.mention()))
+        //     .content(mention.clone())
         //     .embed(embed)).await {
         //     nay!("Failed to send message: {}", e);
         // }
@@ -146,11 +165,20 @@ pub async fn finish_mine(http: Arc<Http>, channel: ChannelId, sender: UserId) {
         return;
     }
 
+    // the super drill digs up extra sludge
+    let sludge = if let Some(drill) = user_file.get_super_drill() {
+        (sludge as f32 * drill.yield_multiplier()).round() as u32
+    } else {
+        sludge
+    };
+
     // determine the value of the mined sludge
     let value = sludge * current_tier.sludge_worth;
 
     // update the user file
     user_file.add_bananas(value as u64);
+    let tier = user_file.get_mine_tier();
+    user_file.add_progress(Stat::MinedAtTier(tier), 1);
 
     // send the success message
     let embed = CreateEmbed::new()
@@ -164,16 +192,17 @@ pub async fn finish_mine(http: Arc<Http>, channel: ChannelId, sender: UserId) {
         .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"));
 
     let builder = CreateMessage::new()
-        .content(format!("{}", sender.mention()))
+        .content(mention.clone())
         .embed(embed)
         .add_file(CreateAttachment::path("./images/mining_ape.jpeg").await.unwrap());
 
     if let Err(e) = channel.send_message(&http, builder).await {
         nay!("Failed to send message: {}", e);
     }
+    achievements::announce(&http, sender, channel).await;
 
     // if let Err(e) = command.edit_response(http, EditInteractionResponse::new()
-    //     .content(format!("{}", sender.mention()))
+    //     .content(mention.clone())
     //     .embed(embed)).await {
     //     nay!("Failed to send message: {}", e);
     // }
@@ -217,9 +246,17 @@ pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, channel: &Channe
 
     // check that the user has a super drill if it is required
     if current_tier.required_super_drill_tier > user_file.get_super_drill_tier() {
-        // todo: update with super drill tier
-        command_response(ctx, &command, format!("You can't mine here! Obtain the Super Drill and come back later! Tier: {}", user_file.get_mine_tier())).await;
+        command_response(ctx, &command, format!("You can't mine here! You need a tier {} Super Drill (`/shop`) to mine at tier {}!",
+                                                current_tier.required_super_drill_tier, user_file.get_mine_tier())).await;
+
+        return;
+    }
 
+    // some tiers stay closed until a milestone unlocks them
+    let tier = user_file.get_mine_tier();
+    if let Some(milestone) = user_file.mine_tier_lock(tier) {
+        command_response(ctx, &command, format!("You can't mine here yet! Tier {} unlocks with the **{}** milestone ({})",
+                                                tier, milestone.name, milestone.requirement())).await;
         return;
     }
 
@@ -228,9 +265,14 @@ pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, channel: &Channe
 
     let ascension = user_file.get_ascension();
 
-    // determine the time
-    let time = if user_file.has_super_drill() && ascension != 0 {
-        ((BASE_MINE_TIME as f32) / (2.0 * ascension as f32)).min(10.0) as u64
+    // determine the time, higher drill tiers mine faster
+    let time = if let Some(drill) = user_file.get_super_drill() {
+        let time = BASE_MINE_TIME as f32 * drill.mine_time_multiplier();
+        if ascension != 0 {
+            (time / (2.0 * ascension as f32)).min(10.0) as u64
+        } else {
+            time as u64
+        }
     } else {
         BASE_MINE_TIME
     };
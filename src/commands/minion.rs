@@ -0,0 +1,105 @@
+use serenity::all::{Colour, CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption, CreateEmbed,
+                    CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage, ResolvedOption, ResolvedValue};
+use crate::{command_response, nay};
+use crate::inventory::item::InventoryItem;
+use crate::userfile::UserValues;
+
+pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, cmd: &CommandInteraction) {
+    let Some(ResolvedOption { name: "upgrade", value: ResolvedValue::SubCommand(options), .. }) = options.first() else {
+        command_response(ctx, cmd, "Me confused, try `/minion upgrade <slot>`").await;
+        return;
+    };
+
+    let mut slot = None;
+    let mut pay_with_nanners = false;
+    for option in options {
+        match option {
+            ResolvedOption { name: "slot", value: ResolvedValue::Integer(val), .. } => slot = Some(*val),
+            ResolvedOption { name: "pay_with", value: ResolvedValue::String(val), .. } => pay_with_nanners = *val == "super_nanners",
+            _ => {}
+        }
+    }
+
+    let Some(slot) = slot else {
+        command_response(ctx, cmd, "Me confused, Enter the inventory slot of the minion you want to upgrade").await;
+        return;
+    };
+
+    if slot < 1 {
+        command_response(ctx, cmd, "Invalid item slot!").await;
+        return;
+    }
+    let slot = slot as usize - 1;
+
+    let mut user_file = UserValues::get(&cmd.user.id);
+
+    let Some(InventoryItem::Minion(minion)) = user_file.get_items().get(slot).cloned() else {
+        command_response(ctx, cmd, "That isn't a minion! (see `/minions`)").await;
+        return;
+    };
+
+    if !minion.can_upgrade() {
+        command_response(ctx, cmd, "That minion is already max level!").await;
+        return;
+    }
+
+    // pay for the upgrade
+    let cost = if pay_with_nanners {
+        let cost = minion.upgrade_super_nanner_cost();
+        if user_file.get_super_nanners() < cost {
+            command_response(ctx, cmd, format!("You need {}:zap: to upgrade this minion!", cost)).await;
+            return;
+        }
+        user_file.remove_super_nanners(cost);
+        format!("{}:zap:", cost)
+    } else {
+        let cost = minion.upgrade_banana_cost();
+        if user_file.get_bananas() < cost {
+            command_response(ctx, cmd, format!("You need {}:banana: to upgrade this minion!", cost)).await;
+            return;
+        }
+        user_file.remove_bananas(cost);
+        format!("{}:banana:", cost)
+    };
+
+    user_file.upgrade_minion(slot);
+
+    let Some(InventoryItem::Minion(upgraded)) = user_file.get_items().get(slot).cloned() else {
+        return;
+    };
+    let capacity = user_file.get_perks().minion_capacity();
+
+    let embed = CreateEmbed::new()
+        .title("Minion Upgraded!")
+        .description(format!("Your {} Minion is now level {}!", upgraded.kind, upgraded.level))
+        .color(Colour::GOLD)
+        .fields(vec![
+            ("Cost", cost, true),
+            ("Production", format!("{}/h -> {}/h", minion.hourly_sludge_production(), upgraded.hourly_sludge_production()), true),
+            ("Storage", format!("{} -> {}", minion.max_sludge(capacity), upgraded.max_sludge(capacity)), true),
+        ])
+        .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"));
+
+    let builder = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+        .embed(embed)
+        .ephemeral(true));
+
+    if let Err(err) = cmd.create_response(&ctx.http, builder).await {
+        nay!("Failed to respond to command: {}", err)
+    }
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("minion")
+        .description("Manage your minions")
+        .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "upgrade", "Upgrade a minion's production and storage")
+            .add_sub_option(CreateCommandOption::new(CommandOptionType::Integer,
+                                                     "slot", "The inventory slot of the minion (see /minions)")
+                .required(true))
+            .add_sub_option(CreateCommandOption::new(CommandOptionType::String,
+                                                     "pay_with", "Pay with bananas or super nanners (default bananas)")
+                .add_string_choice("bananas", "bananas")
+                .add_string_choice("super nanners", "super_nanners")
+                .required(false)))
+        .dm_permission(true)
+}
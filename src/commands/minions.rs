@@ -0,0 +1,60 @@
+use serenity::all::{Colour, CommandInteraction, Context, CreateCommand, CreateEmbed, CreateEmbedFooter,
+                    CreateInteractionResponse, CreateInteractionResponseMessage};
+use crate::{command_response, format_duration, nay};
+use crate::inventory::item::InventoryItem;
+use crate::userfile::UserValues;
+
+pub async fn run(ctx: &Context, cmd: &CommandInteraction) {
+    let mut user_file = UserValues::get(&cmd.user.id);
+
+    let items = user_file.get_items();
+    let capacity = user_file.get_perks().minion_capacity();
+
+    // form a field for each minion, numbered by inventory slot
+    let mut fields = Vec::new();
+    for (x, item) in items.iter().enumerate() {
+        let InventoryItem::Minion(minion) = item else {
+            continue;
+        };
+
+        let sludge = minion.get_sludge_produced(capacity);
+        let max = minion.max_sludge(capacity);
+        let mut status = format!("{}/{} sludge ({}%)\n{}/h", sludge, max, sludge * 100 / max.max(1), minion.hourly_sludge_production());
+        if minion.is_full(capacity) {
+            status.push_str("\n**Full!**");
+        } else {
+            status.push_str(format!("\nFull in {}", format_duration(minion.seconds_until_full(capacity))).as_str());
+        }
+        if minion.max_super_nanners() > 0 {
+            status.push_str(format!("\n{}/{}:zap:", minion.get_super_nanners_produced(), minion.max_super_nanners()).as_str());
+        }
+
+        fields.push((format!("{}: {}", x + 1, item), status, true));
+    }
+
+    if fields.is_empty() {
+        command_response(ctx, cmd, "You don't have any minions! Buy some in the `/shop`").await;
+        return;
+    }
+
+    let embed = CreateEmbed::new()
+        .title("Your Minions")
+        .description("Collect their sludge with `/collect_minions`\nUpgrade them with `/minion upgrade <slot>`")
+        .color(Colour::GOLD)
+        .fields(fields)
+        .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"));
+
+    let builder = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+        .embed(embed)
+        .ephemeral(true));
+
+    if let Err(err) = cmd.create_response(&ctx.http, builder).await {
+        nay!("Failed to respond to command: {}", err)
+    }
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("minions")
+        .description("View your minions and how full they are")
+        .dm_permission(true)
+}
@@ -1,16 +1,40 @@
 pub mod help;
 pub mod banana;
 pub mod blackjack_cmd;
+pub mod blackjack_table;
 pub mod slots;
 pub mod fiftyfifty;
 pub mod mine;
 pub mod admin;
+pub mod admin_rake;
+pub mod admin_lottery;
+pub mod admin_quests;
+pub mod admin_season;
+pub mod admin_economy;
 pub mod inventory_cmd;
 pub mod shop;
 pub mod buy;
+pub mod claim;
+pub mod achievements_cmd;
+pub mod quests_cmd;
+pub mod perks_cmd;
+pub mod season_cmd;
+pub mod clan_cmd;
 pub mod discard;
 pub mod collect_minions;
 pub mod pvp_command;
 pub mod equip;
 pub mod unequip;
 pub mod join;
+pub mod minions;
+pub mod minion;
+pub mod notifications_cmd;
+pub mod expedition;
+pub mod raid;
+pub mod roulette;
+pub mod crash;
+pub mod duel;
+pub mod lottery;
+pub mod monkey_race;
+pub mod fair;
+pub mod verify;
@@ -0,0 +1,241 @@
+use std::sync::Arc;
+use std::sync::atomic::Ordering::SeqCst;
+use serenity::all::{ChannelId, CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption, CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, EditMessage, Http, Mentionable, ResolvedOption, ResolvedValue, Timestamp, UserId};
+use crate::{command_response, GAMES, MINING, nay, say, SUPERBOOST_MODE};
+use crate::games::{GameCode, GameHandler, Games, GamesManager};
+use crate::games::monkey_race::{payout, simulate, MonkeyRace, MONKEYS_PER_RACE};
+use crate::games::rng::GameRng;
+use crate::bets::parse_bet_amount;
+use crate::userfile::UserValues;
+
+const BETTING_WINDOW_SECS: i64 = 45;
+// how long each frame of the race stays up, message edits are rate limited
+const FRAME_MILLIS: u64 = 1500;
+
+// everyone in the channel bets on the same race
+fn open_race(games: &GamesManager, channel: ChannelId) -> Option<GameCode> {
+    games.games.iter()
+        .find(|(_, g)| matches!(&g.game, Games::MonkeyRace(r) if r.channel == channel))
+        .map(|(code, _)| *code)
+}
+
+// runs the race once betting has closed, everyone is paid before the animation so nothing is lost if it is cut short
+pub async fn start_race(http: Arc<Http>, code: GameCode) {
+    tokio::time::sleep(tokio::time::Duration::from_secs(BETTING_WINDOW_SECS as u64)).await;
+
+    let (channel, frames) = {
+        let mut lock = GAMES.lock().await;
+        let Some(game) = lock.get_game(code) else {
+            return;
+        };
+        let Games::MonkeyRace(race) = &game.game else {
+            return;
+        };
+
+        let result = simulate(&race.monkeys, &mut game.rng);
+        let superboost = SUPERBOOST_MODE.load(SeqCst);
+
+        let mut results = Vec::new();
+        for bet in &race.bets {
+            if bet.monkey == result.winner {
+                let returned = payout(bet.stake, race.odds[bet.monkey], superboost);
+                UserValues::get(&bet.user).add_bananas(returned);
+                results.push(format!("{} won {}:banana: on {}", bet.user.mention(), returned - bet.stake, race.monkeys[bet.monkey].name));
+            } else {
+                results.push(format!("{} lost {}:banana: on {}", bet.user.mention(), bet.stake, race.monkeys[bet.monkey].name));
+            }
+        }
+        say!("Monkey race {} won by {} at {:.2}x with {} bet(s)", code, race.monkeys[result.winner].name, race.odds[result.winner], race.bets.len());
+
+        // every frame is drawn up front so the race can be animated without holding the games lock
+        let mut frames = vec![race.frame_embed(&vec![0; race.monkeys.len()]).description("And they're off!")];
+        let last = result.frames.len() - 1;
+        for (i, positions) in result.frames.iter().enumerate() {
+            frames.push(if i == last {
+                race.result_embed(positions, result.winner, &results)
+            } else {
+                race.frame_embed(positions)
+            });
+        }
+
+        let channel = race.channel;
+        lock.end_game(code);
+        (channel, frames)
+    };
+
+    let mut frames = frames.into_iter();
+    let Some(start) = frames.next() else {
+        return;
+    };
+    let mut message = match channel.send_message(&http, CreateMessage::new().embed(start)).await {
+        Ok(message) => message,
+        Err(e) => {
+            nay!("Failed to send monkey race: {}", e);
+            return;
+        }
+    };
+
+    for embed in frames {
+        tokio::time::sleep(tokio::time::Duration::from_millis(FRAME_MILLIS)).await;
+        if let Err(e) = message.edit(&http, EditMessage::new().embed(embed)).await {
+            nay!("Failed to update monkey race: {}", e);
+        }
+    }
+}
+
+pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &CommandInteraction, channel: &ChannelId, user: &UserId) {
+    let mut monkey = None;
+    let mut raw_amt = None;
+
+    for option in options {
+        match option {
+            ResolvedOption { name: "monkey", value: ResolvedValue::Integer(val), .. } => monkey = Some(*val),
+            ResolvedOption { name: "amount", value: ResolvedValue::String(val), .. } => raw_amt = Some(val.to_lowercase()),
+            _ => {}
+        }
+    }
+
+    // without a bet the open race is shown, or a new one is lined up for others to bet on
+    let bet = match (monkey, raw_amt) {
+        (None, None) => None,
+        (Some(monkey), Some(raw_amt)) => Some((monkey, raw_amt)),
+        _ => {
+            command_response(ctx, command, "Me confused, You must pick a monkey and a number of bananas").await;
+            return;
+        }
+    };
+
+    if MINING.lock().await.contains(user) {
+        command_response(ctx, command, "You are currently mining!").await;
+        return;
+    }
+
+    let mut userfile = UserValues::get(user);
+
+    let bet = match bet {
+        None => None,
+        Some((monkey, raw_amt)) => {
+            if !(1..=MONKEYS_PER_RACE as i64).contains(&monkey) {
+                command_response(ctx, command, format!("Pick a monkey from 1 to {}", MONKEYS_PER_RACE)).await;
+                return;
+            }
+
+            let amt = match parse_bet_amount(&mut userfile, &raw_amt) {
+                Ok(amt) => amt,
+                Err(e) => {
+                    command_response(ctx, command, e).await;
+                    return;
+                }
+            };
+            Some((monkey as usize - 1, amt))
+        }
+    };
+
+    // lining up a race simulates its odds, which is too slow to do while every game waits on the lock
+    let is_open = open_race(&*GAMES.lock().await, *channel).is_some();
+    let prepared = if !is_open {
+        let channel = *channel;
+        let closes_at = Timestamp::now().unix_timestamp() + BETTING_WINDOW_SECS;
+        match tokio::task::spawn_blocking(move || {
+            let mut rng = GameRng::new();
+            (MonkeyRace::new(channel, closes_at, &mut rng), rng)
+        }).await {
+            Ok(prepared) => Some(prepared),
+            Err(e) => {
+                nay!("Failed to line up a monkey race: {}", e);
+                command_response(ctx, command, "The monkeys won't line up, try again!").await;
+                return;
+            }
+        }
+    } else {
+        None
+    };
+    // the bot hosts races so lining one up without betting doesn't count as being in a game
+    let bot = ctx.cache.current_user().id;
+
+    let (embed, new_race) = {
+        let mut lock = GAMES.lock().await;
+
+        let open = open_race(&lock, *channel);
+
+        let current = lock.get_player_game(user);
+        if current.is_some() && current != open {
+            drop(lock);
+            command_response(ctx, command, "You are already in a game!").await;
+            return;
+        }
+
+        // the race that was open a moment ago has finished since
+        if open.is_none() && prepared.is_none() {
+            drop(lock);
+            command_response(ctx, command, "That race just finished, try again!").await;
+            return;
+        }
+
+        if let Some((_, amt)) = bet {
+            userfile.remove_bananas(amt);
+        }
+
+        match open {
+            Some(code) => {
+                let game = lock.get_game(code).unwrap();
+                if bet.is_some() && game.host != *user && !game.has_player(user) {
+                    game.add_player(*user);
+                }
+                let Games::MonkeyRace(race) = &mut game.game else {
+                    return;
+                };
+                let message = match bet {
+                    Some((monkey, amt)) => {
+                        race.place_bet(*user, monkey, amt);
+                        format!("{} bets {}:banana: on {}", user.mention(), amt, race.monkeys[monkey].name)
+                    }
+                    None => "Place your bets with `/monkey_race`!".to_string(),
+                };
+                (race.craft_embed(message), None)
+            }
+            None => {
+                let Some((mut race, rng)) = prepared else {
+                    return;
+                };
+                let mut message = "The monkeys are lining up, place your bets with `/monkey_race`!".to_string();
+                if let Some((monkey, amt)) = bet {
+                    race.place_bet(*user, monkey, amt);
+                    message.push_str(&format!("\n{} bets {}:banana: on {}", user.mention(), amt, race.monkeys[monkey].name));
+                }
+                let embed = race.craft_embed(message);
+                let mut game = GameHandler::new(bot, Games::MonkeyRace(race), rng);
+                if bet.is_some() {
+                    game.add_player(*user);
+                }
+                let code = lock.insert(game);
+                (embed, Some(code))
+            }
+        }
+    };
+
+    if let Some(code) = new_race {
+        tokio::spawn(start_race(ctx.http.clone(), code));
+    }
+
+    let msg = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+        .embed(embed));
+
+    if let Err(e) = command.create_response(&ctx.http, msg).await {
+        nay!("Failed to send monkey race message: {}", e);
+    }
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("monkey_race")
+        .description("Bet on a monkey race, everyone in the channel bets on the same race")
+        .add_option(CreateCommandOption::new(CommandOptionType::Integer, "monkey",
+                                             "The number of the monkey to back, leave out to see the runners")
+            .min_int_value(1)
+            .max_int_value(MONKEYS_PER_RACE as u64)
+            .required(false))
+        .add_option(CreateCommandOption::new(CommandOptionType::String, "amount",
+                                             "The amount of bananas you would like to bet")
+            .required(false))
+        .dm_permission(false)
+}
@@ -0,0 +1,77 @@
+use serenity::all::{Colour, CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption, CreateEmbed,
+                    CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage, GuildId, ResolvedOption, ResolvedValue};
+use crate::{command_response, nay};
+use crate::notifications::{NotificationEvent, NotifyMode};
+use crate::userfile::UserValues;
+
+pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, cmd: &CommandInteraction, guild: &GuildId) {
+    let mut user_file = UserValues::get(&cmd.user.id);
+
+    let mut event = None;
+    let mut mode = None;
+    for option in options {
+        match option {
+            ResolvedOption { name: "event", value: ResolvedValue::String(val), .. } => event = Some(*val),
+            ResolvedOption { name: "mode", value: ResolvedValue::String(val), .. } => mode = NotifyMode::from_name(val),
+            _ => {}
+        }
+    }
+
+    // change a setting if one was given
+    if let Some(mode) = mode {
+        let events = match event {
+            Some("all") | None => NotificationEvent::values(),
+            Some(name) => {
+                let Some(event) = NotificationEvent::from_name(name) else {
+                    command_response(ctx, cmd, "Me confused, that isn't a notification!").await;
+                    return;
+                };
+                vec![event]
+            }
+        };
+
+        for event in events {
+            user_file.set_notification_mode(event, mode, cmd.channel_id.get(), guild.get());
+        }
+    }
+
+    let settings = user_file.get_notification_settings();
+
+    let fields = NotificationEvent::values().into_iter()
+        .map(|event| (event.to_string(), settings.mode(event).to_string(), true))
+        .collect::<Vec<_>>();
+
+    let embed = CreateEmbed::new()
+        .title("Notifications")
+        .description("Choose how George tells you about things!\nChannel pings for events outside of a channel go to the channel you last used `/notifications` in.")
+        .color(Colour::GOLD)
+        .fields(fields)
+        .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"));
+
+    let builder = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+        .embed(embed)
+        .ephemeral(true));
+
+    if let Err(err) = cmd.create_response(&ctx.http, builder).await {
+        nay!("Failed to respond to command: {}", err)
+    }
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("notifications")
+        .description("View or change how you get notified")
+        .add_option(CreateCommandOption::new(CommandOptionType::String, "event", "The event to change (default all)")
+            .add_string_choice("all", "all")
+            .add_string_choice("minions full", "minions_full")
+            .add_string_choice("mining complete", "mine_complete")
+            .add_string_choice("crate spawned", "crate_spawn")
+            .add_string_choice("pvp challenge", "pvp_challenge")
+            .add_string_choice("clan invite", "clan_invite")
+            .required(false))
+        .add_option(CreateCommandOption::new(CommandOptionType::String, "mode", "How you want to be notified")
+            .add_string_choice("dm", "dm")
+            .add_string_choice("channel ping", "channel")
+            .add_string_choice("off", "off")
+            .required(false))
+        .dm_permission(false)
+}
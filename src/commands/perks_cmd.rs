@@ -0,0 +1,82 @@
+use serenity::all::{Colour, CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedFooter,
+                    CreateInteractionResponse, CreateInteractionResponseMessage, ResolvedOption, ResolvedValue, Timestamp, UserId};
+use crate::{command_response, nay};
+use crate::perks::{Perk, MAX_RANK, POINTS_PER_ASCENSION};
+use crate::userfile::UserValues;
+
+pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &CommandInteraction, user: &UserId) {
+    let mut perk = None;
+    let mut points = 1;
+
+    for option in options {
+        match option {
+            ResolvedOption { name: "perk", value: ResolvedValue::String(val), .. } => perk = Perk::from_name(val),
+            ResolvedOption { name: "points", value: ResolvedValue::Integer(val), .. } => points = *val,
+            _ => {}
+        }
+    }
+
+    let mut userfile = UserValues::get(user);
+
+    // spend points if a perk was picked, otherwise just show the tree
+    let message = match perk {
+        Some(perk) => {
+            if !(1..=MAX_RANK as i64).contains(&points) {
+                command_response(ctx, command, format!("You can spend between 1 and {} points at a time", MAX_RANK)).await;
+                return;
+            }
+            match userfile.allocate_perk(perk, points as u8) {
+                Ok(rank) => format!("{} is now rank {}!", perk, rank),
+                Err(e) => {
+                    command_response(ctx, command, e).await;
+                    return;
+                }
+            }
+        }
+        None => format!("Every ascension earns {} perk points, spend them with `/perks perk points`", POINTS_PER_ASCENSION),
+    };
+
+    let tree = userfile.get_perks();
+    let ascension = userfile.get_ascension();
+
+    let fields = Perk::values().into_iter().map(|perk| {
+        let rank = tree.rank(perk);
+        (format!("{} ({}/{})", perk, rank, MAX_RANK),
+         format!("`{}{}`\n{} per rank", "■".repeat(rank as usize), "□".repeat((MAX_RANK - rank) as usize), perk.description()),
+         true)
+    }).collect::<Vec<(String, String, bool)>>();
+
+    let embed = CreateEmbed::new()
+        .title("Perk Tree")
+        .description(message)
+        .color(Colour::PURPLE)
+        .field("Perk Points", format!("{} available", tree.available(ascension)), false)
+        .fields(fields)
+        .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"))
+        .timestamp(Timestamp::now());
+
+    let msg = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+        .embed(embed)
+        .ephemeral(true));
+
+    if let Err(e) = command.create_response(&ctx.http, msg).await {
+        nay!("Failed to respond to command: {}", e);
+    }
+}
+
+pub fn register() -> CreateCommand {
+    let mut perk = CreateCommandOption::new(CommandOptionType::String, "perk", "The perk to spend points on")
+        .required(false);
+    for (name, value) in [("Message Gains", "message_gains"), ("Voice Gains", "voice_gains"), ("Minion Capacity", "minion_capacity"),
+                          ("Crate Luck", "crate_luck"), ("Combat Health", "combat_health"), ("Item Find", "item_find")] {
+        perk = perk.add_string_choice(name, value);
+    }
+
+    CreateCommand::new("perks")
+        .description("View your perk tree and spend the points you earn by ascending")
+        .add_option(perk)
+        .add_option(CreateCommandOption::new(CommandOptionType::Integer, "points",
+                                             "How many points to spend (default 1)")
+            .required(false))
+        .dm_permission(true)
+}
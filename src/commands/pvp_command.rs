@@ -1,7 +1,9 @@
-use serenity::all::{Colour, CommandInteraction, CommandOptionType, Context, CreateAttachment, CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage, ResolvedOption, ResolvedValue, UserId};
-use crate::{command_response, nay, GAMES};
+use serenity::all::{Colour, CommandInteraction, CommandOptionType, Context, CreateAttachment, CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage, Mentionable, ResolvedOption, ResolvedValue, UserId};
+use crate::{command_response, nay, notifications, GAMES};
 use crate::games::{GameHandler, Games};
 use crate::games::pvp::{PvPArena, PvPModFlag};
+use crate::games::rng::GameRng;
+use crate::notifications::NotificationEvent;
 use crate::userfile::UserValues;
 
 pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context,
@@ -14,6 +16,8 @@ pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context,
     let mut base_health = 100;
     let mut max_health = 100;
     let mut no_items = false;
+    let mut clan_war = false;
+    let mut challenged = None;
 
     for optn in options {
         match optn {
@@ -26,10 +30,14 @@ pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context,
                     _ => {}
                 }
             },
+            ResolvedOption { name: "challenge", value: ResolvedValue::User(target, ..), .. } => {
+                challenged = Some(target.id);
+            },
             ResolvedOption { name, value: ResolvedValue::Boolean(val), .. } => {
                 match *name {
                     "public" => public = *val,
                     "no_items" => no_items = *val,
+                    "clan_war" => clan_war = *val,
                     _ => {}
                 }
             },
@@ -59,6 +67,13 @@ pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context,
     if no_items {
         flags.push(PvPModFlag::NoItem);
     }
+    if clan_war {
+        if userfile.get_clan().is_none() {
+            command_response(ctx, command, "You need to be in a clan to start a clan war! (`/clan`)").await;
+            return;
+        }
+        flags.push(PvPModFlag::ClanWar);
+    }
 
     // ensure the user is not already in a game
     if GAMES.lock().await.get_player_game(user).is_some() {
@@ -73,7 +88,7 @@ pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context,
     let game = PvPArena::new(user.clone(), stake, flags);
 
     // add user to the game handler
-    let code = GAMES.lock().await.insert(GameHandler::new(user.clone(), Games::PvP(game)));
+    let code = GAMES.lock().await.insert(GameHandler::new(user.clone(), Games::PvP(game), GameRng::new()));
 
     // display the embed
     let embed = CreateEmbed::new()
@@ -88,6 +103,7 @@ pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context,
             ("Base Health", format!("{}", base_health), true),
             ("Max Health", format!("{}", max_health), true),
             ("No Items", format!("{}", no_items), true),
+            ("Clan War", format!("{}", clan_war), true),
         ])
         .footer(CreateEmbedFooter::new("Type `start` to start, `end` to cancel the game, `list` to see players, `leave` to leave, or `kick` to kick a player"));
 
@@ -101,6 +117,13 @@ pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context,
         command_response(ctx, command, "Failed to respond to command: {}").await;
         nay!("Failed to send pvp message: {}", e);
     }
+
+    // let the challenged user know about the arena
+    if let Some(challenged) = challenged {
+        notifications::notify(challenged, NotificationEvent::PvPChallenge,
+                              format!("{} has challenged you to a PvP arena for {}:banana:! Join with `/join {}`", user.mention(), stake, code),
+                              Some(command.channel_id)).await;
+    }
 }
 
 pub fn register() -> CreateCommand {
@@ -118,5 +141,9 @@ pub fn register() -> CreateCommand {
                                              "base_health", "Starting health of all players (default 100)").required(false))
         .add_option(CreateCommandOption::new(CommandOptionType::Integer,
                                              "max_health", "The maximum health you can heal to (default 100)").required(false))
+        .add_option(CreateCommandOption::new(CommandOptionType::User,
+                                             "challenge", "A user to challenge to the arena").required(false))
+        .add_option(CreateCommandOption::new(CommandOptionType::Boolean,
+                                             "clan_war", "Fight for your clan, the pot goes to the winning clan's bank (default: false)").required(false))
         .dm_permission(true)
 }
\ No newline at end of file
@@ -0,0 +1,75 @@
+use serenity::all::{Colour, CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedFooter,
+                    CreateInteractionResponse, CreateInteractionResponseMessage, GuildId, ResolvedOption, ResolvedValue, Timestamp, UserId};
+use crate::{command_response, nay};
+use crate::userfile::UserValues;
+
+pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &CommandInteraction, guild: &GuildId, user: &UserId) {
+    let claim = matches!(options.first(), Some(ResolvedOption { name: "claim", value: ResolvedValue::Boolean(true), .. }));
+
+    let mut userfile = UserValues::get(user);
+    let now = Timestamp::now().unix_timestamp();
+    let board = userfile.get_quests(guild, now);
+
+    if board.quests.is_empty() {
+        command_response(ctx, command, "There are no quests on offer in this server").await;
+        return;
+    }
+
+    let mut message = "Complete quests for bananas, the board refreshes every day".to_string();
+    let board = if claim {
+        let (bananas, nanners, claimed) = userfile.claim_quests(now);
+        if claimed == 0 {
+            command_response(ctx, command, "You haven't finished any quests to claim!").await;
+            return;
+        }
+        message = if nanners > 0 {
+            format!("You claimed {} quest(s) for {}:banana: and {}:zap:!", claimed, bananas, nanners)
+        } else {
+            format!("You claimed {} quest(s) for {}:banana:!", claimed, bananas)
+        };
+        userfile.get_quests(guild, now)
+    } else {
+        board
+    };
+
+    let quests = board.quests.iter().enumerate().map(|(i, quest)| {
+        let status = if quest.claimed {
+            ":white_check_mark: Claimed".to_string()
+        } else if quest.is_complete() {
+            ":tada: Complete! Claim it with `/quests claim`".to_string()
+        } else {
+            format!("{}/{}", quest.progress, quest.template.goal)
+        };
+        let mut reward = format!("{}:banana:", quest.template.bananas);
+        if quest.template.super_nanners > 0 {
+            reward.push_str(&format!(" + {}:zap:", quest.template.super_nanners));
+        }
+        format!("**{}. {}** - {}\n{}", i + 1, quest.template.description, reward, status)
+    }).collect::<Vec<String>>().join("\n\n");
+
+    let embed = CreateEmbed::new()
+        .title("Quest Board")
+        .description(message)
+        .color(Colour::DARK_GREEN)
+        .field("Today's Quests", quests, false)
+        .field("New quests", format!("<t:{}:R>", board.expires), true)
+        .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"))
+        .timestamp(Timestamp::now());
+
+    let msg = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+        .embed(embed)
+        .ephemeral(true));
+
+    if let Err(e) = command.create_response(&ctx.http, msg).await {
+        nay!("Failed to respond to command: {}", e);
+    }
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("quests")
+        .description("See today's quests and claim the ones you've finished")
+        .add_option(CreateCommandOption::new(CommandOptionType::Boolean, "claim",
+                                             "Claim the rewards for finished quests")
+            .required(false))
+        .dm_permission(false)
+}
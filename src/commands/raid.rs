@@ -0,0 +1,104 @@
+use serenity::all::{CommandInteraction, CommandOptionType, Context, CreateAttachment, CreateCommand, CreateCommandOption, CreateInteractionResponse, CreateInteractionResponseMessage, ResolvedOption, ResolvedValue, UserId};
+use crate::{command_response, GAMES, MINING, nay};
+use crate::games::{GameHandler, Games};
+use crate::games::raid::Raid;
+use crate::games::rng::GameRng;
+use crate::mine_data::Mine;
+use crate::userfile::UserValues;
+
+const DEFAULT_MAX_PLAYERS: i64 = 4;
+const MAX_RAID_PLAYERS: i64 = 10;
+
+pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &CommandInteraction, sender: &UserId) {
+    let mut tier = None;
+    let mut max_players = DEFAULT_MAX_PLAYERS;
+
+    for option in options {
+        if let ResolvedOption { name, value: ResolvedValue::Integer(val), .. } = option {
+            match *name {
+                "tier" => tier = Some(*val),
+                "max_players" => max_players = *val,
+                _ => {}
+            }
+        }
+    }
+
+    if !(2..=MAX_RAID_PLAYERS).contains(&max_players) {
+        command_response(ctx, command, format!("A raid party must have between 2 and {} players!", MAX_RAID_PLAYERS)).await;
+        return;
+    }
+
+    if MINING.lock().await.contains(sender) {
+        command_response(ctx, command, "You are currently mining!").await;
+        return;
+    }
+
+    if GAMES.lock().await.get_player_game(sender).is_some() {
+        command_response(ctx, command, "You are already in a game!").await;
+        return;
+    }
+
+    let mine = Mine::get();
+    let mut user_file = UserValues::get(sender);
+
+    let tier = match tier {
+        Some(tier) if tier < 0 || tier > u8::MAX as i64 || !mine.tiers.contains_key(&(tier as u8)) => {
+            command_response(ctx, command, "Invalid mine tier!").await;
+            return;
+        }
+        Some(tier) => tier as u8,
+        None => user_file.get_mine_tier(),
+    };
+
+    let current_tier = mine.get_tier(tier);
+
+    if current_tier.required_super_drill_tier > user_file.get_super_drill_tier() {
+        command_response(ctx, command, format!("You can't lead a raid here! You need a tier {} Super Drill (`/shop`) to raid tier {}!",
+                                               current_tier.required_super_drill_tier, tier)).await;
+        return;
+    }
+
+    // some tiers stay closed until a milestone unlocks them
+    if let Some(milestone) = user_file.mine_tier_lock(tier) {
+        command_response(ctx, command, format!("You can't lead a raid here yet! Tier {} unlocks with the **{}** milestone ({})",
+                                               tier, milestone.name, milestone.requirement())).await;
+        return;
+    }
+
+    // the toughest creature in the tier is the raid boss
+    let Some(boss) = current_tier.creatures.iter().max_by_key(|c| *c.health.end()) else {
+        command_response(ctx, command, "There is nothing to raid in this tier!").await;
+        return;
+    };
+
+    let raid = Raid::new(*sender, tier, boss.clone(), current_tier.sludge_worth, max_players as usize);
+    let thumbnail = raid.thumbnail.clone();
+
+    let code = GAMES.lock().await.insert(GameHandler::new(*sender, Games::Raid(Box::new(raid)), GameRng::new()));
+
+    let embed = {
+        let mut lock = GAMES.lock().await;
+        let Some(Games::Raid(raid)) = lock.get_game(code).map(|g| &g.game) else {
+            return;
+        };
+        raid.craft_embed(format!("**Game code: `{}`**\nOther users can join with *`/join`*. Loot is split by damage dealt!", code))
+    };
+
+    let msg = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+        .embed(embed)
+        .add_file(CreateAttachment::path(format!("./images/sludge_monsters/{}", thumbnail)).await.unwrap()));
+
+    if let Err(e) = command.create_response(&ctx.http, msg).await {
+        nay!("Failed to send raid message: {}", e);
+    }
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("raid")
+        .description("Open a raid lobby to fight a mine boss with other players")
+        .add_option(CreateCommandOption::new(CommandOptionType::Integer,
+                                             "tier", "The tier to raid (defaults to your mining tier)").required(false))
+        .add_option(CreateCommandOption::new(CommandOptionType::Integer,
+                                             "max_players", "Max players that can join (default 4)").required(false))
+        .dm_permission(false)
+}
@@ -0,0 +1,167 @@
+use std::sync::Arc;
+use std::sync::atomic::Ordering::SeqCst;
+use serenity::all::{ChannelId, CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption, CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, Http, Mentionable, ResolvedOption, ResolvedValue, Timestamp, UserId};
+use crate::{command_response, GAMES, MINING, nay, SUPERBOOST_MODE};
+use crate::games::{GameCode, GameHandler, Games};
+use crate::games::rng::GameRng;
+use crate::games::roulette::{Roulette, RouletteBet, Wheel};
+use crate::bets::parse_bet_amount;
+use crate::userfile::UserValues;
+
+const BETTING_WINDOW_SECS: i64 = 30;
+
+// spins the wheel once betting has closed and pays everyone out
+pub async fn spin_wheel(http: Arc<Http>, code: GameCode) {
+    tokio::time::sleep(tokio::time::Duration::from_secs(BETTING_WINDOW_SECS as u64)).await;
+
+    let (channel, embed) = {
+        let mut lock = GAMES.lock().await;
+        let Some(game) = lock.get_game(code) else {
+            return;
+        };
+        let Games::Roulette(roulette) = &game.game else {
+            return;
+        };
+
+        let pocket = roulette.spin(&mut game.rng);
+        let superboost = SUPERBOOST_MODE.load(SeqCst);
+
+        let mut results = Vec::new();
+        for bet in &roulette.bets {
+            let returned = bet.returned(pocket, superboost);
+            if returned > 0 {
+                UserValues::get(&bet.user).add_bananas(returned);
+                results.push(format!("{} won {}:banana: on {}", bet.user.mention(), returned - bet.stake, bet.bet));
+            } else {
+                results.push(format!("{} lost {}:banana: on {}", bet.user.mention(), bet.stake, bet.bet));
+            }
+        }
+
+        let channel = roulette.channel;
+        let embed = roulette.result_embed(pocket, &results);
+        lock.end_game(code);
+        (channel, embed)
+    };
+
+    if let Err(e) = channel.send_message(&http, CreateMessage::new().embed(embed)).await {
+        nay!("Failed to send roulette results: {}", e);
+    }
+}
+
+pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &CommandInteraction, channel: &ChannelId, user: &UserId) {
+    let mut spec = None;
+    let mut raw_amt = None;
+    let mut wheel = Wheel::European;
+
+    for option in options {
+        match option {
+            ResolvedOption { name: "bet", value: ResolvedValue::String(val), .. } => spec = Some(*val),
+            ResolvedOption { name: "amount", value: ResolvedValue::String(val), .. } => raw_amt = Some(val.to_lowercase()),
+            ResolvedOption { name: "wheel", value: ResolvedValue::String("american"), .. } => wheel = Wheel::American,
+            _ => {}
+        }
+    }
+
+    let (Some(spec), Some(raw_amt)) = (spec, raw_amt) else {
+        command_response(ctx, command, "Me confused, You must pick a bet and a number of bananas").await;
+        return;
+    };
+
+    if MINING.lock().await.contains(user) {
+        command_response(ctx, command, "You are currently mining!").await;
+        return;
+    }
+
+    let mut userfile = UserValues::get(user);
+
+    let amt = match parse_bet_amount(&mut userfile, &raw_amt) {
+        Ok(amt) => amt,
+        Err(e) => {
+            command_response(ctx, command, e).await;
+            return;
+        }
+    };
+
+    let (embed, new_spin) = {
+        let mut lock = GAMES.lock().await;
+
+        // everyone in the channel bets on the same spin
+        let open = lock.games.iter()
+            .find(|(_, g)| matches!(&g.game, Games::Roulette(r) if r.channel == *channel))
+            .map(|(code, _)| *code);
+
+        let current = lock.get_player_game(user);
+        if current.is_some() && current != open {
+            drop(lock);
+            command_response(ctx, command, "You are already in a game!").await;
+            return;
+        }
+
+        let wheel = match open.and_then(|code| lock.get_game(code)) {
+            Some(GameHandler { game: Games::Roulette(roulette), .. }) => roulette.wheel,
+            _ => wheel,
+        };
+
+        let bet = match RouletteBet::parse(spec, wheel) {
+            Ok(bet) => bet,
+            Err(e) => {
+                drop(lock);
+                command_response(ctx, command, e).await;
+                return;
+            }
+        };
+
+        userfile.remove_bananas(amt);
+        let message = format!("{} bets {}:banana: on {}", user.mention(), amt, bet);
+
+        match open {
+            Some(code) => {
+                let game = lock.get_game(code).unwrap();
+                if game.host != *user && !game.has_player(user) {
+                    game.add_player(*user);
+                }
+                let Games::Roulette(roulette) = &mut game.game else {
+                    return;
+                };
+                roulette.place_bet(*user, bet, amt);
+                (roulette.craft_embed(message), None)
+            }
+            None => {
+                let closes_at = Timestamp::now().unix_timestamp() + BETTING_WINDOW_SECS;
+                let mut roulette = Roulette::new(wheel, *channel, closes_at);
+                roulette.place_bet(*user, bet, amt);
+                let embed = roulette.craft_embed(format!("George spins the wheel soon, place your bets!\n{}", message));
+                let code = lock.insert(GameHandler::new(*user, Games::Roulette(roulette), GameRng::new()));
+                (embed, Some(code))
+            }
+        }
+    };
+
+    if let Some(code) = new_spin {
+        tokio::spawn(spin_wheel(ctx.http.clone(), code));
+    }
+
+    let msg = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+        .embed(embed));
+
+    if let Err(e) = command.create_response(&ctx.http, msg).await {
+        nay!("Failed to send roulette message: {}", e);
+    }
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("roulette")
+        .description("Bet on a spin of the roulette wheel, everyone in the channel plays the same spin")
+        .add_option(CreateCommandOption::new(CommandOptionType::String, "bet",
+                                             "A number, 2 to 4 numbers like 17-20, red, black, odd, even, low, high, dozen1 or column1")
+            .required(true))
+        .add_option(CreateCommandOption::new(CommandOptionType::String, "amount",
+                                             "The amount of bananas you would like to bet")
+            .required(true))
+        .add_option(CreateCommandOption::new(CommandOptionType::String, "wheel",
+                                             "The wheel to use if no spin is open (default european)")
+            .add_string_choice("European (single zero)", "european")
+            .add_string_choice("American (double zero)", "american")
+            .required(false))
+        .dm_permission(false)
+}
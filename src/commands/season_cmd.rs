@@ -0,0 +1,77 @@
+use serenity::all::{Colour, CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedFooter,
+                    CreateInteractionResponse, CreateInteractionResponseMessage, GuildId, ResolvedOption, ResolvedValue, Timestamp, UserId};
+use crate::{command_response, nay};
+use crate::guildfile::GuildSettings;
+use crate::seasons::{rank, Standing, SEASON_REWARDS};
+use crate::userfile::UserValues;
+
+pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &CommandInteraction, guild: &GuildId) {
+    let seasons = GuildSettings::get(guild).get_seasons();
+    if !seasons.is_running() {
+        command_response(ctx, command, "Seasons haven't been set up here yet!").await;
+        return;
+    }
+
+    // a past season's archive if one was asked for
+    let embed = if let Some(ResolvedOption { value: ResolvedValue::Integer(number), .. }) = options.first() {
+        let Some(record) = seasons.record(*number as u32) else {
+            command_response(ctx, command, format!("There is no archive for season {}", number)).await;
+            return;
+        };
+        record.embed()
+    } else {
+        let current = seasons.participants.iter()
+            .map(|p| Standing::of(&mut UserValues::get(&UserId::new(p.user))))
+            .collect::<Vec<Standing>>();
+        let standings = rank(seasons.standings(&current));
+        let top = if standings.is_empty() {
+            "Nobody has played yet".to_string()
+        } else {
+            standings.iter().take(5).enumerate()
+                .map(|(i, s)| format!("**{}.** {}", i + 1, s.describe()))
+                .collect::<Vec<String>>()
+                .join("\n")
+        };
+        let rewards = SEASON_REWARDS.iter()
+            .map(|(bananas, nanners, badge)| format!("{}: {}:banana:, {}:zap: and a badge", badge, bananas, nanners))
+            .collect::<Vec<String>>()
+            .join("\n");
+        let reset = if seasons.reset.is_empty() {
+            "Nothing is reset".to_string()
+        } else {
+            format!("{} keep {}%", seasons.reset.iter().map(|f| f.to_string()).collect::<Vec<String>>().join(", "), seasons.keep_percent)
+        };
+        let past = if seasons.archive.is_empty() {
+            "None yet".to_string()
+        } else {
+            seasons.archive.iter().map(|r| format!("`{}`", r.number)).collect::<Vec<String>>().join(" ")
+        };
+
+        CreateEmbed::new()
+            .title(format!("Season {}", seasons.number))
+            .description(format!("Started <t:{}:d>, ends <t:{}:R>", seasons.started, seasons.ends))
+            .color(Colour::GOLD)
+            .field("Standings", top, false)
+            .field("Rewards", rewards, true)
+            .field("At the end", format!("{}\nOnly this server's standings are reset", reset), true)
+            .field("Past Seasons", format!("{}\nView one with `/season number`", past), false)
+            .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"))
+            .timestamp(Timestamp::now())
+    };
+
+    let msg = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+        .embed(embed));
+
+    if let Err(e) = command.create_response(&ctx.http, msg).await {
+        nay!("Failed to respond to command: {}", e);
+    }
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("season")
+        .description("View the current season's standings or a past season's results")
+        .add_option(CreateCommandOption::new(CommandOptionType::Integer, "number",
+                                             "The past season to view")
+            .required(false))
+        .dm_permission(false)
+}
@@ -7,15 +7,25 @@ pub async fn run(ctx: &Context, cmd: &CommandInteraction) {
     // form the item fields
     let mut user_file = UserValues::get(&cmd.user.id);
 
+    // the super drill is bought once and then upgraded
+    let drill = match user_file.get_super_drill() {
+        None => ("1: Super Drill".to_string(), "15:zap:".to_string()),
+        Some(drill) if !drill.can_upgrade() => (format!("1: Super Drill (Tier {})", drill.tier), "Max tier!".to_string()),
+        Some(drill) => {
+            let (nanners, parts) = drill.upgrade_cost();
+            (format!("1: Super Drill Upgrade (Tier {} -> {})", drill.tier, drill.tier + 1), format!("{}:zap: + {}:gear:", nanners, parts))
+        }
+    };
+
     let embed = CreateEmbed::new()
         .title("George's Shop")
-        .description("See items you can buy!\nPurchase items with `/buy #`\n:zap: = super nanners")
+        .description("See items you can buy!\nPurchase items with `/buy #`\n:zap: = super nanners\n:gear: = drill parts (dropped by creatures in the mines)")
         .color(Colour::GOLD)
         .thumbnail("attachment://shop.jpeg")
         .fields(vec![
             ("Your Super Nanners:", format!("{}:zap:", user_file.get_super_nanners()), false),
-            // todo: make super drill upgradable rather than a one time purchase (10 x tier)
-            ("1: Super Drill", if user_file.has_super_drill() { "You already own this item!" } else { "15:zap:" }.to_string(), true), // todo: super drill upgrades
+            ("Your Drill Parts:", format!("{}:gear:", user_file.get_drill_parts()), false),
+            (drill.0.as_str(), drill.1, true),
             ("2: Minion", "10:zap:".to_string(), true),
             ("3: Stick of Thwacking (Stick 5-15hp)", "5:zap:".to_string(), true),
             ("4: Excalibur (Sword 50-100hp)", "30:zap:".to_string(), true),
@@ -23,6 +33,10 @@ pub async fn run(ctx: &Context, cmd: &CommandInteraction) {
             ("6: Spell Tome (Mighty Winds 5-25hp)", "3:zap:".to_string(), true),
             ("7: Healing Potion (10hp)", "1:zap:".to_string(), true),
             ("8: Healing Potion (25hp)", "2:zap:".to_string(), true),
+            ("9: Speedy Minion (2x speed, 0.75x storage)", "15:zap:".to_string(), true),
+            ("10: Hoarder Minion (0.75x speed, 3x storage)", "15:zap:".to_string(), true),
+            ("11: Alchemist Minion (brews super nanners)", "25:zap:".to_string(), true),
+            ("12: Streak Freeze (saves a missed /daily or /weekly)", "3:zap:".to_string(), true),
         ])
         .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"));
 
@@ -1,206 +1,127 @@
-use std::cmp::PartialEq;
-use std::fmt::Display;
 use std::sync::atomic::Ordering::SeqCst;
-use rand::{Rng, thread_rng};
 use serenity::all::{CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption, CreateInteractionResponse, CreateInteractionResponseMessage, ResolvedOption, ResolvedValue, UserId};
 use serenity::builder::{CreateEmbed, CreateEmbedFooter};
 use serenity::model::Colour;
-use crate::{command_response, hey, SKEPZ_WIN_ALWAYS, SUPERBOOST, SUPERBOOST_MODE};
+use crate::{command_response, hey, say, SKEPZ_WIN_ALWAYS, SUPERBOOST, SUPERBOOST_MODE};
+use crate::guildfile::GuildSettings;
+use crate::slot_machines::{SlotMachine, SlotMachines, SpinResult, DEFAULT_MACHINE};
+use crate::bets::parse_bet_amount;
 use crate::userfile::UserValues;
 
-const WIN_PERCENTAGE: f64 = 0.1;
-
-#[derive(PartialEq, Clone, Eq)]
-enum SlotVariant {
-    Cherry,
-    Seven,
-    Lemon,
-    Orange,
-    Grapes,
-    Bell,
-    Bar,
-    Diamond,
+// superboost multiplies winnings first, then going all in adds half
+fn boost(mut winnings: u64, all_in: bool, superboost: bool) -> u64 {
+    if superboost {
+        winnings *= SUPERBOOST;
+    }
+    if all_in {
+        winnings += winnings / 2;
+    }
+    winnings
 }
 
-impl SlotVariant {
-
-    fn amount_per_reel(&self) -> u8 {
-        match self {
-            SlotVariant::Cherry => 1,
-            SlotVariant::Seven => 2,
-            SlotVariant::Lemon => 3,
-            SlotVariant::Orange => 4,
-            SlotVariant::Grapes => 5,
-            SlotVariant::Bell => 6,
-            SlotVariant::Bar => 7,
-            SlotVariant::Diamond => 8,
+// settles a spin of a machine from ./slot_machines, the bet is taken and whatever the spin pays is returned
+async fn spin_machine(ctx: &Context, command: &CommandInteraction, userfile: &mut UserValues,
+                      machine: &SlotMachine, result: SpinResult, amt: u64, all_in: bool) -> CreateEmbed {
+    let winnings = boost(result.winnings(), all_in, SUPERBOOST_MODE.load(SeqCst));
+
+    // the jackpot pool is per server, so it isn't played in dms
+    let mut jackpot = 0;
+    let mut pool = None;
+    if let (Some(guild_id), Some(_)) = (command.guild_id, &machine.jackpot) {
+        let mut guild = GuildSettings::get(&guild_id);
+        guild.add_to_jackpot(machine.jackpot_contribution(amt));
+        if result.jackpot {
+            jackpot = guild.take_jackpot();
         }
+        pool = Some(guild.get_jackpot());
     }
 
-    pub fn random() -> Self {
-        // weighted based on amount per reel
-        let mut rng = thread_rng();
-        let total = SlotVariant::values().iter().map(|v| v.amount_per_reel()).sum::<u8>();
-        let mut random = rng.gen_range(0..total) as i16;
-        for variant in SlotVariant::values() {
-            random -= variant.amount_per_reel() as i16;
-            if random < 0 {
-                return variant;
-            }
-        }
-        SlotVariant::Diamond
-    }
+    userfile.remove_bananas(amt);
+    userfile.add_bananas(winnings + jackpot);
 
-    fn values() -> Vec<Self> {
-        vec![
-            SlotVariant::Cherry,
-            SlotVariant::Seven,
-            SlotVariant::Lemon,
-            SlotVariant::Orange,
-            SlotVariant::Grapes,
-            SlotVariant::Bell,
-            SlotVariant::Bar,
-            SlotVariant::Diamond,
-        ]
+    let wins = result.wins.iter().map(|win| match win.line {
+        Some(line) => format!("Line {}: {}x {} pays {}:banana:", line, win.count, machine.emoji(&win.symbol), win.winnings),
+        None => format!("Scatter: {}x {} pays {}:banana:", win.count, machine.emoji(&win.symbol), win.winnings),
+    }).collect::<Vec<String>>();
+
+    let mut embed = CreateEmbed::new()
+        .title(format!("{} ({} for {}:banana:)", machine.name, command.user.id.to_user(&ctx.http).await.unwrap().global_name.unwrap(), amt))
+        .description(machine.render(&result.grid))
+        .color(Colour::GOLD);
+
+    if !wins.is_empty() {
+        embed = embed.field("Wins", wins.join("\n"), false);
+    }
+    if jackpot > 0 {
+        embed = embed.field("JACKPOT!", format!("You won the server jackpot of {}:banana:!", jackpot), false);
     }
 
-    // multiplier times bet if all three are the same
-    fn value(&self) -> f32 {
-        match self {
-            SlotVariant::Cherry => 10.0,
-            SlotVariant::Seven => 3.0,
-            SlotVariant::Lemon => 2.5,
-            SlotVariant::Orange => 2.0,
-            SlotVariant::Grapes => 1.75,
-            SlotVariant::Bell => 1.5,
-            SlotVariant::Bar => 1.0,
-            SlotVariant::Diamond => 0.5,
-        }
+    embed = if winnings + jackpot > amt {
+        embed.field("You Win!", format!("Balance: {}:banana:", userfile.get_bananas()), false)
+            .footer(CreateEmbedFooter::new("Me trustworthy. Odds good!"))
+    } else {
+        embed.field("You Lost!", format!("Balance: {}:banana:", userfile.get_bananas()), false)
+            .footer(CreateEmbedFooter::new("Me trustworthy. your odds good! Play again!"))
+    };
+
+    if let Some(pool) = pool {
+        embed = embed.field("Jackpot", format!("{}:banana:", pool), true);
     }
+    embed
 }
 
-impl Display for SlotVariant {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            SlotVariant::Cherry => write!(f, ":cherries:"),
-            SlotVariant::Lemon => write!(f, ":lemon:"),
-            SlotVariant::Orange => write!(f, ":tangerine:"),
-            SlotVariant::Grapes => write!(f, ":grapes:"),
-            SlotVariant::Bell => write!(f, ":bell:"),
-            SlotVariant::Bar => write!(f, ":bricks:"),
-            SlotVariant::Seven => write!(f, ":seven:"),
-            SlotVariant::Diamond => write!(f, ":diamonds:"),
+pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &CommandInteraction, user: &UserId) {
+    let mut raw_amt = None;
+    let mut machine_id = None;
+    for option in options {
+        match option {
+            ResolvedOption { name: "bet", value: ResolvedValue::String(val), .. } => raw_amt = Some(*val),
+            ResolvedOption { name: "machine", value: ResolvedValue::String(val), .. } => machine_id = Some(*val),
+            _ => {}
         }
     }
-}
 
-pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &CommandInteraction, user: &UserId) {
-    let Some(ResolvedOption { value: ResolvedValue::String(raw_amt), .. }) = options.first() else {
+    let Some(raw_amt) = raw_amt else {
         // error message
         command_response(ctx, command, "Me confused, You must bet a number of bananas").await;
         return;
     };
 
+    let machines = SlotMachines::get();
+    let Some(machine) = machines.get_machine(machine_id.unwrap_or(DEFAULT_MACHINE)) else {
+        command_response(ctx, command, "Me don't know that machine!").await;
+        return;
+    };
+    let min_bet = machine.min_bet;
+
     let mut userfile = UserValues::get(user);
 
-    let mut all_in = false;
-
-    // parse out `k` `m` and all
-    let raw_amt = raw_amt.to_lowercase();
-    let amt = if raw_amt == "all" {
-        all_in = true;
-        userfile.get_bananas()
-    } else if raw_amt == "half" {
-        userfile.get_bananas() / 2
-    } else if raw_amt.ends_with("k") {
-        raw_amt[..raw_amt.len() - 1].parse::<u64>().unwrap_or(5) * 1000
-    } else if raw_amt.ends_with("m") {
-        raw_amt[..raw_amt.len() - 1].parse::<u64>().unwrap_or(5) * 1000000
-    } else {
-        let Ok(parse) = raw_amt.parse::<u64>() else {
-            // error message
-            command_response(ctx, command, "Me confused, You must bet a number of bananas").await;
+    let amt = match parse_bet_amount(&mut userfile, raw_amt) {
+        Ok(amt) => amt,
+        Err(e) => {
+            command_response(ctx, command, e).await;
             return;
-        };
-        parse
+        }
     };
+    let all_in = amt == userfile.get_bananas();
 
-    if amt == userfile.get_bananas() {
-        all_in = true;
-    }
-
-    if amt < 100 {
-        // error message
-        command_response(ctx, command, "You must bet at least 100 bananas").await;
+    if amt < min_bet {
+        command_response(ctx, command, format!("You must bet at least {} bananas", min_bet)).await;
         return;
     }
 
-    // check if the user has enough bananas
-    if userfile.get_bananas() < amt {
-        // error message
-        command_response(ctx, command, "You too poor!").await;
-        return;
-    }
+    let fair = userfile.next_fair_round();
+    let mut rng = fair.as_ref().map(|round| round.rng()).unwrap_or_default();
+    say!("Slots spin for {} (seed {})", user, rng.seed());
 
-    let (first, second, third) = if thread_rng().gen_bool(WIN_PERCENTAGE)
-        || ((user.get() == 318884828508454912) && SKEPZ_WIN_ALWAYS.load(SeqCst)) {
+    // provably fair spins can't be rigged, rigged spins always land the machine's best line
+    let rigged = fair.is_none() && (user.get() == 318884828508454912) && SKEPZ_WIN_ALWAYS.load(SeqCst);
+    let result = if rigged { machine.evaluate(machine.best_grid(), amt) } else { machine.play(amt, &mut rng) };
+    let mut embed = spin_machine(ctx, command, &mut userfile, machine, result, amt, all_in).await;
 
-        let mut slot = SlotVariant::random();
-
-        if (user.get() == 318884828508454912) && SKEPZ_WIN_ALWAYS.load(SeqCst) {
-            slot = SlotVariant::Cherry;
-        }
-
-        (slot.clone(), slot.clone(), slot)
-    } else {
-        let mut first = SlotVariant::random();
-        let mut second = SlotVariant::random();
-        let mut third = SlotVariant::random();
-        while first == second && second == third {
-            first = SlotVariant::random();
-            second = SlotVariant::random();
-            third = SlotVariant::random();
-        }
-        (first, second, third)
-    };
-
-
-    // let first = SlotVariant::random();
-    // let second = SlotVariant::random();
-    // // third is weighted based on the first two and the win percentage
-    // let third = if first == second && thread_rng().gen_bool(WIN_PERCENTAGE) {
-    //     first.clone()
-    // } else {
-    //     SlotVariant::random()
-    // };
-
-    let result = format!("{} | {} | {}", first, second, third);
-
-    let embed = if first == second && second == third {
-        let mut winnings = (amt as f32 * first.value()) as u64;
-        if SUPERBOOST_MODE.load(SeqCst) {
-            winnings *= SUPERBOOST;
-        }
-        if all_in {
-            winnings += winnings / 2;
-        }
-        userfile.add_bananas(winnings);
-
-        CreateEmbed::new()
-            .title(format!("Slots ({} for {}:banana:)", user.to_user(&ctx.http).await.unwrap().global_name.unwrap(), amt))
-            .description(result)
-            .color(Colour::GOLD)
-            .field("You Win!", format!("Balance: {}:banana:", userfile.get_bananas()), false)
-            .footer(CreateEmbedFooter::new("Me trustworthy. Odds good!"))
-    } else {
-        userfile.remove_bananas(amt);
-        CreateEmbed::new()
-            .title(format!("Slots ({} for {}:banana:)", user.to_user(&ctx.http).await.unwrap().global_name.unwrap(), amt))
-            .description(result)
-            .color(Colour::GOLD)
-            .field("You Lost!", format!("Balance: {}:banana:", userfile.get_bananas()), false)
-            .footer(CreateEmbedFooter::new("Me trustworthy. your odds good! Play again!"))
-    };
+    if let Some(round) = fair {
+        embed = embed.field("Provably Fair", round.reveal(), false);
+    }
 
     let response = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
         .add_embed(embed));
@@ -210,10 +131,47 @@ pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, command: &Comman
     }
 }
 
+// one choice per machine in ./slot_machines, leaving it out plays the classic machine
+pub fn machine_option() -> CreateCommandOption {
+    let machines = SlotMachines::get();
+    let mut option = CreateCommandOption::new(CommandOptionType::String, "machine", "The slot machine to play");
+    for id in machines.ids() {
+        option = option.add_string_choice(machines.get_machine(&id).unwrap().name.clone(), id);
+    }
+    option
+}
+
 pub fn register() -> CreateCommand {
     CreateCommand::new("slots")
         .description("Play a game of slots")
         .add_option(CreateCommandOption::new(CommandOptionType::String, "bet",
                                              "The amount of bananas you would like to bet"))
+        .add_option(machine_option())
         .dm_permission(true)
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::rng::GameRng;
+
+    #[test]
+    fn boosts_stack() {
+        // superboost multiplies first, then going all in adds half
+        assert_eq!(boost(1000, false, true), 1000 * SUPERBOOST);
+        assert_eq!(boost(1000, true, false), 1500);
+        assert_eq!(boost(1000, true, true), 1000 * SUPERBOOST * 3 / 2);
+    }
+
+    #[test]
+    fn classic_machine_keeps_its_odds() {
+        let machine = SlotMachines::get().get_machine(DEFAULT_MACHINE).unwrap().clone();
+        let mut rng = GameRng::from_seed(1);
+        let spins = 10000;
+        let wins = (0..spins).filter(|_| machine.play(100, &mut rng).winnings() > 0).count();
+        let rate = wins as f64 / spins as f64;
+        assert!((rate - 0.1).abs() < 0.01, "win rate {}", rate);
+
+        // three cherries return the bet plus 10 times it
+        assert_eq!(machine.evaluate(machine.best_grid(), 1000).winnings(), 11000);
+    }
+}
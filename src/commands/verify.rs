@@ -0,0 +1,122 @@
+use serenity::all::{Colour, CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption, CreateEmbed,
+                    CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage, ResolvedOption, ResolvedValue};
+use crate::{command_response, nay};
+use crate::commands::{fiftyfifty, slots};
+use crate::fairness::{self, FairRound};
+use crate::games::blackjack::BlackJack;
+use crate::games::Card;
+use crate::slot_machines::{SlotMachine, SlotMachines, DEFAULT_MACHINE};
+
+// how many undealt cards to show after a blackjack deal
+const UPCOMING_CARDS: usize = 8;
+
+fn cards_to_string(cards: &[Card]) -> String {
+    cards.iter().map(|c| format!("{} ({})", c, c.display_no_suite())).collect::<Vec<String>>().join(", ")
+}
+
+// recomputes a bet from its seeds, exactly as the game played it
+fn replay(game: &str, machine: &SlotMachine, round: &FairRound) -> Option<Vec<(&'static str, String)>> {
+    let mut rng = round.rng();
+    match game {
+        "slots" => {
+            // winnings scale with the bet, only the symbols are replayed
+            let grid = machine.spin(&mut rng);
+            Some(vec![("Machine", machine.name.clone()), ("Reels", machine.render(&grid))])
+        }
+        "fiftyfifty" => {
+            let result = if fiftyfifty::flip(&mut rng) { "Win" } else { "Loss" };
+            Some(vec![("Result", result.to_string())])
+        }
+        "blackjack" => {
+            // the bet doesn't change how the deck is shuffled
+            let mut game = BlackJack::new(0, &mut rng);
+            game.deal();
+            Some(vec![
+                ("Your Hand", game.player.playing_hand().to_string()),
+                ("George's Hand", game.dealer_hand().to_string()),
+                ("Next Cards", cards_to_string(&game.upcoming_cards(UPCOMING_CARDS))),
+            ])
+        }
+        _ => None,
+    }
+}
+
+pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, cmd: &CommandInteraction) {
+    let mut game = None;
+    let mut server_seed = None;
+    let mut client_seed = None;
+    let mut nonce = None;
+    let mut machine_id = None;
+
+    for option in options {
+        match option {
+            ResolvedOption { name: "game", value: ResolvedValue::String(val), .. } => game = Some(*val),
+            ResolvedOption { name: "server_seed", value: ResolvedValue::String(val), .. } => server_seed = Some(val.trim().to_string()),
+            ResolvedOption { name: "client_seed", value: ResolvedValue::String(val), .. } => client_seed = Some(val.trim().to_string()),
+            ResolvedOption { name: "nonce", value: ResolvedValue::Integer(val), .. } => nonce = Some(*val),
+            ResolvedOption { name: "machine", value: ResolvedValue::String(val), .. } => machine_id = Some(*val),
+            _ => {}
+        }
+    }
+
+    let (Some(game), Some(server_seed), Some(client_seed), Some(nonce)) = (game, server_seed, client_seed, nonce) else {
+        command_response(ctx, cmd, "Me confused, you must give the game, both seeds and the nonce!").await;
+        return;
+    };
+
+    if nonce < 0 {
+        command_response(ctx, cmd, "The nonce can't be negative!").await;
+        return;
+    }
+
+    let round = FairRound {
+        server_seed,
+        client_seed,
+        nonce: nonce as u64,
+        next_server_seed_hash: String::new(),
+    };
+
+    let machines = SlotMachines::get();
+    let Some(machine) = machines.get_machine(machine_id.unwrap_or(DEFAULT_MACHINE)) else {
+        command_response(ctx, cmd, "Me don't know that machine!").await;
+        return;
+    };
+
+    let Some(fields) = replay(game, machine, &round) else {
+        command_response(ctx, cmd, "Me don't know that game!").await;
+        return;
+    };
+
+    let embed = CreateEmbed::new()
+        .title(format!("Verify - {}", game))
+        .description("Check the server seed hash matches the one George showed you before the bet.")
+        .color(Colour::GOLD)
+        .field("Server Seed Hash", format!("`{}`", fairness::hash_seed(&round.server_seed)), false)
+        .field("Client Seed", format!("`{}`", round.client_seed), true)
+        .field("Nonce", format!("{}", round.nonce), true)
+        .fields(fields.into_iter().map(|(name, value)| (name, value, false)))
+        .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"));
+
+    let builder = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+        .embed(embed)
+        .ephemeral(true));
+
+    if let Err(err) = cmd.create_response(&ctx.http, builder).await {
+        nay!("Failed to respond to command: {}", err)
+    }
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("verify")
+        .description("Recompute a provably fair bet from its seeds")
+        .add_option(CreateCommandOption::new(CommandOptionType::String, "game", "The game that was played")
+            .add_string_choice("slots", "slots")
+            .add_string_choice("fiftyfifty", "fiftyfifty")
+            .add_string_choice("blackjack", "blackjack")
+            .required(true))
+        .add_option(CreateCommandOption::new(CommandOptionType::String, "server_seed", "The revealed server seed").required(true))
+        .add_option(CreateCommandOption::new(CommandOptionType::String, "client_seed", "Your client seed").required(true))
+        .add_option(CreateCommandOption::new(CommandOptionType::Integer, "nonce", "The bet's nonce").required(true))
+        .add_option(slots::machine_option())
+        .dm_permission(false)
+}
@@ -0,0 +1,144 @@
+use std::fmt::Display;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::Write;
+use serde::{Deserialize, Serialize};
+use serenity::all::{GuildId, UserId};
+use crate::{hey, say};
+
+pub const MAX_LEVEL: u64 = 100;
+pub const MAX_PRESTIGE: u64 = 10;
+
+// the values admins can change on a user
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum EconomyField {
+    Bananas,
+    SuperNanners,
+    Level,
+    Prestige,
+    Ascension,
+}
+
+impl EconomyField {
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "bananas" => Some(EconomyField::Bananas),
+            "super_nanners" => Some(EconomyField::SuperNanners),
+            "level" => Some(EconomyField::Level),
+            "prestige" => Some(EconomyField::Prestige),
+            "ascension" => Some(EconomyField::Ascension),
+            _ => None,
+        }
+    }
+
+    // the range the field can be set to
+    pub fn limits(&self) -> (u64, u64) {
+        match self {
+            EconomyField::Bananas => (0, u64::MAX),
+            EconomyField::SuperNanners | EconomyField::Ascension => (0, u16::MAX as u64),
+            EconomyField::Level => (1, MAX_LEVEL),
+            EconomyField::Prestige => (1, MAX_PRESTIGE),
+        }
+    }
+}
+
+impl Display for EconomyField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EconomyField::Bananas => write!(f, "bananas"),
+            EconomyField::SuperNanners => write!(f, "super nanners"),
+            EconomyField::Level => write!(f, "level"),
+            EconomyField::Prestige => write!(f, "prestige"),
+            EconomyField::Ascension => write!(f, "ascension"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Adjustment {
+    Give,
+    Take,
+    Set,
+}
+
+// the field's new value, kept within its limits
+pub fn adjust(field: EconomyField, current: u64, adjustment: Adjustment, amount: u64) -> u64 {
+    let (min, max) = field.limits();
+    let value = match adjustment {
+        Adjustment::Give => current.saturating_add(amount),
+        Adjustment::Take => current.saturating_sub(amount),
+        Adjustment::Set => amount,
+    };
+    value.clamp(min, max)
+}
+
+// one admin action, appended to ./audit/{guild}.jsonl
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AuditEntry {
+    pub time: i64,
+    pub admin: u64,
+    pub target: Option<u64>,
+    pub action: String,
+    // denied attempts are logged too
+    pub allowed: bool,
+}
+
+impl AuditEntry {
+
+    pub fn describe(&self) -> String {
+        let target = self.target.map(|t| format!(" on <@{}>", t)).unwrap_or_default();
+        format!("<t:{}:f> <@{}>{}: {}{}", self.time, self.admin, target, self.action, if self.allowed { "" } else { " **(denied)**" })
+    }
+}
+
+fn log_path(guild: &GuildId) -> String {
+    format!("./audit/{}.jsonl", guild.get())
+}
+
+pub fn audit(guild: &GuildId, admin: &UserId, target: Option<&UserId>, action: String, allowed: bool, now: i64) {
+    say!("Audit in guild {}: {} {}{}{}", guild, admin, action, target.map(|t| format!(" on {}", t)).unwrap_or_default(),
+         if allowed { "" } else { " (denied)" });
+
+    let entry = AuditEntry { time: now, admin: admin.get(), target: target.map(|t| t.get()), action, allowed };
+    let Ok(data) = serde_json::to_string(&entry) else {
+        hey!("Failed to serialize audit entry for guild {}", guild);
+        return;
+    };
+
+    let Ok(mut file) = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_path(guild)) else {
+        hey!("Failed to open the audit log for guild {}", guild);
+        return;
+    };
+
+    if let Err(e) = writeln!(file, "{}", data) {
+        hey!("Failed to write to the audit log for guild {}: {}", guild, e);
+    }
+}
+
+// the whole log, oldest first
+pub fn read_audit(guild: &GuildId) -> Vec<AuditEntry> {
+    let Ok(data) = fs::read_to_string(log_path(guild)) else {
+        return Vec::new();
+    };
+    data.lines().filter_map(|line| serde_json::from_str(line).ok()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adjustments_stay_within_limits() {
+        assert_eq!(adjust(EconomyField::Bananas, 100, Adjustment::Take, 500), 0);
+        assert_eq!(adjust(EconomyField::Bananas, u64::MAX, Adjustment::Give, 1), u64::MAX);
+        assert_eq!(adjust(EconomyField::Level, 90, Adjustment::Give, 50), MAX_LEVEL);
+        assert_eq!(adjust(EconomyField::Level, 5, Adjustment::Take, 10), 1);
+        assert_eq!(adjust(EconomyField::Prestige, 1, Adjustment::Set, 0), 1);
+        assert_eq!(adjust(EconomyField::SuperNanners, 0, Adjustment::Set, 100_000), u16::MAX as u64);
+        assert_eq!(adjust(EconomyField::Ascension, 2, Adjustment::Set, 7), 7);
+    }
+}
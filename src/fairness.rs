@@ -0,0 +1,90 @@
+use hmac::{Hmac, Mac};
+use rand::random;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use crate::games::rng::GameRng;
+
+// a user's provably fair state, the server seed stays secret until the bet it is used for is over
+#[derive(Serialize, Deserialize, Clone)]
+pub struct FairnessSettings {
+    pub enabled: bool,
+    server_seed: String,
+    pub client_seed: String,
+    pub nonce: u64,
+}
+
+impl Default for FairnessSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            server_seed: new_server_seed(),
+            client_seed: hex::encode(random::<[u8; 8]>()),
+            nonce: 0,
+        }
+    }
+}
+
+impl FairnessSettings {
+
+    // the commitment shown to the user before they bet
+    pub fn server_seed_hash(&self) -> String {
+        hash_seed(&self.server_seed)
+    }
+
+    // uses the committed server seed for a bet and commits to a new one for the next
+    pub fn next_round(&mut self) -> FairRound {
+        let server_seed = std::mem::replace(&mut self.server_seed, new_server_seed());
+        let round = FairRound {
+            server_seed,
+            client_seed: self.client_seed.clone(),
+            nonce: self.nonce,
+            next_server_seed_hash: self.server_seed_hash(),
+        };
+        self.nonce += 1;
+        round
+    }
+}
+
+// the seeds behind a single bet, enough to recompute its outcome with /verify
+#[derive(Clone)]
+pub struct FairRound {
+    pub server_seed: String,
+    pub client_seed: String,
+    pub nonce: u64,
+    // the commitment for the user's next bet
+    pub next_server_seed_hash: String,
+}
+
+impl FairRound {
+
+    pub fn rng(&self) -> GameRng {
+        GameRng::from_seed(round_seed(&self.server_seed, &self.client_seed, self.nonce))
+    }
+
+    // shown before the result, the server seed must stay hidden
+    pub fn commitment(&self) -> String {
+        format!("Server seed hash: `{}`\nClient seed: `{}`\nNonce: `{}`", hash_seed(&self.server_seed), self.client_seed, self.nonce)
+    }
+
+    // shown once the bet is over
+    pub fn reveal(&self) -> String {
+        format!("Server seed: `{}`\nClient seed: `{}`\nNonce: `{}`\nNext server seed hash: `{}`\nCheck it with `/verify`",
+                self.server_seed, self.client_seed, self.nonce, self.next_server_seed_hash)
+    }
+}
+
+fn new_server_seed() -> String {
+    hex::encode(random::<[u8; 32]>())
+}
+
+pub fn hash_seed(server_seed: &str) -> String {
+    hex::encode(Sha256::digest(server_seed.as_bytes()))
+}
+
+// HMAC-SHA256(server seed, "client seed:nonce"), the first 8 bytes seed the game
+pub fn round_seed(server_seed: &str, client_seed: &str, nonce: u64) -> u64 {
+    let mut mac = Hmac::<Sha256>::new_from_slice(server_seed.as_bytes()).expect("hmac accepts keys of any length");
+    mac.update(format!("{}:{}", client_seed, nonce).as_bytes());
+    let bytes = mac.finalize().into_bytes();
+    u64::from_be_bytes(bytes[..8].try_into().unwrap())
+}
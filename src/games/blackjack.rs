@@ -3,9 +3,17 @@ use std::sync::atomic::Ordering::SeqCst;
 use serenity::all::{Colour, CreateEmbed, Message};
 use serenity::builder::CreateEmbedFooter;
 use crate::{SKEPZ_WIN_ALWAYS, SUPERBOOST, SUPERBOOST_MODE};
-use crate::games::{Card, CardType, Deck};
+use crate::achievements::Stat;
+use crate::fairness::FairRound;
+use crate::games::{Card, CardType};
+use crate::games::rng::GameRng;
+use crate::games::shoe::Shoe;
 use crate::userfile::UserValues;
 
+// a single player game gets a fresh shoe every hand
+const DECKS: u8 = 6;
+const PENETRATION: f32 = 0.75;
+
 fn is_10_value(card: &Card) -> bool {
     match card.card_type {
         CardType::Ten | CardType::Jack | CardType::Queen | CardType::King => true,
@@ -73,6 +81,18 @@ impl BlackjackHand {
         score
     }
 
+    // two cards that can be split
+    pub fn is_pair(&self) -> bool {
+        self.cards.len() == 2 && (card_value(&self.cards[0]) == card_value(&self.cards[1])
+            || (is_10_value(&self.cards[0]) && is_10_value(&self.cards[1])))
+    }
+
+    // an ace is still being counted as 11
+    pub fn is_soft(&self) -> bool {
+        let hard = self.cards.iter().map(|c| if c.card_type == CardType::Ace { 1 } else { card_value(c) }).sum::<u64>();
+        hard != self.score()
+    }
+
     pub fn is_blackjack(&self) -> bool {
         // if the first two cards are an Ace and a 10-value card
         self.cards.len() == 2 && self.score() == 21
@@ -112,6 +132,78 @@ impl Display for BlackjackHand {
     }
 }
 
+// bananas returned for a blackjack on the deal, the bet has already been taken
+pub fn natural_payout(bet: u64) -> u64 {
+    bet + (bet as f64 * 2.5) as u64
+}
+
+// how a finished hand settles against George's
+#[derive(Debug, PartialEq)]
+pub enum HandResult {
+    Bust,
+    Push,
+    Win,
+    Blackjack,
+    Loss,
+}
+
+impl HandResult {
+    pub fn settle(hand: &BlackjackHand, dealer: &BlackjackHand, blackjack: bool) -> Self {
+        if hand.is_bust() {
+            HandResult::Bust
+        } else if dealer.is_push(hand) {
+            HandResult::Push
+        } else if dealer.is_bust() || hand.is_winning(dealer) {
+            if blackjack { HandResult::Blackjack } else { HandResult::Win }
+        } else {
+            HandResult::Loss
+        }
+    }
+
+    // bananas returned to the player, the bet has already been taken
+    pub fn payout(&self, bet: u64, superboost: bool) -> u64 {
+        let payout = match self {
+            HandResult::Bust | HandResult::Loss => return 0,
+            HandResult::Push => return bet,
+            HandResult::Win => bet * 2,
+            HandResult::Blackjack => (bet as f32 * 2.5).round() as u64,
+        };
+        if superboost { payout * SUPERBOOST } else { payout }
+    }
+
+    // counts the hand towards blackjack achievements, a push leaves the streak alone
+    pub fn track(&self, userfile: &mut UserValues) {
+        match self {
+            HandResult::Win | HandResult::Blackjack => {
+                userfile.add_progress(Stat::BlackjackWins, 1);
+                userfile.add_progress(Stat::BlackjackStreak, 1);
+            }
+            HandResult::Bust | HandResult::Loss => userfile.set_progress(Stat::BlackjackStreak, 0),
+            HandResult::Push => {}
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum InsuranceResult {
+    // the player has blackjack, the bet is returned
+    Push,
+    // George has blackjack, the insurance covers the bet
+    Paid,
+    // George has no blackjack, half the bet is lost and the game continues
+    Lost,
+}
+
+impl InsuranceResult {
+    // bananas returned to the player and bananas taken for the insurance
+    pub fn payout(&self, bet: u64) -> (u64, u64) {
+        match self {
+            InsuranceResult::Push | InsuranceResult::Paid => (bet, 0),
+            InsuranceResult::Lost => (0, bet / 2),
+        }
+    }
+}
+
 pub struct BlackjackPlayer {
     hands: Vec<BlackjackHand>, // allow up to 3 splits (4 hands total)
     bet: u64,
@@ -132,12 +224,9 @@ impl BlackjackPlayer {
         let hand_len = self.hands.len();
         let playing_hand = self.playing_hand();
 
-        let is_2_cards = playing_hand.cards.len() == 2;
         let less_than_4_hands = hand_len < 4;
-        let is_pair = card_value(&playing_hand.cards[0]) == card_value(&playing_hand.cards[1]);
-        let is_10_value = is_10_value(&playing_hand.cards[0]) && is_10_value(&playing_hand.cards[1]);
 
-        is_2_cards && less_than_4_hands && (is_pair || is_10_value)
+        less_than_4_hands && playing_hand.is_pair()
     }
 
     pub fn split(&mut self) {
@@ -154,7 +243,7 @@ impl BlackjackPlayer {
         self.hands[self.playing_hand as usize].add_card(card);
     }
 
-    pub fn playing_hand(&mut self) -> &BlackjackHand {
+    pub fn playing_hand(&self) -> &BlackjackHand {
         &self.hands[self.playing_hand as usize]
     }
 
@@ -172,17 +261,18 @@ impl BlackjackPlayer {
 pub struct BlackJack {
     pub player: BlackjackPlayer,
     dealer: BlackjackHand,
-    deck: Deck,
+    shoe: Shoe,
     pub offered_insurance: bool,
     pub player_blackjack: bool,
     turn: u64,
     original_bet: u64,
+    // set when the deck was shuffled from provably fair seeds, revealed when the game ends
+    pub fair: Option<FairRound>,
 }
 
 impl BlackJack {
-    pub fn new(bet: u64) -> BlackJack {
-        let mut deck = Deck::new(6, false);
-        deck.shuffle();
+    pub fn new(bet: u64, rng: &mut GameRng) -> BlackJack {
+        let shoe = Shoe::new(DECKS, PENETRATION, rng);
 
         let player = BlackjackPlayer::new(bet.clone());
         let dealer = BlackjackHand::new();
@@ -190,19 +280,20 @@ impl BlackJack {
         BlackJack {
             player,
             dealer,
-            deck,
+            shoe,
             offered_insurance: false,
             player_blackjack: false,
             turn: 0,
-            original_bet: bet
+            original_bet: bet,
+            fair: None,
         }
     }
 
     pub fn deal(&mut self) {
-        self.player.hands[0].add_card(self.deck.deal());
-        self.dealer.add_card(self.deck.deal());
-        self.player.hands[0].add_card(self.deck.deal());
-        self.dealer.add_card(self.deck.deal());
+        self.player.hands[0].add_card(self.shoe.deal());
+        self.dealer.add_card(self.shoe.deal());
+        self.player.hands[0].add_card(self.shoe.deal());
+        self.dealer.add_card(self.shoe.deal());
 
         // insurance check
         //self.offered_insurance = self.dealer_card().card_type == CardType::Ace || is_10_value(&self.dealer_card());
@@ -222,10 +313,28 @@ impl BlackJack {
     }
 
     pub fn hit(&mut self) {
-        self.player.hit(self.deck.deal());
+        self.player.hit(self.shoe.deal());
         self.turn += 1;
     }
 
+    pub fn settle_insurance(&mut self) -> InsuranceResult {
+        if self.player.playing_hand().is_blackjack() {
+            InsuranceResult::Push
+        } else if self.dealer.is_blackjack() {
+            InsuranceResult::Paid
+        } else {
+            InsuranceResult::Lost
+        }
+    }
+
+    pub fn dealer_hand(&self) -> &BlackjackHand {
+        &self.dealer
+    }
+
+    pub fn upcoming_cards(&self, count: usize) -> Vec<Card> {
+        self.shoe.peek(count)
+    }
+
     pub fn dealer_card(&self) -> Card {
         self.dealer.cards[0]
     }
@@ -236,6 +345,11 @@ impl BlackJack {
         self.dealer_turn();
     }
 
+    // doubling is only allowed as the first move on a hand
+    pub fn can_double_down(&mut self) -> bool {
+        self.turn == 0 && !self.player.playing_hand().is_blackjack()
+    }
+
     pub fn double_down(&mut self, amt: u64) {
         // increase bet and end game
         self.player.double_down(amt);
@@ -267,7 +381,7 @@ impl BlackJack {
 
     pub fn dealer_turn(&mut self) {
         while self.dealer.score() < 17 {
-            self.dealer.add_card(self.deck.deal());
+            self.dealer.add_card(self.shoe.deal());
         }
     }
 
@@ -344,19 +458,29 @@ impl BlackJack {
     }
 
     pub fn end_embed(&self, msg: &Message, end_message: String, toast: String, user_values: &mut UserValues) -> CreateEmbed {
-        CreateEmbed::new()
+        let embed = CreateEmbed::new()
             .title(format!("Blackjack ({}'s Game)", msg.author.global_name.clone().unwrap()))
             .description(end_message)
             .thumbnail("attachment://monkey.png")
             .field(format!("George's hand ({})", self.dealer.score()), format!("{}", self.dealer), false)
             .field("Balance:", format!("{}:banana:", user_values.get_bananas()), false)
             .color(Colour::GOLD)
-            .footer(CreateEmbedFooter::new(toast))
+            .footer(CreateEmbedFooter::new(toast));
+
+        match &self.fair {
+            Some(round) => embed.field("Provably Fair", round.reveal(), false),
+            None => embed,
+        }
+    }
+
+    // provably fair games can't be rigged
+    fn rigged(&self, msg: &Message) -> bool {
+        self.fair.is_none() && msg.author.id.get() == 318884828508454912 && SKEPZ_WIN_ALWAYS.load(SeqCst)
     }
 
     pub fn determine_winner(&mut self, userfile: &mut UserValues, msg: &Message) -> (CreateEmbed, bool) {
         let old_score = self.player.playing_hand().score();
-        if msg.author.id.get() == 318884828508454912 && SKEPZ_WIN_ALWAYS.load(SeqCst) { // skepz wins
+        if self.rigged(msg) { // skepz wins
             let mut payout = self.player.bet * 2;
             if SUPERBOOST_MODE.load(SeqCst) {
                 payout *= SUPERBOOST;
@@ -368,61 +492,32 @@ impl BlackJack {
                                          "**NEXT HAND** I forfeit.".to_string()),
                         false);
             }
-            (self.end_embed(msg, "I forfeit.".to_string(),
-                            "Me George, me forfeit".to_string(), userfile), true)
-        } else if self.player.playing_hand().is_bust() { // PLAYER BUST
-            // add bananas to fed
-            if self.next_player_hand() {
-                return (self.craft_embed(&msg.author.global_name.clone().unwrap(),
-                                         format!("**NEXT HAND** You bust with {}. Me win! Me eat good tonight!", old_score)),
-                        false);
-            }
-            (self.end_embed(msg,
-                           format!("You bust with {}. Me win! Me eat good tonight!", old_score),
-                           "Me George the monkey, me win".to_string(), userfile), true)
-        } else if self.dealer.is_push(self.player.playing_hand()) { // PUSH
-            userfile.add_bananas(self.player.bet);
-            if self.next_player_hand() {
-                return (self.craft_embed(&msg.author.global_name.clone().unwrap(),
-                                         format!("**NEXT HAND** We tie at {}. Me no like tie. Me hungry for nanners!", self.dealer.score())),
-                        false);
-            }
-            (self.end_embed(msg,
-                           format!("We tie at {}. Me no like tie. Me hungry for nanners!", self.dealer.score()),
-                           "Please play again! I hungry for nanners!".to_string(), userfile), true)
-
-        } else if self.dealer.is_bust() || self.player.playing_hand().is_winning(&self.dealer) { // DEALER LOOSE
-            if self.player_blackjack {
-                let mut payout = (self.player.bet as f32 * 2.5).round() as u64;
-                if SUPERBOOST_MODE.load(SeqCst) {
-                    payout *= SUPERBOOST;
-                }
-                userfile.add_bananas(payout);
-            } else {
-                let mut payout = self.player.bet * 2;
-                if SUPERBOOST_MODE.load(SeqCst) {
-                    payout *= SUPERBOOST;
-                }
-                userfile.add_bananas(payout);
-            }
-            if self.next_player_hand() {
-                return (self.craft_embed(&msg.author.global_name.clone().unwrap(),
-                                         "**NEXT HAND** Me no like when you win. Now me gonna starve!".to_string()),
-                        false);
-            }
-            (self.end_embed(msg,
-                           "Me no like when you win. Now me gonna starve!".to_string(),
-                           "Please play again! I hungry for nanners!".to_string(), userfile), true)
-        } else { // DEALER WINS
-            if self.next_player_hand() {
-                return (self.craft_embed(&msg.author.global_name.clone().unwrap(),
-                                         format!("**NEXT HAND** Me win with {}! You loose! Me eat good tonight!", self.dealer.score())),
-                        false);
-            }
-            (self.end_embed(msg,
-                           format!("Me win with {}! You loose! Me eat good tonight!", self.dealer.score()),
-                           "Me George, me win".to_string(), userfile), true)
+            return (self.end_embed(msg, "I forfeit.".to_string(),
+                                   "Me George, me forfeit".to_string(), userfile), true);
+        }
+
+        let result = HandResult::settle(self.player.playing_hand(), &self.dealer, self.player_blackjack);
+        let payout = result.payout(self.player.bet, SUPERBOOST_MODE.load(SeqCst));
+        if payout > 0 {
+            userfile.add_bananas(payout);
+        }
+        result.track(userfile);
+
+        let (message, toast) = match result {
+            HandResult::Bust => (format!("You bust with {}. Me win! Me eat good tonight!", old_score),
+                                 "Me George the monkey, me win"),
+            HandResult::Push => (format!("We tie at {}. Me no like tie. Me hungry for nanners!", self.dealer.score()),
+                                 "Please play again! I hungry for nanners!"),
+            HandResult::Win | HandResult::Blackjack => ("Me no like when you win. Now me gonna starve!".to_string(),
+                                                        "Please play again! I hungry for nanners!"),
+            HandResult::Loss => (format!("Me win with {}! You loose! Me eat good tonight!", self.dealer.score()),
+                                 "Me George, me win"),
+        };
+
+        if self.next_player_hand() {
+            return (self.craft_embed(&msg.author.global_name.clone().unwrap(), format!("**NEXT HAND** {}", message)), false);
         }
+        (self.end_embed(msg, message, toast.to_string(), userfile), true)
     }
 
     // returns true if the game has ended
@@ -441,31 +536,30 @@ impl BlackJack {
                         // reply with error
                         return (self.craft_embed(&msg.author.global_name.clone().unwrap(), "You do not have enough bananas for insurance!".to_string()), false);
                     }
-                    if self.player.playing_hand().is_blackjack() {
-                        // player has blackjack, pay out insurance
-                        userfile.add_bananas(self.player.bet); // add bet back
-                        return (self.end_embed(msg,
-                                               "You go straight to point. I like that. We push.. this time".to_string(),
-                                               "Give me nanners please!".to_string(), &mut userfile), true);
+                    let result = self.settle_insurance();
+                    let (returned, lost) = result.payout(self.player.bet);
+                    if returned > 0 {
+                        userfile.add_bananas(returned);
                     }
-                    // if dealer has blackjack, pay out insurance
-                    return if self.dealer.is_blackjack() {
-                        // payout insurance and pay back bet
-                        userfile.add_bananas(self.player.bet);
-                        // reply with insurance payout
-                        (self.end_embed(msg,
-                                        "George has blackjack! You get your nanners back.".to_string(),
-                                        "Give me nanners please!".to_string(), &mut userfile), true)
-                    } else {
-                        // player looses insurance
-                        userfile.remove_bananas(self.player.bet / 2); // remove half of bet
-                        self.offered_insurance = false;
-                        (self.craft_embed(&msg.author.global_name.clone().unwrap(), "George does not have blackjack. You loose half your bet".to_string()), false)
+                    if lost > 0 {
+                        userfile.remove_bananas(lost);
                     }
+                    return match result {
+                        InsuranceResult::Push => (self.end_embed(msg,
+                                                                 "You go straight to point. I like that. We push.. this time".to_string(),
+                                                                 "Give me nanners please!".to_string(), &mut userfile), true),
+                        InsuranceResult::Paid => (self.end_embed(msg,
+                                                                 "George has blackjack! You get your nanners back.".to_string(),
+                                                                 "Give me nanners please!".to_string(), &mut userfile), true),
+                        InsuranceResult::Lost => {
+                            self.offered_insurance = false;
+                            (self.craft_embed(&msg.author.global_name.clone().unwrap(), "George does not have blackjack. You loose half your bet".to_string()), false)
+                        }
+                    };
                 }
                 "no" => {
                     if self.dealer.is_blackjack() {
-                        if msg.author.id.get() == 318884828508454912 && SKEPZ_WIN_ALWAYS.load(SeqCst) {
+                        if self.rigged(msg) {
                             let mut payout = self.player.bet * 2;
                             if SUPERBOOST_MODE.load(SeqCst) {
                                 payout *= SUPERBOOST;
@@ -476,6 +570,7 @@ impl BlackJack {
                                                   "Give me nanners please!".to_string(), &mut userfile), true);
                         }
                         // dealer has blackjack, end game
+                        HandResult::Loss.track(&mut userfile);
                         return (self.end_embed(msg,
                                                "George has blackjack! You loose!".to_string(),
                                                "Give me nanners please!".to_string(), &mut userfile), true);
@@ -517,7 +612,7 @@ impl BlackJack {
 
                 (self.craft_embed(&msg.author.global_name.clone().unwrap(), "You split! Me for sure gonna win now!".to_string()), false)
             }
-            "double" if self.turn == 0 && !self.player.playing_hand().is_blackjack() => {
+            "double" if self.can_double_down() => {
                 let mut amt = self.player.bet;
                 if let Some(next) = words.next() {
                     let Ok(a) = next.parse::<u64>() else {
@@ -551,4 +646,120 @@ impl BlackJack {
             }
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::{Deck, Suit};
+
+    // builds a game that deals the given cards in order
+    fn stacked(bet: u64, cards: &[CardType]) -> BlackJack {
+        let mut game = BlackJack::new(bet, &mut GameRng::from_seed(0));
+        game.shoe.deck = Deck { cards: cards.iter().rev().map(|c| Card::new(*c, Suit::Spades)).collect() };
+        game.deal();
+        game
+    }
+
+    #[test]
+    fn aces_counted_as_11_make_a_soft_hand() {
+        let mut hand = BlackjackHand::new();
+        hand.add_card(Card::new(CardType::Ace, Suit::Hearts));
+        hand.add_card(Card::new(CardType::Six, Suit::Hearts));
+        assert!(hand.is_soft());
+        assert_eq!(hand.score(), 17);
+
+        hand.add_card(Card::new(CardType::Ten, Suit::Hearts));
+        assert!(!hand.is_soft());
+        assert_eq!(hand.score(), 17);
+    }
+
+    #[test]
+    fn seeded_games_deal_the_same_cards() {
+        let mut first = BlackJack::new(100, &mut GameRng::from_seed(42));
+        let mut second = BlackJack::new(100, &mut GameRng::from_seed(42));
+        first.deal();
+        second.deal();
+
+        assert_eq!(first.player.playing_hand().cards, second.player.playing_hand().cards);
+        assert_eq!(first.dealer.cards, second.dealer.cards);
+    }
+
+    #[test]
+    fn split_plays_each_hand_in_turn() {
+        // player 8 8, dealer 10 7, then 3 and 10 to the first hand and 9 to the second
+        let mut game = stacked(100, &[CardType::Eight, CardType::Ten, CardType::Eight, CardType::Seven,
+            CardType::Three, CardType::Ten, CardType::Nine]);
+
+        assert!(game.player.can_split());
+        game.player.split();
+        game.hit();
+        assert_eq!(game.player.hands.len(), 2);
+        assert_eq!(game.player.playing_hand().score(), 11);
+
+        game.hit();
+        game.stand();
+        assert_eq!(game.dealer.score(), 17);
+        let first = HandResult::settle(game.player.playing_hand(), &game.dealer, game.player_blackjack);
+        assert_eq!(first, HandResult::Win);
+
+        // moving on deals the second hand its card
+        assert!(game.next_player_hand());
+        assert_eq!(game.player.playing_hand().score(), 17);
+        let second = HandResult::settle(game.player.playing_hand(), &game.dealer, game.player_blackjack);
+        assert_eq!(second, HandResult::Push);
+        assert!(!game.player.next_hand());
+    }
+
+    #[test]
+    fn split_needs_a_pair() {
+        let mut game = stacked(100, &[CardType::Eight, CardType::Ten, CardType::Nine, CardType::Seven]);
+        assert!(!game.player.can_split());
+
+        let mut game = stacked(100, &[CardType::King, CardType::Ten, CardType::Queen, CardType::Seven]);
+        assert!(game.player.can_split());
+    }
+
+    #[test]
+    fn insurance_pays_when_george_has_blackjack() {
+        let mut game = stacked(100, &[CardType::Nine, CardType::Ace, CardType::Eight, CardType::King]);
+        assert!(game.offered_insurance);
+        assert_eq!(game.settle_insurance(), InsuranceResult::Paid);
+        assert_eq!(InsuranceResult::Paid.payout(100), (100, 0));
+    }
+
+    #[test]
+    fn insurance_is_lost_without_george_blackjack() {
+        let mut game = stacked(100, &[CardType::Nine, CardType::Ace, CardType::Eight, CardType::Six]);
+        assert!(game.offered_insurance);
+        assert_eq!(game.settle_insurance(), InsuranceResult::Lost);
+        assert_eq!(InsuranceResult::Lost.payout(100), (0, 50));
+    }
+
+    #[test]
+    fn insurance_pushes_on_player_blackjack() {
+        let mut game = stacked(100, &[CardType::Ace, CardType::Ace, CardType::King, CardType::Queen]);
+        assert!(game.player_blackjack);
+        assert_eq!(game.settle_insurance(), InsuranceResult::Push);
+    }
+
+    #[test]
+    fn hand_payouts() {
+        assert_eq!(HandResult::Win.payout(100, false), 200);
+        assert_eq!(HandResult::Blackjack.payout(100, false), 250);
+        assert_eq!(HandResult::Push.payout(100, false), 100);
+        assert_eq!(HandResult::Loss.payout(100, false), 0);
+        assert_eq!(HandResult::Bust.payout(100, true), 0);
+        assert_eq!(HandResult::Win.payout(100, true), 200 * SUPERBOOST);
+        // a push just returns the bet, even when boosted
+        assert_eq!(HandResult::Push.payout(100, true), 100);
+    }
+
+    #[test]
+    fn dealer_bust_is_a_win() {
+        // player 10 8, dealer 10 6, then the dealer draws a 10
+        let mut game = stacked(100, &[CardType::Ten, CardType::Ten, CardType::Eight, CardType::Six, CardType::Ten]);
+        game.stand();
+        assert!(game.dealer.is_bust());
+        assert_eq!(HandResult::settle(game.player.playing_hand(), &game.dealer, game.player_blackjack), HandResult::Win);
+    }
+}
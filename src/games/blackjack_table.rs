@@ -0,0 +1,520 @@
+use std::sync::atomic::Ordering::SeqCst;
+use serenity::all::{Colour, CreateEmbed, CreateEmbedFooter, Mentionable, Message, Timestamp, UserId};
+use crate::SUPERBOOST_MODE;
+use crate::games::blackjack::{BlackjackHand, HandResult};
+use crate::games::rng::GameRng;
+use crate::games::shoe::Shoe;
+use crate::userfile::UserValues;
+
+pub const MAX_DECKS: u8 = 8;
+pub const MAX_SPLITS: u8 = 3;
+// after this long anyone at the table can skip the player whose turn it is
+pub const TURN_TIMEOUT_SECS: i64 = 60;
+
+#[derive(Clone)]
+pub struct TableRules {
+    pub decks: u8,
+    // how far into the shoe the cut card is placed
+    pub penetration: f32,
+    pub dealer_hits_soft_17: bool,
+    // give up half the bet instead of playing the hand, only once George has checked for blackjack
+    pub late_surrender: bool,
+    pub max_splits: u8,
+    pub min_bet: u64,
+}
+
+impl Default for TableRules {
+    fn default() -> Self {
+        Self {
+            decks: 6,
+            penetration: 0.75,
+            dealer_hits_soft_17: false,
+            late_surrender: true,
+            max_splits: MAX_SPLITS,
+            min_bet: 5,
+        }
+    }
+}
+
+impl TableRules {
+    fn describe(&self) -> String {
+        format!("{} decks, George {} on soft 17, surrender {}, up to {} splits, {}:banana: minimum",
+                self.decks,
+                if self.dealer_hits_soft_17 { "hits" } else { "stands" },
+                if self.late_surrender { "allowed" } else { "not allowed" },
+                self.max_splits,
+                self.min_bet)
+    }
+}
+
+struct TableHand {
+    hand: BlackjackHand,
+    bet: u64,
+    surrendered: bool,
+    finished: bool,
+}
+
+impl TableHand {
+    fn new(bet: u64) -> Self {
+        Self { hand: BlackjackHand::new(), bet, surrendered: false, finished: false }
+    }
+}
+
+struct Seat {
+    user: UserId,
+    // the bet for the next round, 0 sits the round out
+    bet: u64,
+    // empty when the seat isn't playing this round
+    hands: Vec<TableHand>,
+    playing_hand: usize,
+}
+
+impl Seat {
+    fn new(user: UserId) -> Self {
+        Self { user, bet: 0, hands: Vec::new(), playing_hand: 0 }
+    }
+
+    fn in_round(&self) -> bool {
+        !self.hands.is_empty()
+    }
+
+    fn current(&mut self) -> &mut TableHand {
+        &mut self.hands[self.playing_hand]
+    }
+}
+
+pub struct BlackjackTable {
+    host: UserId,
+    pub rules: TableRules,
+    shoe: Shoe,
+    seats: Vec<Seat>,
+    dealer: BlackjackHand,
+    // the seat whose turn it is, None between rounds
+    turn: Option<usize>,
+    // when the current player last acted or their turn began
+    turn_started: i64,
+    max_players: usize,
+}
+
+impl BlackjackTable {
+
+    pub fn new(host: UserId, rules: TableRules, max_players: usize, rng: &mut GameRng) -> Self {
+        Self {
+            host,
+            shoe: Shoe::new(rules.decks, rules.penetration, rng),
+            rules,
+            seats: vec![Seat::new(host)],
+            dealer: BlackjackHand::new(),
+            turn: None,
+            turn_started: 0,
+            max_players,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.turn.is_some()
+    }
+
+    // new players sit out until the next round
+    pub fn can_join(&self) -> bool {
+        self.seats.len() < self.max_players
+    }
+
+    pub fn add_player(&mut self, user: UserId) {
+        self.seats.push(Seat::new(user));
+    }
+
+    // only called between rounds or for seats sitting the round out
+    pub fn remove_player(&mut self, user: UserId) {
+        let Some(index) = self.seats.iter().position(|s| s.user == user) else {
+            return;
+        };
+        self.seats.remove(index);
+        if let Some(turn) = self.turn {
+            if index < turn {
+                self.turn = Some(turn - 1);
+            }
+        }
+        if self.host == user {
+            if let Some(seat) = self.seats.first() {
+                self.host = seat.user;
+            }
+        }
+    }
+
+    fn seat_list(&self) -> String {
+        self.seats.iter().enumerate().map(|(i, seat)| {
+            if !seat.in_round() {
+                let bet = if seat.bet > 0 { format!("betting {}:banana:", seat.bet) } else { "no bet".to_string() };
+                return format!("{} - {}", seat.user.mention(), bet);
+            }
+            let hands = seat.hands.iter().enumerate().map(|(h, hand)| {
+                let pointer = if self.turn == Some(i) && seat.playing_hand == h { "▶ " } else { "" };
+                let status = if hand.surrendered { " surrendered" } else if hand.hand.is_bust() { " bust" } else { "" };
+                format!("{}{} ({}){} - {}:banana:", pointer, hand.hand, hand.hand.score(), status, hand.bet)
+            }).collect::<Vec<String>>().join("\n");
+            format!("{}\n{}", seat.user.mention(), hands)
+        }).collect::<Vec<String>>().join("\n")
+    }
+
+    fn dealer_display(&self) -> String {
+        if self.dealer.cards.is_empty() {
+            return "Waiting for the deal".to_string();
+        }
+        if self.is_running() {
+            // the hole card stays hidden until the players are done
+            let up = self.dealer.cards[0];
+            return format!("{} {} ({})", up, up.show_hidden(), up.display_no_suite());
+        }
+        format!("{} ({})", self.dealer, self.dealer.score())
+    }
+
+    pub fn craft_embed<S: Into<String>>(&self, message: S) -> CreateEmbed {
+        let options = match self.turn {
+            Some(turn) => format!("{}'s turn: `hit`, `stand`, `double`, `split`{}\nAfter {} seconds anyone can `skip` them, the host can `skip` or `end` any time",
+                                  self.seats[turn].user.mention(),
+                                  if self.rules.late_surrender { ", `surrender`" } else { "" },
+                                  TURN_TIMEOUT_SECS),
+            None => "`bet {amount}`, `leave`, the host can `deal` or `end`".to_string(),
+        };
+
+        CreateEmbed::new()
+            .title("Blackjack Table")
+            .thumbnail("attachment://monkey.png")
+            .description(message)
+            .color(Colour::GOLD)
+            .field("George's hand", self.dealer_display(), false)
+            .field("Players", self.seat_list(), false)
+            .field("Rules", format!("{}\n{} cards left in the shoe", self.rules.describe(), self.shoe.remaining()), false)
+            .field("Options", options, false)
+            .timestamp(Timestamp::now())
+            .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"))
+    }
+
+    fn parse_bet(&self, amount: Option<&str>) -> Result<u64, String> {
+        let Some(Ok(bet)) = amount.map(|a| a.parse::<u64>()) else {
+            return Err("Me confused, type `bet {amount}` to bet for the next round".to_string());
+        };
+        if bet != 0 && bet < self.rules.min_bet {
+            return Err(format!("The minimum bet at this table is {} bananas", self.rules.min_bet));
+        }
+        Ok(bet)
+    }
+
+    fn deal(&mut self) -> (CreateEmbed, bool) {
+        let mut message = Vec::new();
+        if self.shoe.needs_reshuffle() {
+            self.shoe.reshuffle();
+            message.push("The cut card came out, George reshuffles the shoe.".to_string());
+        }
+
+        // bets are taken when the cards come out, anyone who can't cover theirs sits out
+        for seat in self.seats.iter_mut() {
+            if seat.bet == 0 {
+                continue;
+            }
+            let mut user_file = UserValues::get(&seat.user);
+            if user_file.get_bananas() < seat.bet {
+                message.push(format!("{} can't cover their bet and sits this round out.", seat.user.mention()));
+                seat.bet = 0;
+                continue;
+            }
+            user_file.remove_bananas(seat.bet);
+            seat.hands = vec![TableHand::new(seat.bet)];
+            seat.playing_hand = 0;
+        }
+
+        if self.seats.iter().all(|s| !s.in_round()) {
+            message.push("Nobody has placed a bet! Type `bet {amount}` first.".to_string());
+            return (self.craft_embed(message.join("\n")), false);
+        }
+
+        self.dealer = BlackjackHand::new();
+        for _ in 0..2 {
+            for seat in self.seats.iter_mut().filter(|s| s.in_round()) {
+                seat.hands[0].hand.add_card(self.shoe.deal());
+            }
+            self.dealer.add_card(self.shoe.deal());
+        }
+
+        // George peeks for blackjack before anyone plays
+        if self.dealer.is_blackjack() {
+            message.push("George has blackjack!".to_string());
+            return (self.settle(message), false);
+        }
+
+        for seat in self.seats.iter_mut() {
+            if let Some(hand) = seat.hands.first_mut() {
+                if hand.hand.is_blackjack() {
+                    hand.finished = true;
+                    message.push(format!("{} has blackjack!", seat.user.mention()));
+                }
+            }
+        }
+
+        self.turn = None;
+        self.advance(message)
+    }
+
+    // moves on to the next unfinished hand, George plays once every hand is done
+    fn advance(&mut self, message: Vec<String>) -> (CreateEmbed, bool) {
+        let mut turn = self.turn.unwrap_or(0);
+        while turn < self.seats.len() {
+            let seat = &mut self.seats[turn];
+            if let Some(index) = seat.hands.iter().position(|h| !h.finished) {
+                seat.playing_hand = index;
+                if self.turn != Some(turn) {
+                    self.turn_started = Timestamp::now().unix_timestamp();
+                }
+                self.turn = Some(turn);
+                return (self.craft_embed(message.join("\n")), false);
+            }
+            turn += 1;
+        }
+        self.turn = None;
+        self.dealer_turn();
+        (self.settle(message), false)
+    }
+
+    fn dealer_turn(&mut self) {
+        // George only draws if someone is still in against him
+        let live = self.seats.iter().flat_map(|s| s.hands.iter()).any(|h| !h.surrendered && !h.hand.is_bust());
+        if !live {
+            return;
+        }
+        loop {
+            let score = self.dealer.score();
+            let hits_soft_17 = score == 17 && self.dealer.is_soft() && self.rules.dealer_hits_soft_17;
+            if score >= 17 && !hits_soft_17 {
+                break;
+            }
+            self.dealer.add_card(self.shoe.deal());
+        }
+    }
+
+    fn settle(&mut self, mut message: Vec<String>) -> CreateEmbed {
+        self.turn = None;
+        let superboost = SUPERBOOST_MODE.load(SeqCst);
+        let mut results = Vec::new();
+
+        for seat in self.seats.iter_mut().filter(|s| s.in_round()) {
+            let split = seat.hands.len() > 1;
+            let mut userfile = UserValues::get(&seat.user);
+            let mut returned = 0;
+            let mut wagered = 0;
+            for hand in &seat.hands {
+                wagered += hand.bet;
+                if hand.surrendered {
+                    returned += hand.bet / 2;
+                    continue;
+                }
+                // a split hand reaching 21 in two cards isn't a natural
+                let natural = !split && hand.hand.is_blackjack();
+                let result = HandResult::settle(&hand.hand, &self.dealer, natural);
+                returned += result.payout(hand.bet, superboost);
+                result.track(&mut userfile);
+            }
+            if returned > 0 {
+                userfile.add_bananas(returned);
+            }
+
+            let outcome = if returned > wagered {
+                format!("won {}:banana:", returned - wagered)
+            } else if returned == wagered {
+                "pushed".to_string()
+            } else {
+                format!("lost {}:banana:", wagered - returned)
+            };
+            results.push(format!("{} {}", seat.user.mention(), outcome));
+        }
+
+        let embed = self.craft_embed({
+            message.push(format!("George finishes with {} ({}).", self.dealer, self.dealer.score()));
+            message.join("\n")
+        }).field("Results", results.join("\n"), false);
+
+        for seat in self.seats.iter_mut() {
+            seat.hands.clear();
+            seat.playing_hand = 0;
+        }
+        embed
+    }
+
+    // stands every unfinished hand of the current player, the host can always, anyone seated once they time out
+    fn skip_turn(&mut self, user: UserId, now: i64) -> Option<(CreateEmbed, bool)> {
+        let turn = self.turn?;
+        if !self.seats.iter().any(|s| s.user == user) {
+            return None;
+        }
+        let waited = now - self.turn_started;
+        if user != self.host && waited < TURN_TIMEOUT_SECS {
+            return Some((self.craft_embed(format!("{} still has {} seconds to play!", self.seats[turn].user.mention(), TURN_TIMEOUT_SECS - waited)), false));
+        }
+        let seat = &mut self.seats[turn];
+        for hand in seat.hands.iter_mut() {
+            hand.finished = true;
+        }
+        let message = format!("{} was skipped and stands.", seat.user.mention());
+        Some(self.advance(vec![message]))
+    }
+
+    // hands every bet in the round back, used when the host closes the table mid round
+    fn refund_round(&mut self) -> Vec<String> {
+        self.turn = None;
+        let mut refunds = Vec::new();
+        for seat in self.seats.iter_mut().filter(|s| s.in_round()) {
+            let wagered = seat.hands.iter().map(|h| h.bet).sum::<u64>();
+            UserValues::get(&seat.user).add_bananas(wagered);
+            refunds.push(format!("{} gets {}:banana: back", seat.user.mention(), wagered));
+            seat.hands.clear();
+            seat.playing_hand = 0;
+        }
+        refunds
+    }
+
+    fn handle_turn(&mut self, action: &str, user: UserId) -> Option<(CreateEmbed, bool)> {
+        let turn = self.turn?;
+        if self.seats[turn].user != user {
+            return Some((self.craft_embed(format!("Wait your turn, it's {}'s go!", self.seats[turn].user.mention())), false));
+        }
+        self.turn_started = Timestamp::now().unix_timestamp();
+
+        let max_splits = self.rules.max_splits as usize;
+        let late_surrender = self.rules.late_surrender;
+        let seat = &mut self.seats[turn];
+        let hand_count = seat.hands.len();
+        let mention = seat.user.mention();
+
+        match action {
+            "hit" => {
+                let card = self.shoe.deal();
+                let hand = seat.current();
+                hand.hand.add_card(card);
+                let score = hand.hand.score();
+                if score >= 21 {
+                    hand.finished = true;
+                    let message = if score > 21 { format!("{} busts with {}!", mention, score) } else { format!("{} has 21!", mention) };
+                    return Some(self.advance(vec![message]));
+                }
+                Some((self.craft_embed(format!("{} hits.", mention)), false))
+            }
+            "stand" => {
+                seat.current().finished = true;
+                Some(self.advance(vec![format!("{} stands.", mention)]))
+            }
+            "double" => {
+                let bet = seat.current().bet;
+                if seat.current().hand.cards.len() != 2 {
+                    return Some((self.craft_embed("You can only double on your first two cards!"), false));
+                }
+                let mut user_file = UserValues::get(&user);
+                if user_file.get_bananas() < bet {
+                    return Some((self.craft_embed("You do not have enough bananas to double down with!"), false));
+                }
+                user_file.remove_bananas(bet);
+                let card = self.shoe.deal();
+                let hand = seat.current();
+                hand.bet += bet;
+                hand.hand.add_card(card);
+                hand.finished = true;
+                let score = hand.hand.score();
+                Some(self.advance(vec![format!("{} doubles down and draws to {}.", mention, score)]))
+            }
+            "split" => {
+                if !seat.current().hand.is_pair() || hand_count > max_splits {
+                    return Some((self.craft_embed("You can't split that hand!"), false));
+                }
+                let bet = seat.current().bet;
+                let mut user_file = UserValues::get(&user);
+                if user_file.get_bananas() < bet {
+                    return Some((self.craft_embed("You do not have enough bananas to split!"), false));
+                }
+                user_file.remove_bananas(bet);
+
+                let mut new_hand = TableHand::new(bet);
+                new_hand.hand.add_card(seat.current().hand.cards.pop().unwrap());
+                new_hand.hand.add_card(self.shoe.deal());
+                let card = self.shoe.deal();
+                seat.current().hand.add_card(card);
+                let index = seat.playing_hand + 1;
+                seat.hands.insert(index, new_hand);
+                Some((self.craft_embed(format!("{} splits!", mention)), false))
+            }
+            "surrender" => {
+                if !late_surrender || hand_count > 1 || seat.current().hand.cards.len() != 2 {
+                    return Some((self.craft_embed("You can't surrender now!"), false));
+                }
+                let hand = seat.current();
+                hand.surrendered = true;
+                hand.finished = true;
+                Some(self.advance(vec![format!("{} surrenders and gets half their bet back.", mention)]))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn handle_message(&mut self, msg: &Message) -> Option<(CreateEmbed, bool, Option<UserId>)> {
+        let content = msg.content.as_str().to_lowercase();
+        let mut split = content.split_whitespace();
+        let first = split.next()?;
+        let user = msg.author.id;
+
+        match first {
+            "hit" | "stand" | "double" | "split" | "surrender" if self.is_running() => {
+                self.handle_turn(first, user).map(|(embed, end)| (embed, end, None))
+            }
+            "skip" if self.is_running() => {
+                self.skip_turn(user, Timestamp::now().unix_timestamp()).map(|(embed, end)| (embed, end, None))
+            }
+            "table" | "list" => Some((self.craft_embed("The table:"), false, None)),
+            "bet" => {
+                if self.is_running() {
+                    return Some((self.craft_embed("Wait for the round to finish to change your bet."), false, None));
+                }
+                let bet = match self.parse_bet(split.next()) {
+                    Ok(bet) => bet,
+                    Err(e) => return Some((self.craft_embed(e), false, None)),
+                };
+                let mut userfile = UserValues::get(&user);
+                if userfile.get_bananas() < bet {
+                    return Some((self.craft_embed("You too poor!"), false, None));
+                }
+                let max_bet = userfile.max_bet();
+                if bet > max_bet {
+                    return Some((self.craft_embed(format!("Your bet limit is {}:banana:, reach milestones to raise it", max_bet)), false, None));
+                }
+                let seat = self.seats.iter_mut().find(|s| s.user == user)?;
+                seat.bet = bet;
+                let message = if bet == 0 { format!("{} sits out the next round.", user.mention()) } else { format!("{} bets {}:banana:.", user.mention(), bet) };
+                Some((self.craft_embed(message), false, None))
+            }
+            "deal" if !self.is_running() => {
+                if user != self.host {
+                    return None;
+                }
+                let (embed, end) = self.deal();
+                Some((embed, end, None))
+            }
+            "leave" => {
+                if self.seats.iter().any(|s| s.user == user && s.in_round()) {
+                    return Some((self.craft_embed("Finish your hand before leaving the table!"), false, None));
+                }
+                if self.seats.len() < 2 {
+                    return Some((self.craft_embed("The last player has left, the table is closed."), true, None));
+                }
+                Some((self.craft_embed(format!("{} has left the table.", user.mention())), false, Some(user)))
+            }
+            "end" => {
+                if user != self.host {
+                    return None;
+                }
+                if self.is_running() {
+                    let refunds = self.refund_round();
+                    return Some((self.craft_embed(format!("George calls off the round and closes the table.\n{}", refunds.join("\n"))), true, None));
+                }
+                Some((self.craft_embed("George closes the table."), true, None))
+            }
+            _ => None,
+        }
+    }
+}
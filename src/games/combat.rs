@@ -0,0 +1,326 @@
+use std::fmt::{Display, Formatter};
+use std::ops::RangeInclusive;
+use rand::Rng;
+use crate::games::rng::GameRng;
+use crate::games::status_effect::StatusEffects;
+
+#[derive(Clone)]
+pub struct Stats {
+    // healing can't go past this, None leaves healing uncapped
+    pub max_health: Option<u32>,
+    // damage dealt by a basic attack without a weapon
+    pub damage: RangeInclusive<u32>,
+}
+
+// anything that can fight: players, creatures and their summons
+#[derive(Clone)]
+pub struct Combatant {
+    pub name: String,
+    pub health: u32,
+    pub stats: Stats,
+    pub effects: StatusEffects,
+}
+
+impl Combatant {
+
+    pub fn new<S: Into<String>>(name: S, health: u32, stats: Stats) -> Self {
+        Self {
+            name: name.into(),
+            health,
+            stats,
+            effects: StatusEffects::default(),
+        }
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.health > 0
+    }
+
+    // weapons replace the base damage range, effects like enrage multiply it
+    pub fn roll_damage(&self, weapon: Option<RangeInclusive<u32>>, rng: &mut GameRng) -> u32 {
+        let damage = rng.gen_range(weapon.unwrap_or(self.stats.damage.clone()));
+        (damage as f32 * self.effects.damage_multiplier()) as u32
+    }
+
+    // runs incoming damage through shields before it reaches health, returns (damage taken, damage absorbed)
+    pub fn take_damage(&mut self, damage: u32) -> (u32, u32) {
+        let through = self.effects.absorb(damage);
+        let taken = through.min(self.health);
+        self.health -= taken;
+        (taken, damage - through)
+    }
+
+    // returns how much health was actually restored
+    pub fn heal(&mut self, amount: u32) -> u32 {
+        let healed = match self.stats.max_health {
+            Some(max) => amount.min(max.saturating_sub(self.health)),
+            None => amount,
+        };
+        self.health += healed;
+        healed
+    }
+
+    // the lower your health the more likely you are to escape
+    pub fn attempt_flee(&self, rng: &mut GameRng) -> bool {
+        rng.gen_range(0..self.health.max(1)) == 0
+    }
+}
+
+pub enum Action {
+    // `with` describes the weapon used, if any
+    Attack { weapon: Option<RangeInclusive<u32>>, with: Option<String> },
+    Spell { name: String, damage: RangeInclusive<u32> },
+    Heal { amount: u32 },
+    Flee,
+    Surrender,
+}
+
+pub enum BattleEvent {
+    Attacked { attacker: String, target: String, damage: u32, with: Option<String> },
+    Absorbed { target: String, amount: u32 },
+    Healed { target: String, amount: u32, health: u32 },
+    Stunned { name: String },
+    // anything else, like abilities and status effects ticking
+    Effect(String),
+    Defeated { name: String },
+    Fled { name: String },
+    FailedFlee { name: String },
+    Surrendered { name: String },
+}
+
+// names are written mid sentence ("you", "the Sludge King") so the first letter of each line is capitalized
+fn capitalize(line: String) -> String {
+    let mut chars = line.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => line,
+    }
+}
+
+impl Display for BattleEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let line = match self {
+            BattleEvent::Attacked { attacker, target, damage, with } => match with {
+                Some(with) => format!("{} attacked {} with {} for {} damage!", attacker, target, with, damage),
+                None => format!("{} attacked {} for {} damage!", attacker, target, damage),
+            },
+            BattleEvent::Absorbed { target, amount } => format!("{}'s shield absorbed {} damage!", target, amount),
+            BattleEvent::Healed { target, amount, health } => format!("{} healed for {}hp ({}hp)", target, amount, health),
+            BattleEvent::Stunned { name } => format!("{} couldn't move while stunned!", name),
+            BattleEvent::Effect(effect) => effect.clone(),
+            BattleEvent::Defeated { name } => format!("{} fell!", name),
+            BattleEvent::Fled { name } => format!("{} fled the battle!", name),
+            BattleEvent::FailedFlee { name } => format!("{} failed to flee!", name),
+            BattleEvent::Surrendered { name } => format!("{} surrendered!", name),
+        };
+        write!(f, "{}", capitalize(line))
+    }
+}
+
+// everything that happened in a turn, rendered by each game into its own embeds
+#[derive(Default)]
+pub struct BattleLog {
+    events: Vec<BattleEvent>,
+}
+
+impl BattleLog {
+
+    pub fn push(&mut self, event: BattleEvent) {
+        self.events.push(event);
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+impl Display for BattleLog {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let lines = self.events.iter().map(|e| e.to_string()).collect::<Vec<String>>();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+// the result of an action, from the point of view of the combatant who acted
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Outcome {
+    Continue,
+    // the target was defeated
+    Victory,
+    // the actor gave up
+    Defeat,
+    Fled,
+}
+
+// ticks the actor's effects at the start of their turn, returns false if they are stunned and can't act
+pub fn start_turn(actor: &mut Combatant, log: &mut BattleLog) -> bool {
+    let stunned = actor.effects.is_stunned();
+    let (damage, lines) = actor.effects.tick();
+    actor.health = actor.health.saturating_sub(damage);
+    for line in lines {
+        log.push(BattleEvent::Effect(line));
+    }
+    if !actor.is_alive() {
+        log.push(BattleEvent::Defeated { name: actor.name.clone() });
+        return false;
+    }
+    if stunned {
+        log.push(BattleEvent::Stunned { name: actor.name.clone() });
+    }
+    !stunned
+}
+
+// heals the actor, capped at their max health
+pub fn heal(actor: &mut Combatant, amount: u32, log: &mut BattleLog) {
+    let healed = actor.heal(amount);
+    log.push(BattleEvent::Healed { target: actor.name.clone(), amount: healed, health: actor.health });
+}
+
+fn strike(attacker: &Combatant, target: &mut Combatant, damage: u32, with: Option<String>, log: &mut BattleLog) -> Outcome {
+    let (taken, absorbed) = target.take_damage(damage);
+    if absorbed > 0 {
+        log.push(BattleEvent::Absorbed { target: target.name.clone(), amount: absorbed });
+    }
+    log.push(BattleEvent::Attacked { attacker: attacker.name.clone(), target: target.name.clone(), damage: taken, with });
+
+    if target.is_alive() {
+        Outcome::Continue
+    } else {
+        log.push(BattleEvent::Defeated { name: target.name.clone() });
+        Outcome::Victory
+    }
+}
+
+// resolves one combatant's action against another and records what happened
+pub fn resolve(actor: &mut Combatant, target: &mut Combatant, action: Action, log: &mut BattleLog, rng: &mut GameRng) -> Outcome {
+    match action {
+        Action::Attack { weapon, with } => {
+            let damage = actor.roll_damage(weapon, rng);
+            strike(actor, target, damage, with, log)
+        }
+        Action::Spell { name, damage } => {
+            let damage = rng.gen_range(damage);
+            strike(actor, target, damage, Some(format!("a {} Tome", name)), log)
+        }
+        Action::Heal { amount } => {
+            heal(actor, amount, log);
+            Outcome::Continue
+        }
+        Action::Flee => {
+            if actor.attempt_flee(rng) {
+                log.push(BattleEvent::Fled { name: actor.name.clone() });
+                Outcome::Fled
+            } else {
+                log.push(BattleEvent::FailedFlee { name: actor.name.clone() });
+                Outcome::Continue
+            }
+        }
+        Action::Surrender => {
+            log.push(BattleEvent::Surrendered { name: actor.name.clone() });
+            Outcome::Defeat
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::status_effect::StatusEffect;
+
+    fn fighter(name: &str, health: u32, damage: RangeInclusive<u32>) -> Combatant {
+        Combatant::new(name, health, Stats { max_health: Some(health), damage })
+    }
+
+    fn attack() -> Action {
+        Action::Attack { weapon: None, with: None }
+    }
+
+    // fights until someone falls, returning the outcome for the first fighter and the full log
+    fn duel(seed: u64) -> (Outcome, String) {
+        let mut rng = GameRng::from_seed(seed);
+        let mut log = BattleLog::default();
+        let mut player = fighter("you", 100, 0..=25);
+        let mut monster = fighter("the sludge monster", 300, 0..=10);
+        loop {
+            if resolve(&mut player, &mut monster, attack(), &mut log, &mut rng) == Outcome::Victory {
+                return (Outcome::Victory, log.to_string());
+            }
+            if resolve(&mut monster, &mut player, attack(), &mut log, &mut rng) == Outcome::Victory {
+                return (Outcome::Defeat, log.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn seeded_battles_replay() {
+        for seed in 0..20 {
+            assert_eq!(duel(seed), duel(seed));
+        }
+    }
+
+    #[test]
+    fn fixed_damage_kills_in_order() {
+        let mut rng = GameRng::from_seed(0);
+        let mut log = BattleLog::default();
+        let mut player = fighter("you", 100, 10..=10);
+        let mut monster = fighter("the sludge", 25, 0..=0);
+
+        assert_eq!(resolve(&mut player, &mut monster, attack(), &mut log, &mut rng), Outcome::Continue);
+        assert_eq!(resolve(&mut player, &mut monster, attack(), &mut log, &mut rng), Outcome::Continue);
+        assert_eq!(monster.health, 5);
+        assert_eq!(resolve(&mut player, &mut monster, attack(), &mut log, &mut rng), Outcome::Victory);
+        assert!(!monster.is_alive());
+        assert!(log.to_string().ends_with("The sludge fell!"));
+    }
+
+    #[test]
+    fn shields_absorb_before_health() {
+        let mut rng = GameRng::from_seed(0);
+        let mut log = BattleLog::default();
+        let mut player = fighter("you", 100, 10..=10);
+        let mut monster = fighter("the sludge", 50, 0..=0);
+        monster.effects.apply(StatusEffect::Shielded { amount: 15 });
+
+        resolve(&mut player, &mut monster, attack(), &mut log, &mut rng);
+        assert_eq!(monster.health, 50);
+        resolve(&mut player, &mut monster, attack(), &mut log, &mut rng);
+        assert_eq!(monster.health, 45);
+    }
+
+    #[test]
+    fn stuns_skip_turns_and_poison_can_kill() {
+        let mut log = BattleLog::default();
+        let mut player = fighter("you", 5, 10..=10);
+        player.effects.apply(StatusEffect::Stunned { turns: 1 });
+        assert!(!start_turn(&mut player, &mut log));
+        assert!(player.is_alive());
+
+        player.effects.apply(StatusEffect::Poisoned { damage: 5, turns: 2 });
+        assert!(!start_turn(&mut player, &mut log));
+        assert!(!player.is_alive());
+    }
+
+    #[test]
+    fn fleeing_at_one_health_always_works() {
+        let mut rng = GameRng::from_seed(9);
+        let mut log = BattleLog::default();
+        let mut player = fighter("you", 1, 0..=0);
+        let mut monster = fighter("the sludge", 100, 0..=0);
+        assert_eq!(resolve(&mut player, &mut monster, Action::Flee, &mut log, &mut rng), Outcome::Fled);
+        assert_eq!(resolve(&mut player, &mut monster, Action::Surrender, &mut log, &mut rng), Outcome::Defeat);
+    }
+
+    #[test]
+    fn healing_is_capped() {
+        let mut log = BattleLog::default();
+        let mut player = fighter("you", 100, 0..=0);
+        player.health = 90;
+        heal(&mut player, 50, &mut log);
+        assert_eq!(player.health, 100);
+        assert_eq!(log.to_string(), "You healed for 10hp (100hp)");
+    }
+}
@@ -0,0 +1,186 @@
+use std::time::Instant;
+use rand::Rng;
+use serenity::all::{ChannelId, Colour, CreateEmbed, CreateEmbedFooter, Mentionable, Timestamp, UserId};
+use crate::games::rng::GameRng;
+use crate::SUPERBOOST;
+
+// how fast the multiplier climbs, e^(0.1t) doubles about every 7 seconds
+const GROWTH_PER_SEC: f64 = 0.1;
+// the share of rounds that crash instantly, this is the house edge
+const HOUSE_EDGE: f64 = 0.01;
+const MAX_CRASH_POINT: f64 = 1000.0;
+
+pub struct Rider {
+    pub user: UserId,
+    pub bet: u64,
+    pub cashed_out: Option<f64>,
+}
+
+// one round of crash, everyone who bets before it starts rides the same multiplier
+pub struct Crash {
+    pub channel: ChannelId,
+    pub riders: Vec<Rider>,
+    pub closes_at: i64,
+    crash_point: f64,
+    started_at: Option<Instant>,
+    crashed: bool,
+}
+
+impl Crash {
+
+    pub fn new(channel: ChannelId, closes_at: i64, rng: &mut GameRng) -> Self {
+        Self {
+            channel,
+            riders: Vec::new(),
+            closes_at,
+            crash_point: Self::crash_point(rng),
+            started_at: None,
+            crashed: false,
+        }
+    }
+
+    // picked before anyone bets, P(crash point >= x) = (1 - edge) / x
+    pub fn crash_point(rng: &mut GameRng) -> f64 {
+        let roll: f64 = rng.gen();
+        let point = (1.0 - HOUSE_EDGE) / (1.0 - roll);
+        (point.clamp(1.0, MAX_CRASH_POINT) * 100.0).floor() / 100.0
+    }
+
+    pub fn multiplier_at(secs: f64) -> f64 {
+        ((GROWTH_PER_SEC * secs).exp() * 100.0).floor() / 100.0
+    }
+
+    // winnings are boosted, the bet itself isn't
+    pub fn payout(bet: u64, multiplier: f64, superboost: bool) -> u64 {
+        let returned = (bet as f64 * multiplier) as u64;
+        let mut winnings = returned.saturating_sub(bet);
+        if superboost {
+            winnings *= SUPERBOOST;
+        }
+        bet + winnings
+    }
+
+    pub fn can_bet(&self) -> bool {
+        self.started_at.is_none()
+    }
+
+    pub fn has_rider(&self, user: &UserId) -> bool {
+        self.riders.iter().any(|r| r.user == *user)
+    }
+
+    pub fn add_rider(&mut self, user: UserId, bet: u64) {
+        self.riders.push(Rider { user, bet, cashed_out: None });
+    }
+
+    pub fn start(&mut self) {
+        self.started_at = Some(Instant::now());
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.started_at.is_some() && !self.crashed
+    }
+
+    pub fn current_multiplier(&self) -> f64 {
+        match self.started_at {
+            Some(start) => Self::multiplier_at(start.elapsed().as_secs_f64()).min(self.crash_point),
+            None => 1.0,
+        }
+    }
+
+    // returns true once the multiplier has reached the crash point
+    pub fn tick(&mut self) -> bool {
+        if self.started_at.is_some() && self.current_multiplier() >= self.crash_point {
+            self.crashed = true;
+        }
+        self.crashed
+    }
+
+    // returns the multiplier and bet of the rider cashing out
+    pub fn cash_out(&mut self, user: &UserId) -> Result<(f64, u64), &'static str> {
+        if !self.is_running() || self.tick() {
+            return Err("The round isn't running!");
+        }
+        let multiplier = self.current_multiplier();
+        let Some(rider) = self.riders.iter_mut().find(|r| r.user == *user) else {
+            return Err("You aren't riding this round!");
+        };
+        if rider.cashed_out.is_some() {
+            return Err("You already cashed out!");
+        }
+        rider.cashed_out = Some(multiplier);
+        Ok((multiplier, rider.bet))
+    }
+
+    fn rider_list(&self) -> String {
+        if self.riders.is_empty() {
+            return "Nobody yet".to_string();
+        }
+        self.riders.iter().map(|r| match r.cashed_out {
+            Some(multiplier) => format!("{} - {}:banana: cashed out at {:.2}x", r.user.mention(), r.bet, multiplier),
+            None if self.crashed => format!("{} - {}:banana: crashed", r.user.mention(), r.bet),
+            None => format!("{} - {}:banana:", r.user.mention(), r.bet),
+        }).collect::<Vec<String>>().join("\n")
+    }
+
+    pub fn craft_embed<S: Into<String>>(&self, message: S) -> CreateEmbed {
+        let (title, colour) = if self.crashed {
+            (format!("CRASHED at {:.2}x", self.crash_point), Colour::RED)
+        } else if self.started_at.is_some() {
+            (format!("{:.2}x", self.current_multiplier()), Colour::DARK_GREEN)
+        } else {
+            ("Crash".to_string(), Colour::GOLD)
+        };
+
+        let mut embed = CreateEmbed::new()
+            .title(title)
+            .description(message)
+            .color(colour)
+            .field("Riders", self.rider_list(), false)
+            .timestamp(Timestamp::now())
+            .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"));
+        if self.can_bet() {
+            embed = embed.field("Takes off", format!("<t:{}:R>", self.closes_at), true);
+        }
+        embed
+    }
+
+    // a line per rider for the logs
+    pub fn summary(&self) -> String {
+        let riders = self.riders.iter().map(|r| match r.cashed_out {
+            Some(multiplier) => format!("{} bet {} cashed out at {:.2}x", r.user, r.bet, multiplier),
+            None => format!("{} bet {} crashed", r.user, r.bet),
+        }).collect::<Vec<String>>().join(", ");
+        format!("crashed at {:.2}x: {}", self.crash_point, riders)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crash_points_follow_the_house_edge() {
+        let mut rng = GameRng::from_seed(9);
+        let rounds = 100000;
+        let points = (0..rounds).map(|_| Crash::crash_point(&mut rng)).collect::<Vec<f64>>();
+        assert!(points.iter().all(|p| (1.0..=MAX_CRASH_POINT).contains(p)));
+
+        // cashing out at 2x should survive about 49.5% of rounds
+        let survived = points.iter().filter(|p| **p >= 2.0).count() as f64 / rounds as f64;
+        assert!((survived - 0.495).abs() < 0.01, "survived {}", survived);
+    }
+
+    #[test]
+    fn cash_out_pays_the_multiplier() {
+        assert_eq!(Crash::payout(100, 2.5, false), 250);
+        assert_eq!(Crash::payout(100, 1.0, true), 100);
+        assert_eq!(Crash::payout(100, 2.0, true), 100 + 100 * SUPERBOOST);
+    }
+
+    #[test]
+    fn the_multiplier_climbs() {
+        assert_eq!(Crash::multiplier_at(0.0), 1.0);
+        assert!(Crash::multiplier_at(7.0) >= 2.0);
+        assert!(Crash::multiplier_at(10.0) > Crash::multiplier_at(9.0));
+    }
+}
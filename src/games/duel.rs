@@ -0,0 +1,193 @@
+use std::fmt::Display;
+use rand::Rng;
+use serenity::all::{ChannelId, Colour, CreateEmbed, CreateEmbedFooter, Mentionable, UserId};
+use crate::games::{Card, CardType, Deck};
+use crate::games::rng::GameRng;
+
+// the most an admin can set the house rake to
+pub const MAX_RAKE_PERCENT: u8 = 25;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DuelKind {
+    CoinFlip,
+    Dice,
+    HighCard,
+}
+
+impl DuelKind {
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind.to_lowercase().as_str() {
+            "coinflip" | "coin" | "flip" => Some(DuelKind::CoinFlip),
+            "dice" | "die" => Some(DuelKind::Dice),
+            "highcard" | "card" => Some(DuelKind::HighCard),
+            _ => None,
+        }
+    }
+}
+
+impl Display for DuelKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DuelKind::CoinFlip => write!(f, "Coin Flip"),
+            DuelKind::Dice => write!(f, "Dice"),
+            DuelKind::HighCard => write!(f, "High Card"),
+        }
+    }
+}
+
+// aces are high
+fn card_rank(card: &Card) -> u8 {
+    match card.card_type {
+        CardType::Two => 2,
+        CardType::Three => 3,
+        CardType::Four => 4,
+        CardType::Five => 5,
+        CardType::Six => 6,
+        CardType::Seven => 7,
+        CardType::Eight => 8,
+        CardType::Nine => 9,
+        CardType::Ten => 10,
+        CardType::Jack => 11,
+        CardType::Queen => 12,
+        CardType::King => 13,
+        CardType::Ace => 14,
+        CardType::Joker => 15,
+    }
+}
+
+pub struct DuelOutcome {
+    pub winner: UserId,
+    pub loser: UserId,
+    // what happened, one line per throw including any ties
+    pub plays: Vec<String>,
+}
+
+// a challenge from one player to another, the stakes are only taken once it is accepted
+pub struct Duel {
+    pub challenger: UserId,
+    pub opponent: UserId,
+    pub kind: DuelKind,
+    pub stake: u64,
+    pub channel: ChannelId,
+    pub expires_at: i64,
+}
+
+impl Duel {
+
+    pub fn new(challenger: UserId, opponent: UserId, kind: DuelKind, stake: u64, channel: ChannelId, expires_at: i64) -> Self {
+        Self { challenger, opponent, kind, stake, channel, expires_at }
+    }
+
+    // ties are thrown again until someone wins
+    pub fn resolve(&self, rng: &mut GameRng) -> DuelOutcome {
+        let (challenger, opponent) = (self.challenger.mention(), self.opponent.mention());
+        let mut plays = Vec::new();
+
+        let challenger_wins = match self.kind {
+            DuelKind::CoinFlip => {
+                let heads = rng.gen_range(0..2) == 1;
+                plays.push(format!("The coin lands on **{}**, {} called heads", if heads { "heads" } else { "tails" }, challenger));
+                heads
+            }
+            DuelKind::Dice => loop {
+                let (a, b) = (rng.gen_range(1..=6), rng.gen_range(1..=6));
+                plays.push(format!("{} rolls **{}**, {} rolls **{}**", challenger, a, opponent, b));
+                if a != b {
+                    break a > b;
+                }
+            },
+            DuelKind::HighCard => {
+                let mut deck = Deck::new(1, false);
+                deck.shuffle(rng);
+                loop {
+                    // a tie uses up both cards, a fresh deck comes out if it runs dry
+                    let (Some(a), Some(b)) = (deck.deal(), deck.deal()) else {
+                        deck = Deck::new(1, false);
+                        deck.shuffle(rng);
+                        continue;
+                    };
+                    plays.push(format!("{} draws **{}**, {} draws **{}**", challenger, a, opponent, b));
+                    if card_rank(&a) != card_rank(&b) {
+                        break card_rank(&a) > card_rank(&b);
+                    }
+                }
+            }
+        };
+
+        if challenger_wins {
+            DuelOutcome { winner: self.challenger, loser: self.opponent, plays }
+        } else {
+            DuelOutcome { winner: self.opponent, loser: self.challenger, plays }
+        }
+    }
+
+    // what the winner takes home and what the house keeps
+    pub fn split_pot(&self, rake_percent: u8) -> (u64, u64) {
+        let pot = self.stake * 2;
+        let rake = pot * rake_percent.min(MAX_RAKE_PERCENT) as u64 / 100;
+        (pot - rake, rake)
+    }
+
+    pub fn challenge_embed(&self) -> CreateEmbed {
+        CreateEmbed::new()
+            .title(format!("{} Duel", self.kind))
+            .description(format!("{} challenges {} for {}:banana: each!", self.challenger.mention(), self.opponent.mention(), self.stake))
+            .color(Colour::ORANGE)
+            .field("Expires", format!("<t:{}:R>", self.expires_at), true)
+            .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"))
+    }
+
+    pub fn closed_embed<S: Into<String>>(&self, message: S) -> CreateEmbed {
+        CreateEmbed::new()
+            .title(format!("{} Duel", self.kind))
+            .description(message)
+            .color(Colour::DARK_GREY)
+            .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"))
+    }
+
+    pub fn result_embed(&self, outcome: &DuelOutcome, winnings: u64, rake: u64) -> CreateEmbed {
+        let mut embed = CreateEmbed::new()
+            .title(format!("{} Duel", self.kind))
+            .description(outcome.plays.join("\n"))
+            .color(Colour::GOLD)
+            .field("Winner", format!("{} takes {}:banana:", outcome.winner.mention(), winnings), false);
+        if rake > 0 {
+            embed = embed.field("House rake", format!("{}:banana:", rake), true);
+        }
+        embed.footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn duel(kind: DuelKind) -> Duel {
+        Duel::new(UserId::new(1), UserId::new(2), kind, 100, ChannelId::new(1), 0)
+    }
+
+    #[test]
+    fn rake_comes_out_of_the_pot() {
+        assert_eq!(duel(DuelKind::CoinFlip).split_pot(0), (200, 0));
+        assert_eq!(duel(DuelKind::CoinFlip).split_pot(5), (190, 10));
+        assert_eq!(duel(DuelKind::CoinFlip).split_pot(100), (150, 50));
+    }
+
+    #[test]
+    fn duels_always_have_a_winner() {
+        let mut rng = GameRng::from_seed(3);
+        for kind in [DuelKind::CoinFlip, DuelKind::Dice, DuelKind::HighCard] {
+            let duel = duel(kind);
+            let mut challenger_wins = 0;
+            for _ in 0..2000 {
+                let outcome = duel.resolve(&mut rng);
+                assert_ne!(outcome.winner, outcome.loser);
+                if outcome.winner == duel.challenger {
+                    challenger_wins += 1;
+                }
+            }
+            // neither side has an edge
+            assert!((900..1100).contains(&challenger_wins), "{} {}", kind, challenger_wins);
+        }
+    }
+}
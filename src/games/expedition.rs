@@ -0,0 +1,264 @@
+use rand::Rng;
+use serenity::all::{Colour, CreateEmbed, CreateEmbedFooter, Message, Timestamp, UserId};
+use crate::games::mine_battle::MineBattle;
+use crate::games::rng::GameRng;
+use crate::mine_data::{Enemy, ExpeditionConfig, MerchantOffer};
+use crate::userfile::UserValues;
+
+const START_HEALTH: u32 = 100;
+// hidden chambers hold this many times the sludge of a normal dig
+const CHAMBER_MULTIPLIER: u32 = 5;
+// defeating the boss at the bottom multiplies the haul
+const BOSS_HAUL_MULTIPLIER: u32 = 2;
+
+enum ExpeditionState {
+    Exploring,
+    Merchant(MerchantOffer),
+    Battle { battle: Box<MineBattle>, boss: bool },
+}
+
+pub struct Expedition {
+    pub tier: u8,
+    config: ExpeditionConfig,
+    creatures: Vec<Enemy>,
+    sludge_worth: u32,
+    pub depth: u8,
+    pub haul: u32,
+    pub health: u32,
+    state: ExpeditionState,
+    // the creature being fought, if any (in ./images/sludge_monsters)
+    creature_thumbnail: Option<String>,
+}
+
+impl Expedition {
+
+    pub fn new(tier: u8, config: ExpeditionConfig, creatures: Vec<Enemy>, sludge_worth: u32) -> Self {
+        Self {
+            tier,
+            config,
+            creatures,
+            sludge_worth,
+            depth: 0,
+            haul: 0,
+            health: START_HEALTH,
+            state: ExpeditionState::Exploring,
+            creature_thumbnail: None,
+        }
+    }
+
+    pub fn thumbnail(&self) -> String {
+        self.creature_thumbnail.clone().unwrap_or("cartoon_mine.jpeg".to_string())
+    }
+
+    pub fn thumbnail_path(&self) -> String {
+        match &self.creature_thumbnail {
+            Some(thumbnail) => format!("./images/sludge_monsters/{}", thumbnail),
+            None => "./images/cartoon_mine.jpeg".to_string(),
+        }
+    }
+
+    fn haul_value(&self) -> u64 {
+        self.haul as u64 * self.sludge_worth as u64
+    }
+
+    fn options(&self) -> &'static str {
+        match self.state {
+            ExpeditionState::Exploring => "`deeper` or `cashout`",
+            ExpeditionState::Merchant(_) => "`buy` or `skip`",
+            ExpeditionState::Battle { .. } => "`attack`, `run`, `item {inventory slot #}` or `surrender`",
+        }
+    }
+
+    fn status_fields(&self) -> Vec<(&'static str, String, bool)> {
+        vec![
+            ("Depth", format!("{}/{}", self.depth, self.config.depth), true),
+            ("Haul", format!("{} sludge ({}:banana:)", self.haul, self.haul_value()), true),
+            ("Health", self.health.to_string(), true),
+        ]
+    }
+
+    pub fn craft_embed<S: Into<String>>(&self, title: &str, message: S, colour: Colour) -> CreateEmbed {
+        CreateEmbed::new()
+            .title(format!("Tier {} Expedition - {}", self.tier, title))
+            .thumbnail(format!("attachment://{}", self.thumbnail()))
+            .description(message)
+            .color(colour)
+            .fields(self.status_fields())
+            .field("Options: ", self.options(), false)
+            .timestamp(Timestamp::now())
+            .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"))
+    }
+
+    fn end_embed<S: Into<String>>(&self, title: &str, message: S, colour: Colour) -> CreateEmbed {
+        CreateEmbed::new()
+            .title(format!("Tier {} Expedition - {}", self.tier, title))
+            .thumbnail(format!("attachment://{}", self.thumbnail()))
+            .description(message)
+            .color(colour)
+            .timestamp(Timestamp::now())
+            .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"))
+    }
+
+    // pays the haul to the player, returning the bananas earned
+    fn pay_out(&self, user: UserId, multiplier: u32) -> u64 {
+        let bananas = self.haul_value() * multiplier as u64;
+        UserValues::get(&user).add_bananas(bananas);
+        bananas
+    }
+
+    fn start_battle(&mut self, enemy: Enemy, rng: &mut GameRng) -> MineBattle {
+        let mut battle = MineBattle::new(enemy, self.sludge_worth, rng);
+        battle.player.health = self.health;
+        self.creature_thumbnail = Some(battle.thumbnail.clone());
+        battle
+    }
+
+    fn descend(&mut self, rng: &mut GameRng) -> (CreateEmbed, bool) {
+        self.depth += 1;
+        self.creature_thumbnail = None;
+
+        // the boss waits at the bottom
+        if self.depth >= self.config.depth {
+            let battle = self.start_battle(self.config.boss.clone(), rng);
+            let message = format!("You have reached the bottom of the mine and awoken the {}! Defeat it to escape with {}x your haul.",
+                                  battle.enemy.name, BOSS_HAUL_MULTIPLIER);
+            let embed = battle.craft_embed(message)
+                .field("Depth", format!("{}/{}", self.depth, self.config.depth), true)
+                .field("Haul", format!("{} sludge ({}:banana:)", self.haul, self.haul_value()), true);
+            self.state = ExpeditionState::Battle { battle: Box::new(battle), boss: true };
+            return (embed, false);
+        }
+
+        let roll = rng.gen_range(0..100);
+        let cave_in = self.config.cave_in_chance;
+        let chamber = cave_in + self.config.chamber_chance;
+        let merchant = chamber + self.config.merchant_chance;
+        let creature = merchant + self.config.creature_chance;
+
+        // deeper levels hold more sludge
+        let found = rng.gen_range(self.config.sludge_per_depth.clone()) * self.depth as u32;
+
+        if roll < cave_in {
+            let damage = rng.gen_range(self.config.cave_in_damage.clone());
+            self.health = self.health.saturating_sub(damage);
+            // some of the haul gets buried
+            let buried = self.haul / 4;
+            self.haul -= buried;
+
+            if self.health == 0 {
+                return (self.end_embed("Cave In!", format!("The tunnel collapsed on top of you! Your haul of {} sludge is lost forever.", self.haul),
+                                       Colour::RED), true);
+            }
+
+            return (self.craft_embed("Cave In!", format!("The tunnel collapsed! You took {} damage and {} sludge was buried.", damage, buried),
+                                     Colour::RED), false);
+        }
+
+        if roll < chamber {
+            let found = found * CHAMBER_MULTIPLIER;
+            self.haul += found;
+            return (self.craft_embed("Hidden Chamber!", format!("You broke into a hidden chamber full of sludge! You found {} sludge.", found),
+                                     Colour::GOLD), false);
+        }
+
+        if roll < merchant {
+            if let Some(offer) = self.config.random_offer(rng) {
+                let message = format!("A wandering merchant offers you a {} for {}:banana:", offer.item, offer.price);
+                self.state = ExpeditionState::Merchant(offer);
+                return (self.craft_embed("Merchant", message, Colour::BLUE), false);
+            }
+        }
+
+        if roll < creature && !self.creatures.is_empty() {
+            let enemy = self.creatures[rng.gen_range(0..self.creatures.len())].clone();
+            let battle = self.start_battle(enemy, rng);
+            let embed = battle.craft_embed(format!("A {} blocks your path!", battle.enemy.name))
+                .field("Depth", format!("{}/{}", self.depth, self.config.depth), true);
+            self.state = ExpeditionState::Battle { battle: Box::new(battle), boss: false };
+            return (embed, false);
+        }
+
+        self.haul += found;
+        (self.craft_embed("Digging", format!("You dug deeper and found {} sludge.", found), Colour::DARK_GREEN), false)
+    }
+
+    fn cash_out(&self, user: UserId) -> (CreateEmbed, bool) {
+        let bananas = self.pay_out(user, 1);
+        (self.end_embed("Cashed Out", format!("You climbed out of the mine with {} sludge.", self.haul), Colour::DARK_GREEN)
+             .field("Bananas Earned:", format!("{}:banana:", bananas), false), true)
+    }
+
+    fn handle_merchant(&mut self, user: UserId, command: &str) -> (CreateEmbed, bool) {
+        let ExpeditionState::Merchant(offer) = &self.state else {
+            unreachable!()
+        };
+        let offer = offer.clone();
+
+        match command {
+            "buy" => {
+                let mut user_file = UserValues::get(&user);
+                if user_file.get_bananas() < offer.price {
+                    return (self.craft_embed("Merchant", "You too poor!", Colour::RED), false);
+                }
+                if user_file.file.inventory.is_full() {
+                    return (self.craft_embed("Merchant", "Your inventory is full!", Colour::RED), false);
+                }
+                user_file.remove_bananas(offer.price);
+                user_file.add_item(offer.item.clone());
+                self.state = ExpeditionState::Exploring;
+                (self.craft_embed("Merchant", format!("You bought a {} for {}:banana:", offer.item, offer.price), Colour::DARK_GREEN), false)
+            }
+            "skip" => {
+                self.state = ExpeditionState::Exploring;
+                (self.craft_embed("Merchant", "You ignored the merchant and carried on.", Colour::DARK_GREEN), false)
+            }
+            _ => (self.craft_embed("Merchant", "Me no understand!", Colour::RED), false),
+        }
+    }
+
+    fn handle_battle(&mut self, msg: &Message, command: &str, rng: &mut GameRng) -> (CreateEmbed, bool) {
+        let ExpeditionState::Battle { battle, boss } = &mut self.state else {
+            unreachable!()
+        };
+        let boss = *boss;
+
+        let (embed, end) = battle.handle_message(msg, rng);
+        self.creature_thumbnail = Some(battle.thumbnail.clone());
+        self.health = battle.player.health;
+        let won = !battle.creature.is_alive();
+
+        if !end {
+            return (embed.field("Depth", format!("{}/{}", self.depth, self.config.depth), true), false);
+        }
+
+        self.state = ExpeditionState::Exploring;
+
+        // dying or surrendering ends the expedition and the haul is lost
+        if self.health == 0 || command == "surrender" {
+            return (embed.field("Expedition Failed", format!("Your haul of {} sludge was lost in the depths.", self.haul), false), true);
+        }
+
+        if won && boss {
+            let bananas = self.pay_out(msg.author.id, BOSS_HAUL_MULTIPLIER);
+            return (embed.field("Expedition Complete!", format!("You escaped the mine with {} sludge, worth {}:banana:", self.haul, bananas), false), true);
+        }
+
+        (embed.field("Expedition", format!("You are {} levels deep. {}", self.depth, self.options()), false), false)
+    }
+
+    pub fn handle_message(&mut self, msg: &Message, rng: &mut GameRng) -> (CreateEmbed, bool) {
+        let content = msg.content.as_str().to_lowercase();
+        let command = content.split_whitespace().next().unwrap_or("").to_string();
+
+        match self.state {
+            ExpeditionState::Battle { .. } => self.handle_battle(msg, &command, rng),
+            ExpeditionState::Merchant(_) => self.handle_merchant(msg.author.id, &command),
+            ExpeditionState::Exploring => match command.as_str() {
+                "deeper" => self.descend(rng),
+                "cashout" => self.cash_out(msg.author.id),
+                _ => (self.craft_embed("Exploring", "Me no understand!", Colour::RED), false),
+            },
+        }
+    }
+
+}
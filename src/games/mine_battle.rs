@@ -1,112 +1,167 @@
-use rand::{Rng, thread_rng};
+use rand::Rng;
 use serenity::all::{Colour, CreateEmbed, CreateEmbedFooter, Message, Timestamp, UserId};
+use crate::achievements::Stat;
+use crate::games::combat::{self, Action, BattleEvent, BattleLog, Combatant, Outcome, Stats};
+use crate::games::rng::GameRng;
+use crate::games::status_effect::StatusEffect;
 use crate::inventory::item::InventoryItem;
-use crate::mine_data::Enemy;
+use crate::mine_data::{Ability, Enemy};
 use crate::userfile::UserValues;
 
+const PLAYER_START_HEALTH: u32 = 100;
+// praying once per battle heals the player up to this
+const PRAYER_HEALTH: u32 = 200;
+
 pub struct MineBattle {
     pub enemy: Enemy,
     pub sludge_value: u32,
-    pub enemy_health: u32,
-    pub player_health: u32,
+    pub creature: Combatant,
+    pub player: Combatant,
     pub initial_health: u32,
     pub has_prayed: bool,
     pub thumbnail: String,
+    // weaker creatures summoned by the enemy, they must be killed before the enemy can be hit
+    pub summons: Vec<Combatant>,
+    // what happened this turn, shown in the next embed
+    pub log: BattleLog,
 }
 
 impl MineBattle {
 
-    pub fn new(enemy: Enemy, sludge_value: u32) -> Self {
+    pub fn new(enemy: Enemy, sludge_value: u32, rng: &mut GameRng) -> Self {
         // create a new mine battle
-        let enemy_health = thread_rng().gen_range(enemy.health.clone());
+        let enemy_health = rng.gen_range(enemy.health.clone());
         // convert to round 100 base number
         let enemy_health = enemy_health - (enemy_health % 100);
         Self {
             thumbnail: enemy.thumbnail.clone(),
+            creature: Combatant::new(format!("the {}", enemy.name), enemy_health,
+                                     Stats { max_health: Some(enemy_health), damage: enemy.damage.clone() }),
+            // potions are not capped in the mines, deeper tiers hit far harder than 100hp
+            player: Combatant::new("you", PLAYER_START_HEALTH, Stats { max_health: None, damage: 0..=10 }),
             enemy,
             sludge_value,
-            enemy_health,
-            player_health: 100,
             initial_health: enemy_health,
             has_prayed: false,
+            summons: Vec::new(),
+            log: BattleLog::default(),
         }
     }
 
-    pub fn attack(&mut self, equipped: Option<InventoryItem>) -> u32 {
-
-        // calculate damage
-        let damage = if let Some(item) = equipped {
-            match item {
-                InventoryItem::Weapon { damage, .. } => thread_rng().gen_range(damage),
-                _ => thread_rng().gen_range(0..=10)
+    // summons protect the enemy, so they are hit first
+    fn player_action(&mut self, action: Action, rng: &mut GameRng) -> Outcome {
+        if let Some(summon) = self.summons.first_mut() {
+            let outcome = combat::resolve(&mut self.player, summon, action, &mut self.log, rng);
+            if outcome == Outcome::Victory {
+                self.summons.remove(0);
+                return Outcome::Continue;
             }
-        } else {
-            thread_rng().gen_range(0..=10)
-        };
-
-        if damage > self.enemy_health {
-            self.enemy_health = 0;
-            return damage;
+            return outcome;
         }
-        self.enemy_health -= damage;
-        damage
+
+        combat::resolve(&mut self.player, &mut self.creature, action, &mut self.log, rng)
     }
 
-    pub fn attempt_flee(&mut self) -> bool {
-        // calculate if the user flees based on health
-        thread_rng().gen_range(0..self.player_health) == 0
+    pub fn attack(&mut self, equipped: Option<InventoryItem>, rng: &mut GameRng) -> Outcome {
+        let action = match equipped {
+            Some(InventoryItem::Weapon { name, damage, .. }) => Action::Attack { weapon: Some(damage), with: Some(format!("your {}", name)) },
+            _ => Action::Attack { weapon: None, with: Some("your fists".to_string()) },
+        };
+        self.player_action(action, rng)
     }
 
-    pub fn enemy_turn(&mut self) -> u32 {
-        // calculate damage between 0 and 25 hp
-        let damage = thread_rng().gen_range(self.enemy.damage.clone());
-        if damage > self.player_health {
-            self.player_health = 0;
-            return damage;
+    // the enemy and its summons attack, then it may use its abilities
+    pub fn enemy_turn(&mut self, rng: &mut GameRng) -> u32 {
+        let health_before = self.player.health;
+
+        combat::resolve(&mut self.creature, &mut self.player, Action::Attack { weapon: None, with: None }, &mut self.log, rng);
+        for summon in self.summons.iter_mut() {
+            if !self.player.is_alive() {
+                break;
+            }
+            combat::resolve(summon, &mut self.player, Action::Attack { weapon: None, with: None }, &mut self.log, rng);
+        }
+
+        let damage = health_before - self.player.health;
+        if self.player.is_alive() {
+            self.use_abilities(damage, rng);
         }
-        self.player_health -= damage;
         damage
     }
 
-    pub fn heal_player(&mut self, amt: u32) {
-        // heal the player for 25 hp
-        self.player_health += amt;
-        // if self.player_health > 100 {
-        //     self.player_health = 100;
-        // }
+    fn roll_chance(rng: &mut GameRng, chance: u32) -> bool {
+        rng.gen_range(0..100) < chance
+    }
+
+    fn use_abilities(&mut self, damage_dealt: u32, rng: &mut GameRng) {
+        let name = self.enemy.name.clone();
+        let mut events = Vec::new();
+        for ability in self.enemy.abilities.clone() {
+            match ability {
+                Ability::Poison { chance, damage, turns } => if Self::roll_chance(rng, chance) {
+                    self.player.effects.apply(StatusEffect::Poisoned { damage, turns });
+                    events.push(format!("The {} poisoned you!", name));
+                },
+                Ability::Stun { chance, turns } => if Self::roll_chance(rng, chance) {
+                    self.player.effects.apply(StatusEffect::Stunned { turns });
+                    events.push(format!("The {} stunned you!", name));
+                },
+                Ability::Lifesteal { percent } => {
+                    let healed = self.creature.heal((damage_dealt as u64 * percent as u64 / 100) as u32);
+                    if healed > 0 {
+                        events.push(format!("The {} drained {}hp from you!", name, healed));
+                    }
+                },
+                Ability::Enrage { below_percent, damage_multiplier } => {
+                    if !self.creature.effects.is_enraged() && (self.creature.health as u64) * 100 < self.initial_health as u64 * below_percent as u64 {
+                        self.creature.effects.apply(StatusEffect::Enraged { damage_multiplier });
+                        events.push(format!("The {} has become enraged!", name));
+                    }
+                },
+                Ability::Summon { chance, name: summon, health, damage, max } => {
+                    if (self.summons.len() as u32) < max && Self::roll_chance(rng, chance) {
+                        let health = rng.gen_range(health);
+                        events.push(format!("The {} summoned a {}!", name, summon));
+                        self.summons.push(Combatant::new(format!("the {}", summon), health, Stats { max_health: Some(health), damage }));
+                    }
+                },
+                Ability::Shield { chance, amount } => if Self::roll_chance(rng, chance) {
+                    self.creature.effects.apply(StatusEffect::Shielded { amount });
+                    events.push(format!("The {} raised a shield!", name));
+                },
+            }
+        }
+        for event in events {
+            self.log.push(BattleEvent::Effect(event));
+        }
     }
 
-    pub fn use_item(&mut self, item: InventoryItem, user: UserId) -> (bool, (CreateEmbed, bool)) {
+    pub fn use_item(&mut self, item: InventoryItem, user: UserId, rng: &mut GameRng) -> (bool, (CreateEmbed, bool)) {
         // use an item in the battle
         match item {
             InventoryItem::HealingPotion { health } => {
-                self.heal_player(health);
-                (true, (self.craft_embed(format!("You healed for {}hp!", health)), false))
+                combat::heal(&mut self.player, health, &mut self.log);
+                (true, (self.craft_embed(String::new()), false))
             }
             InventoryItem::SpellTome { name, damage } => {
-                let damage = thread_rng().gen_range(damage);
-
                 let used = name.as_str() != "Developer Tome";
                 if !used {
                     self.thumbnail = "developer_tome.jpeg".to_string();
                 }
 
-                if damage > self.enemy_health {
-                    self.enemy_health = 0;
+                self.player_action(Action::Spell { name: name.clone(), damage }, rng);
+                if !self.creature.is_alive() {
                     return if name.as_str() == "Mog"
-                    { (used, self.handle_win(user, format!("You have mogged on the {} so hard he died", self.enemy.name))) }
-                    else { (used, self.handle_win(user, format!("You have defeated the {} using your {} Tome.", self.enemy.name, name))) };
+                    { (used, self.handle_win(user, format!("You have mogged on the {} so hard he died", self.enemy.name), rng)) }
+                    else { (used, self.handle_win(user, format!("You have defeated the {} using your {} Tome.", self.enemy.name, name), rng)) };
                 }
-                self.enemy_health -= damage;
 
-                let boss_attack = self.enemy_turn();
+                self.enemy_turn(rng);
 
                 if name.as_str() == "Mog" {
-                    (used, (self.craft_embed(format!("You Mogged and dealt {} damage! The {} attacked you for {} damage!",
-                                                     damage, self.enemy.name, boss_attack)), false))
+                    (used, (self.craft_embed("You Mogged!".to_string()), false))
                 } else {
-                    (used, (self.craft_embed(format!("You used your {} Tome and dealt {} damage! The {} attacked you for {} damage!",
-                                                     name, damage, self.enemy.name, boss_attack)), false))
+                    (used, (self.craft_embed(String::new()), false))
                 }
             }
             InventoryItem::Weapon { .. } => {
@@ -116,34 +171,74 @@ impl MineBattle {
         }
     }
 
+    // renders the message followed by the battle log
     pub fn craft_embed(&self, message: String) -> CreateEmbed {
-        // create an embed message for the battle
-        CreateEmbed::new()
+        let message = match (message.is_empty(), self.log.is_empty()) {
+            (_, true) => message,
+            (true, false) => self.log.to_string(),
+            (false, false) => format!("{}\n{}", message, self.log),
+        };
+
+        let mut embed = CreateEmbed::new()
             .title(format!("{} Battle", self.enemy.name))
             .thumbnail(format!("attachment://{}", self.thumbnail))
             .description(message)
             .color(Colour::DARK_GREEN)
             .fields(
                 vec![
-                    ("Creature Health", format!("{}", self.enemy_health), true),
-                    ("Your Health", self.player_health.to_string(), true),
-                    ("Options: ", "`attack`, `run`, `item {inventory slot #}` or `surrender`".to_string(), false),
+                    ("Creature Health", format!("{}", self.creature.health), true),
+                    ("Your Health", self.player.health.to_string(), true),
                 ]
-            )
+            );
+
+        if !self.summons.is_empty() {
+            let summons = self.summons.iter()
+                .map(|s| format!("{} ({}hp)", s.name, s.health))
+                .collect::<Vec<String>>();
+            embed = embed.field("Summoned", summons.join("\n"), false);
+        }
+
+        embed
+            .field("Creature Effects", self.creature.effects.to_string(), true)
+            .field("Your Effects", self.player.effects.to_string(), true)
+            .field("Options: ", "`attack`, `run`, `item {inventory slot #}` or `surrender`", false)
     }
 
-    pub fn handle_win(&self, user: UserId, msg: String) -> (CreateEmbed, bool) {
+    pub fn handle_win(&self, user: UserId, msg: String, rng: &mut GameRng) -> (CreateEmbed, bool) {
+        let (mut embed, end) = self.roll_reward(user, msg, rng);
+
         let mut user_file = UserValues::get(&user);
+        user_file.add_progress(Stat::CreaturesDefeated, 1);
+        user_file.add_progress(Stat::Defeated(self.enemy.name.clone()), 1);
+
+        // some creatures also drop parts for upgrading the super drill
+        if let Some(parts) = self.enemy.drops.drill_parts.clone() {
+            let parts = rng.gen_range(parts);
+            if parts > 0 {
+                user_file.add_drill_parts(parts);
+                embed = embed.field("Drill Parts:", format!("{}:gear:", parts), false);
+            }
+        }
 
-        let reward_chance = thread_rng().gen_range(0..3);
+        (embed, end)
+    }
+
+    fn roll_reward(&self, user: UserId, msg: String, rng: &mut GameRng) -> (CreateEmbed, bool) {
+        let mut user_file = UserValues::get(&user);
+
+        let mut reward_chance = rng.gen_range(0..3);
+        // the item find perk turns some sludge drops into items
+        if reward_chance == 0 && rng.gen_range(0..100) < user_file.get_perks().item_find_chance() {
+            reward_chance = 1;
+        }
         match reward_chance {
             0 => { // sludge mined
                 let sludge = self.enemy.drops.sludge.clone();
                 let reward = if self.enemy.reward_scaling {
                     // multiply the winnings by health / 100
-                    self.sludge_value * ((self.initial_health / 100) * thread_rng().gen_range(sludge))
+                    self.sludge_value * ((self.initial_health / 100) * rng.gen_range(sludge))
                 } else {
-                    self.sludge_value * thread_rng().gen_range(sludge)
+                    self.sludge_value * rng.gen_range(sludge)
                 };
                 user_file.add_bananas(reward as u64);
                 (CreateEmbed::new()
@@ -157,7 +252,7 @@ impl MineBattle {
                  true)
             }
             1 => { // item found
-                let item = self.enemy.drops.random_item();
+                let item = self.enemy.drops.random_item(rng);
                 if user_file.file.inventory.is_full() {
                     return (CreateEmbed::new()
                                 .thumbnail(format!("attachment://{}", self.thumbnail))
@@ -181,7 +276,7 @@ impl MineBattle {
                  true)
             }
             2 => { // super nanners
-                let nanners = thread_rng().gen_range(1..=5);
+                let nanners = rng.gen_range(1..=5);
                 user_file.add_super_nanners(nanners);
                 (CreateEmbed::new()
                      .title("Victory!")
@@ -197,13 +292,13 @@ impl MineBattle {
         }
     }
 
-    pub fn handle_player_death(&self, user: UserId) -> (CreateEmbed, bool) {
+    pub fn handle_player_death(&self, user: UserId, rng: &mut GameRng) -> (CreateEmbed, bool) {
         let mut user_file = UserValues::get(&user);
         let nanners = user_file.get_bananas();
         let cost_min = nanners / 5;
         let cost_max = nanners / 2;
 
-        let cost  = thread_rng().gen_range(cost_min..=cost_max);
+        let cost  = rng.gen_range(cost_min..=cost_max);
 
         let balance = user_file.get_bananas();
         if balance < cost {
@@ -223,40 +318,58 @@ impl MineBattle {
                 true)
     }
 
-    pub fn handle_message(&mut self, msg: &Message) -> (CreateEmbed, bool) {
+
+    pub fn handle_message(&mut self, msg: &Message, rng: &mut GameRng) -> (CreateEmbed, bool) {
         let content = msg.content.as_str().to_lowercase();
         let mut split = content.split_whitespace();
         let first = split.next().unwrap();
 
+        self.log.clear();
+
+        // effects tick whenever the player takes an action
+        if matches!(first, "attack" | "item" | "run") && !combat::start_turn(&mut self.player, &mut self.log) {
+            if !self.player.is_alive() {
+                return self.handle_player_death(msg.author.id, rng);
+            }
+
+            // stunned, the enemy gets a free turn
+            self.enemy_turn(rng);
+
+            if !self.player.is_alive() {
+                return self.handle_player_death(msg.author.id, rng);
+            }
+
+            return (self.craft_embed(String::new()), false);
+        }
+
         match first {
             "attack" => {
                 let mut user_file = UserValues::get(&msg.author.id);
 
                 let equipped = user_file.get_equiped();
 
-                let attack = self.attack(equipped.clone());
+                self.attack(equipped, rng);
 
-                if self.enemy_health == 0 {
-                    return self.handle_win(msg.author.id, format!("You have defeated the {}!", self.enemy.name));
+                if !self.creature.is_alive() {
+                    return self.handle_win(msg.author.id, format!("You have defeated the {}!", self.enemy.name), rng);
                 }
 
-                let boss_attack = self.enemy_turn();
+                self.enemy_turn(rng);
 
-                if self.player_health == 0 {
-                    return self.handle_player_death(msg.author.id);
+                if !self.player.is_alive() {
+                    return self.handle_player_death(msg.author.id, rng);
                 }
 
-                (self.craft_embed(format!("You attacked the {}{} for {} damage, and it attacked you for {} damage!",
-                                          self.enemy.name, if let Some(item) = equipped { format!(" with your {}", item) } else { "fists".to_string() }, attack, boss_attack)), false)
+                (self.craft_embed(String::new()), false)
             }
             "item" => {
                 // get the item the user is trying to use as u8
-                let Ok(slot) = split.next().unwrap().parse::<u8>() else {
+                let Some(Ok(slot)) = split.next().map(|s| s.parse::<u8>()) else {
                     return (self.craft_embed("**INVALID ITEM** To use an item, type `item #`, where # is the slot in your inventory (see `/inventory`)"
                         .to_string()), false)
                 };
 
-                let slot = slot - 1;
+                let slot = slot.saturating_sub(1);
 
                 let mut user_file = UserValues::get(&msg.author.id);
 
@@ -268,7 +381,7 @@ impl MineBattle {
                         .to_string()), false)
                 };
 
-                let (used, data) = self.use_item(item.clone(), msg.author.id);
+                let (used, data) = self.use_item(item.clone(), msg.author.id, rng);
 
                 // remove the item
                 if used {
@@ -276,8 +389,8 @@ impl MineBattle {
                 }
 
                 // check if user needs to die
-                if self.player_health == 0 {
-                    return self.handle_player_death(msg.author.id);
+                if !self.player.is_alive() {
+                    return self.handle_player_death(msg.author.id, rng);
                 }
 
                 data
@@ -287,16 +400,14 @@ impl MineBattle {
                     return (self.craft_embed("You have already prayed this battle! (This easter egg can't be too powerful!)".to_string()), false)
                 }
 
-                //let heal = thread_rng().gen_range(1..=25);
-                let heal = 200 - self.player_health;
-                self.heal_player(heal);
+                // prayer ignores the usual healing rules
+                let heal = PRAYER_HEALTH.saturating_sub(self.player.health);
+                self.player.health += heal;
                 self.has_prayed = true;
                 (self.craft_embed(format!("You prayed and healed for {}hp!", heal)), false)
             }
             "run" => {
-                let flee = self.attempt_flee();
-
-                if flee {
+                if combat::resolve(&mut self.player, &mut self.creature, Action::Flee, &mut self.log, rng) == Outcome::Fled {
                     return (CreateEmbed::new()
                                 .title("Flee!")
                                 .description("You have successfully fled from the creature.")
@@ -306,16 +417,16 @@ impl MineBattle {
                             true);
                 }
 
-                let boss_attack = self.enemy_turn();
+                self.enemy_turn(rng);
 
-                if self.player_health == 0 {
-                    return self.handle_player_death(msg.author.id);
+                if !self.player.is_alive() {
+                    return self.handle_player_death(msg.author.id, rng);
                 }
 
-                (self.craft_embed(format!("You failed to flee, and the sludge monster attacked you for {} damage!", boss_attack)), false)
+                (self.craft_embed(String::new()), false)
             }
             "surrender" => {
-                self.handle_player_death(msg.author.id)
+                self.handle_player_death(msg.author.id, rng)
             }
             _ => {
                 (self.craft_embed("Me no understand!".to_string()), false)
@@ -323,4 +434,4 @@ impl MineBattle {
         }
     }
 
-}
\ No newline at end of file
+}
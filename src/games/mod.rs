@@ -2,17 +2,37 @@ use std::collections::HashMap;
 use std::fmt::Display;
 use rand::{random, Rng};
 use serenity::all::{UserId};
+use crate::say;
 use crate::games::blackjack::BlackJack;
+use crate::games::blackjack_table::BlackjackTable;
+use crate::games::crash::Crash;
+use crate::games::duel::Duel;
+use crate::games::expedition::Expedition;
 use crate::games::mine_battle::MineBattle;
+use crate::games::monkey_race::MonkeyRace;
 use crate::games::pvp::PvPArena;
+use crate::games::raid::Raid;
+use crate::games::roulette::Roulette;
+use crate::games::rng::GameRng;
 use crate::games::sludge_monster_battle::SludgeMonsterBattle;
 use crate::games::texas_holdem::TexasHoldem;
 
 pub mod blackjack;
+pub mod blackjack_table;
+pub mod crash;
+pub mod duel;
 pub mod texas_holdem;
 pub mod sludge_monster_battle;
 pub mod mine_battle;
+pub mod monkey_race;
 pub mod pvp;
+pub mod expedition;
+pub mod raid;
+pub mod roulette;
+pub mod status_effect;
+pub mod combat;
+pub mod rng;
+pub mod shoe;
 
 #[derive(Clone, Copy, Debug)]
 pub enum CardType {
@@ -387,15 +407,20 @@ impl Deck {
         Deck { cards }
     }
 
-    pub fn shuffle(&mut self) {
+    pub fn shuffle(&mut self, rng: &mut GameRng) {
         use rand::seq::SliceRandom;
-        use rand::thread_rng;
 
-        self.cards.shuffle(&mut thread_rng());
+        self.cards.shuffle(rng);
     }
 
-    pub fn deal(&mut self) -> Card {
-        self.cards.pop().unwrap()
+    // None once every card has been dealt
+    pub fn deal(&mut self) -> Option<Card> {
+        self.cards.pop()
+    }
+
+    // the next cards to be dealt, in order
+    pub fn peek(&self, count: usize) -> Vec<Card> {
+        self.cards.iter().rev().take(count).copied().collect()
     }
 }
 
@@ -410,8 +435,8 @@ pub enum D20 {
 }
 
 impl D20 {
-    pub fn roll() -> Self {
-        let roll = rand::thread_rng().gen_range(1..21);
+    pub fn roll(rng: &mut GameRng) -> Self {
+        let roll = rng.gen_range(1..21);
         match roll {
             1 => D20::SuperBad,
             2..=5 => D20::Bad,
@@ -427,10 +452,17 @@ impl D20 {
 
 pub enum Games {
     BlackJack(BlackJack),
+    BlackjackTable(Box<BlackjackTable>),
+    Roulette(Roulette),
+    Crash(Crash),
+    Duel(Duel),
+    MonkeyRace(MonkeyRace),
     TexasHoldem(TexasHoldem),
 
     SludgeMonsterBattle(SludgeMonsterBattle),
     MineBattle(MineBattle),
+    Expedition(Box<Expedition>),
+    Raid(Box<Raid>),
 
     PvP(PvPArena),
 }
@@ -440,11 +472,13 @@ pub struct GameHandler {
     pub host: UserId,
     pub players: Vec<UserId>,
     pub game: Games,
+    // the game's randomness, its seed is logged when the game ends so results can be replayed
+    pub rng: GameRng,
 }
 
 impl GameHandler {
-    pub fn new(host: UserId, game: Games) -> GameHandler {
-        GameHandler { host, players: Vec::new(), game }
+    pub fn new(host: UserId, game: Games, rng: GameRng) -> GameHandler {
+        GameHandler { host, players: Vec::new(), game, rng }
     }
 
     pub fn has_player(&self, player: &UserId) -> bool {
@@ -480,17 +514,25 @@ impl GamesManager {
     pub fn get_join_required_info(&mut self, code: GameCode) -> Option<usize> {
         if let Some(g) = self.games.get_mut(&code) {
             match &g.game {
-                Games::BlackJack(_) | Games::SludgeMonsterBattle(_) | Games::MineBattle(_) => None,
+                Games::BlackJack(_) | Games::Roulette(_) | Games::Crash(_) | Games::Duel(_) | Games::MonkeyRace(_) | Games::SludgeMonsterBattle(_) | Games::MineBattle(_) | Games::Expedition(_) => None,
                 Games::TexasHoldem(_) => todo!(),
                 Games::PvP(arena) => Some(arena.stake as usize),
+                Games::Raid(_) | Games::BlackjackTable(_) => Some(0),
             }
         } else {
             None
         }
     }
 
+    // clan war arenas can only be joined by players in a clan
+    pub fn requires_clan(&self, code: GameCode) -> bool {
+        matches!(self.games.get(&code).map(|g| &g.game), Some(Games::PvP(arena)) if arena.is_clan_war())
+    }
+
     pub fn end_game(&mut self, code: GameCode) {
-        self.games.remove(&code);
+        if let Some(game) = self.games.remove(&code) {
+            say!("Game {} hosted by {} ended (seed {})", code, game.host, game.rng.seed());
+        }
     }
 
     pub fn generate_game_code(&self) -> GameCode {
@@ -548,9 +590,11 @@ impl GamesManager {
     pub fn can_join(&self, game_code: &GameCode) -> bool {
         let game = self.games.get(game_code).unwrap();
         match &game.game {
-            Games::BlackJack(_) | Games::SludgeMonsterBattle(_) | Games::MineBattle(_) => false,
+            Games::BlackJack(_) | Games::Roulette(_) | Games::Crash(_) | Games::Duel(_) | Games::MonkeyRace(_) | Games::SludgeMonsterBattle(_) | Games::MineBattle(_) | Games::Expedition(_) => false,
             Games::TexasHoldem(th) => th.can_join(),
             Games::PvP(arena) => arena.can_join(),
+            Games::Raid(raid) => raid.can_join(),
+            Games::BlackjackTable(table) => table.can_join(),
         }
     }
 
@@ -558,7 +602,7 @@ impl GamesManager {
         let game = self.games.get_mut(&game_code).unwrap();
         game.add_player(user.clone());
         match &mut game.game {
-            Games::BlackJack(_) | Games::SludgeMonsterBattle(_) | Games::MineBattle(_) => false,
+            Games::BlackJack(_) | Games::Roulette(_) | Games::Crash(_) | Games::Duel(_) | Games::MonkeyRace(_) | Games::SludgeMonsterBattle(_) | Games::MineBattle(_) | Games::Expedition(_) => false,
             Games::TexasHoldem(_) => todo!(),
             Games::PvP(arena) => if arena.can_join() {
                 arena.add_player(user);
@@ -566,6 +610,18 @@ impl GamesManager {
             } else {
                 false
             },
+            Games::Raid(raid) => if raid.can_join() {
+                raid.add_player(user);
+                true
+            } else {
+                false
+            },
+            Games::BlackjackTable(table) => if table.can_join() {
+                table.add_player(user);
+                true
+            } else {
+                false
+            },
         }
     }
 
@@ -575,18 +631,24 @@ impl GamesManager {
         if let Some(index) = game.players.iter().position(|x| *x == player) {
             game.players.remove(index);
         }
+        // if the host leaves, the next player takes over
+        if game.host == player && !game.players.is_empty() {
+            game.host = game.players.remove(0);
+        }
 
         match &mut game.game {
-            Games::BlackJack(_) | Games::SludgeMonsterBattle(_) | Games::MineBattle(_) => (),
+            Games::BlackJack(_) | Games::Roulette(_) | Games::Crash(_) | Games::Duel(_) | Games::MonkeyRace(_) | Games::SludgeMonsterBattle(_) | Games::MineBattle(_) | Games::Expedition(_) => (),
             Games::TexasHoldem(_) => todo!(),
             Games::PvP(arena) => arena.remove_player(player),
+            Games::Raid(raid) => raid.remove_player(player),
+            Games::BlackjackTable(table) => table.remove_player(player),
         }
     }
 
     pub fn get_hand(&self, game_code: &GameCode, player: UserId) -> Option<Vec<Card>> {
         let game = self.games.get(game_code).unwrap();
         match &game.game {
-            Games::BlackJack(_) | Games::SludgeMonsterBattle(_) | Games::MineBattle(_) | Games::PvP(_) => None,
+            Games::BlackJack(_) | Games::Roulette(_) | Games::Crash(_) | Games::Duel(_) | Games::MonkeyRace(_) | Games::SludgeMonsterBattle(_) | Games::MineBattle(_) | Games::Expedition(_) | Games::PvP(_) | Games::Raid(_) | Games::BlackjackTable(_) => None,
             Games::TexasHoldem(th) => th.get_hand(player),
         }
     }
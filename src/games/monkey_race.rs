@@ -0,0 +1,253 @@
+use rand::Rng;
+use rand::seq::SliceRandom;
+use serenity::all::{ChannelId, Colour, CreateEmbed, CreateEmbedFooter, Mentionable, Message, Timestamp, UserId};
+use crate::games::rng::GameRng;
+use crate::SUPERBOOST;
+
+pub const MONKEYS_PER_RACE: usize = 5;
+pub const TRACK_LENGTH: u32 = 60;
+// the share of every bet the house expects to keep
+pub const HOUSE_EDGE: f64 = 0.1;
+pub const MIN_ODDS: f64 = 1.01;
+pub const MAX_ODDS: f64 = 50.0;
+// races simulated to work out each monkey's chances
+const ODDS_TRIALS: usize = 5000;
+// fields with a bigger favourite are rerolled, its odds would sit on MIN_ODDS and eat the house edge
+const MAX_FAVOURITE_CHANCE: f64 = 0.6;
+const MAX_FIELD_REROLLS: usize = 20;
+const BAR_LENGTH: u32 = 15;
+
+const NAMES: [&str; 12] = ["George", "Bubbles", "Coco", "Mango", "Kong Jr.", "Banana Bob", "Chimpy", "Peanut", "Rocket", "Sir Swings", "Gorilla Glue", "Marmalade"];
+const EMOJIS: [&str; 5] = [":monkey:", ":monkey_face:", ":orangutan:", ":gorilla:", ":see_no_evil:"];
+
+#[derive(Clone, Debug)]
+pub struct Monkey {
+    pub name: String,
+    // out of 10, the chance each tick of an extra step
+    pub speed: u32,
+    // out of 10, the chance each tick of a banana boost
+    pub burst: u32,
+    // ticks before the monkey tires and slows down
+    pub stamina: u32,
+}
+
+impl Monkey {
+    fn random(name: &str, rng: &mut GameRng) -> Self {
+        Self {
+            name: name.to_string(),
+            speed: rng.gen_range(1..=9),
+            burst: rng.gen_range(1..=5),
+            stamina: rng.gen_range(6..=14),
+        }
+    }
+
+    // every monkey runs 1 to 8 a tick, stats only nudge the odds
+    fn step(&self, tick: u32, rng: &mut GameRng) -> u32 {
+        let mut step = rng.gen_range(1..=8);
+        if rng.gen_range(0..10) < self.speed {
+            step += 1;
+        }
+        if rng.gen_range(0..50) < self.burst {
+            step += 4;
+        }
+        if tick > self.stamina {
+            step -= 1;
+        }
+        step
+    }
+}
+
+pub struct RaceResult {
+    // every monkey's distance after each tick
+    pub frames: Vec<Vec<u32>>,
+    pub winner: usize,
+}
+
+// runs a race to the finish, a photo finish is settled at random
+pub fn simulate(monkeys: &[Monkey], rng: &mut GameRng) -> RaceResult {
+    let mut positions = vec![0; monkeys.len()];
+    let mut frames = Vec::new();
+    let mut tick = 0;
+    loop {
+        tick += 1;
+        for (position, monkey) in positions.iter_mut().zip(monkeys) {
+            *position += monkey.step(tick, rng);
+        }
+        frames.push(positions.clone());
+
+        let best = *positions.iter().max().unwrap();
+        if best >= TRACK_LENGTH {
+            let leaders = (0..monkeys.len()).filter(|i| positions[*i] == best).collect::<Vec<usize>>();
+            let winner = *leaders.choose(rng).unwrap();
+            return RaceResult { frames, winner };
+        }
+    }
+}
+
+// each monkey's chance of winning, worked out by running the race many times
+pub fn estimate_chances(monkeys: &[Monkey], rng: &mut GameRng, trials: usize) -> Vec<f64> {
+    let mut wins = vec![0; monkeys.len()];
+    for _ in 0..trials {
+        wins[simulate(monkeys, rng).winner] += 1;
+    }
+    wins.iter().map(|w| *w as f64 / trials as f64).collect()
+}
+
+// decimal odds (the stake included) that leave the house its edge
+pub fn odds_for(chance: f64) -> f64 {
+    if chance <= 0.0 {
+        return MAX_ODDS;
+    }
+    (((1.0 - HOUSE_EDGE) / chance * 100.0).floor() / 100.0).clamp(MIN_ODDS, MAX_ODDS)
+}
+
+pub struct RaceBet {
+    pub user: UserId,
+    pub monkey: usize,
+    pub stake: u64,
+}
+
+// bananas returned for a bet on the winner, winnings are boosted but not the stake
+pub fn payout(stake: u64, odds: f64, superboost: bool) -> u64 {
+    let mut winnings = ((stake as f64 * odds) as u64).saturating_sub(stake);
+    if superboost {
+        winnings *= SUPERBOOST;
+    }
+    stake + winnings
+}
+
+// one race, everyone in the channel can bet on it until betting closes
+pub struct MonkeyRace {
+    pub channel: ChannelId,
+    pub monkeys: Vec<Monkey>,
+    pub odds: Vec<f64>,
+    pub bets: Vec<RaceBet>,
+    pub closes_at: i64,
+}
+
+impl MonkeyRace {
+
+    pub fn new(channel: ChannelId, closes_at: i64, rng: &mut GameRng) -> Self {
+        let names = NAMES.choose_multiple(rng, MONKEYS_PER_RACE).copied().collect::<Vec<&str>>();
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let monkeys = names.iter().map(|name| Monkey::random(name, rng)).collect::<Vec<Monkey>>();
+            let chances = estimate_chances(&monkeys, rng, ODDS_TRIALS);
+            if attempts < MAX_FIELD_REROLLS && chances.iter().any(|c| *c > MAX_FAVOURITE_CHANCE) {
+                continue;
+            }
+            let odds = chances.into_iter().map(odds_for).collect();
+            return Self { channel, monkeys, odds, bets: Vec::new(), closes_at };
+        }
+    }
+
+    pub fn place_bet(&mut self, user: UserId, monkey: usize, stake: u64) {
+        self.bets.push(RaceBet { user, monkey, stake });
+    }
+
+    fn lane(&self, index: usize) -> String {
+        format!("{} **{}. {}**", EMOJIS[index % EMOJIS.len()], index + 1, self.monkeys[index].name)
+    }
+
+    fn bet_list(&self) -> String {
+        if self.bets.is_empty() {
+            return "No bets yet".to_string();
+        }
+        self.bets.iter()
+            .map(|b| format!("{} - {} on {}", b.user.mention(), b.stake, self.monkeys[b.monkey].name))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    pub fn craft_embed<S: Into<String>>(&self, message: S) -> CreateEmbed {
+        let field = (0..self.monkeys.len()).map(|i| {
+            let monkey = &self.monkeys[i];
+            format!("{} - odds **{:.2}x**\nSpeed {} | Burst {} | Stamina {}", self.lane(i), self.odds[i], monkey.speed, monkey.burst, monkey.stamina)
+        }).collect::<Vec<String>>().join("\n");
+
+        CreateEmbed::new()
+            .title("Monkey Race")
+            .description(message)
+            .color(Colour::ORANGE)
+            .field("Runners", field, false)
+            .field("Race starts", format!("<t:{}:R>", self.closes_at), true)
+            .field("Bets", self.bet_list(), false)
+            .timestamp(Timestamp::now())
+            .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"))
+    }
+
+    // progress bars for one tick of the race
+    pub fn frame_embed(&self, positions: &[u32]) -> CreateEmbed {
+        let track = (0..self.monkeys.len()).map(|i| {
+            let filled = (positions[i].min(TRACK_LENGTH) * BAR_LENGTH / TRACK_LENGTH) as usize;
+            format!("{}\n`{}{}` :checkered_flag:", self.lane(i), "█".repeat(filled), "░".repeat(BAR_LENGTH as usize - filled))
+        }).collect::<Vec<String>>().join("\n");
+
+        CreateEmbed::new()
+            .title("Monkey Race")
+            .description(track)
+            .color(Colour::ORANGE)
+            .timestamp(Timestamp::now())
+            .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"))
+    }
+
+    pub fn result_embed(&self, positions: &[u32], winner: usize, results: &[String]) -> CreateEmbed {
+        self.frame_embed(positions)
+            .title(format!("{} wins the race!", self.monkeys[winner].name))
+            .color(Colour::GOLD)
+            .field("Results", if results.is_empty() { "No bets were placed".to_string() } else { results.join("\n") }, false)
+    }
+
+    // bets are placed with /monkey_race, players can check the runners while they wait
+    pub fn handle_message(&self, msg: &Message) -> Option<CreateEmbed> {
+        let content = msg.content.as_str().to_lowercase();
+        match content.split_whitespace().next()? {
+            "bets" | "odds" | "runners" => Some(self.craft_embed("Waiting for the race to start")),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn odds_keep_the_house_edge() {
+        assert_eq!(odds_for(0.5), 1.8);
+        assert_eq!(odds_for(0.95), MIN_ODDS);
+        assert_eq!(odds_for(0.001), MAX_ODDS);
+        assert_eq!(payout(100, 1.8, false), 180);
+        assert_eq!(payout(100, 1.8, true), 100 + 80 * SUPERBOOST);
+    }
+
+    #[test]
+    fn posted_odds_return_what_the_edge_promises() {
+        for seed in 0..3 {
+            let race = MonkeyRace::new(ChannelId::new(1), 0, &mut GameRng::from_seed(seed));
+            assert_eq!(race.monkeys.len(), MONKEYS_PER_RACE);
+            assert!(race.odds.iter().all(|o| *o > MIN_ODDS), "seed {} posted {:?}", seed, race.odds);
+
+            // race the same field again with fresh randomness and pay every monkey's backers
+            let races = 10000;
+            let mut returned = vec![0.0; race.monkeys.len()];
+            let mut rng = GameRng::from_seed(seed + 100);
+            for _ in 0..races {
+                let winner = simulate(&race.monkeys, &mut rng).winner;
+                returned[winner] += race.odds[winner];
+            }
+
+            for (i, total) in returned.iter().enumerate() {
+                let rtp = total / races as f64;
+                // long shots pay out in big lumps, so their return is noisier
+                let tolerance = 0.06 + 0.004 * race.odds[i];
+                assert!(rtp <= 1.0 - HOUSE_EDGE + tolerance, "seed {} monkey {} returned {:.3} at {:.2}x", seed, i, rtp, race.odds[i]);
+            }
+
+            // a bet spread across the whole field returns about 90%
+            let spread = returned.iter().sum::<f64>() / (races * race.monkeys.len()) as f64;
+            assert!((spread - (1.0 - HOUSE_EDGE)).abs() < 0.05, "seed {} spread returned {:.3}", seed, spread);
+        }
+    }
+}
@@ -1,21 +1,29 @@
 use std::ops::RangeInclusive;
-use rand::Rng;
-use serenity::all::{Colour, Context, CreateEmbed, Message, UserId};
+use serenity::all::{ChannelId, Colour, Context, CreateEmbed, Mentionable, Message, UserId};
 use serenity::builder::CreateEmbedFooter;
+use crate::achievements::{self, Stat};
+use crate::clans::Clan;
+use crate::games::combat::{self, Action, BattleLog, Combatant, Outcome, Stats};
+use crate::games::rng::GameRng;
 use crate::inventory::item::InventoryItem;
 use crate::userfile::UserValues;
 
+const ARENA_OPTIONS: &str = "You can type `attack @player`, `item #`, `list` or `surrender`";
+
 pub enum PvPModFlag {
     NoItem,
     MaxPlayers(u64),
     CustomStartHealth(u32),
     CustomMaxHealth(u32),
     DamageRange(u32, u32),
+    // players fight for their clans and the pot goes to the winning clan's bank
+    ClanWar,
 }
 
 struct Player {
     user: UserId,
-    health: u32,
+    combatant: Combatant,
+    clan: Option<String>,
 }
 
 pub struct PvPArena {
@@ -31,8 +39,10 @@ pub struct PvPArena {
     base_health: u32,
     damage_range: RangeInclusive<u32>,
     enable_items: bool,
+    clan_war: bool,
 
     total_players: u64,
+    log: BattleLog,
 }
 
 impl PvPArena {
@@ -43,6 +53,7 @@ impl PvPArena {
         let mut max_health = 100;
         let mut base_health = 100;
         let mut damage_range: RangeInclusive<u32> = 0..=10;
+        let mut clan_war = false;
 
         for flag in flags {
             match flag {
@@ -51,13 +62,14 @@ impl PvPArena {
                 PvPModFlag::CustomStartHealth(health) => base_health = health,
                 PvPModFlag::CustomMaxHealth(health) => max_health = health,
                 PvPModFlag::DamageRange(min, max) => damage_range = min..=max,
+                PvPModFlag::ClanWar => clan_war = true,
             }
         }
 
-        Self {
+        let mut arena = Self {
             stake,
-            host: host.clone(),
-            players: vec![Player { user: host, health: 100 }],
+            host,
+            players: Vec::new(),
             turn: 0,
             started: false,
 
@@ -66,23 +78,40 @@ impl PvPArena {
             base_health,
             damage_range,
             enable_items,
+            clan_war,
 
-            total_players: 1,
-        }
+            total_players: 0,
+            log: BattleLog::default(),
+        };
+        arena.add_player(host);
+        arena
     }
 
     pub fn add_player(&mut self, user: UserId) {
+        let stats = Stats { max_health: Some(self.max_health), damage: self.damage_range.clone() };
+        let clan = if self.clan_war { UserValues::get(&user).get_clan() } else { None };
         self.players.push(Player {
             user,
-            health: self.base_health as u32,
+            combatant: Combatant::new(user.mention().to_string(), self.base_health, stats),
+            clan,
         });
 
         self.total_players += 1;
     }
 
     pub fn remove_player(&mut self, user: UserId) {
-        let index = self.players.iter().position(|p| p.user == user).unwrap();
+        let Some(index) = self.players.iter().position(|p| p.user == user) else {
+            return;
+        };
         self.players.remove(index);
+
+        // keep the turn on the same player
+        if index < self.turn as usize {
+            self.turn -= 1;
+        }
+        if self.turn as usize >= self.players.len() {
+            self.turn = 0;
+        }
     }
 
     pub fn is_host(&self, user: UserId) -> bool {
@@ -93,6 +122,27 @@ impl PvPArena {
         self.started
     }
 
+    pub fn is_clan_war(&self) -> bool {
+        self.clan_war
+    }
+
+    // in a clan war clanmates are on the same side
+    fn same_side(&self, a: UserId, b: UserId) -> bool {
+        if a == b {
+            return true;
+        }
+        if !self.clan_war {
+            return false;
+        }
+        let clan = |user: UserId| self.players.iter().find(|p| p.user == user).and_then(|p| p.clan.as_ref());
+        clan(a).is_some() && clan(a) == clan(b)
+    }
+
+    // true once everyone left, other than the defeated player, is on the user's side
+    fn is_last_side(&self, user: UserId, defeated: UserId) -> bool {
+        self.players.iter().filter(|p| p.user != defeated).all(|p| self.same_side(p.user, user))
+    }
+
     pub fn can_join(&self) -> bool {
         self.players.len() < self.max_players as usize && !self.is_running()
     }
@@ -104,7 +154,6 @@ impl PvPArena {
         }
     }
 
-    // todo: make list show health for all players
     pub async fn list_players(&self, ctx: &Context) -> Vec<String> {
         // get the player names
         let mut names = Vec::new();
@@ -115,7 +164,7 @@ impl PvPArena {
             } else {
                 "unknown".to_string()
             };
-            names.push(name);
+            names.push(if self.started { format!("{} - {}hp", name, player.combatant.health) } else { name });
         }
 
         names
@@ -143,6 +192,14 @@ impl PvPArena {
                         false, None));
                 }
 
+                // a clan war needs at least 2 clans
+                if self.is_last_side(self.host, self.host) {
+                    return Some((CreateEmbed::default()
+                        .title("Not enough clans to start the war.")
+                        .description("Players from at least 2 clans must join to start the war."),
+                        false, None));
+                }
+
                 // start the game
                 self.started = true;
 
@@ -151,7 +208,7 @@ impl PvPArena {
 
                 Some((CreateEmbed::default()
                           .title("The arena has started!")
-                          .description(format!("It is {}'s turn to perform an action.\n  Health: {}", user, self.players[self.turn as usize].health))
+                          .description(format!("It is {}'s turn to perform an action.\n  Health: {}", user, self.players[self.turn as usize].combatant.health))
                           .thumbnail("attachment://battle_monkey.jpeg")
                           .color(Colour::RED)
                           .footer(CreateEmbedFooter::new("You can type `attack @player`, `item #`, `list` or `surrender`")),
@@ -208,14 +265,35 @@ impl PvPArena {
         }
     }
 
-    async fn handle_win(&mut self, ctx: &Context, winner_id: UserId) -> (CreateEmbed, bool, Option<UserId>) {
+    async fn handle_win(&mut self, ctx: &Context, channel: ChannelId, winner_id: UserId) -> (CreateEmbed, bool, Option<UserId>) {
         let winner = winner_id.to_user(&ctx.http).await.unwrap().clone().global_name.unwrap_or("unknown".to_string());
 
         let pot = self.stake * self.total_players;
 
+        // the clan's bank takes the pot and every clanmate still standing gets the win
+        let clan = self.players.iter().find(|p| p.user == winner_id).and_then(|p| p.clan.clone());
+        if let Some(mut clan) = clan.filter(|_| self.clan_war).and_then(|tag| Clan::get(&tag)) {
+            clan.add_war_win(pot);
+            // unlocks are announced here, only the one who sent the message is announced after it
+            for player in self.players.iter().filter(|p| self.same_side(p.user, winner_id)) {
+                UserValues::get(&player.user).add_progress(Stat::PvPWins, 1);
+                achievements::announce(&ctx.http, player.user, channel).await;
+            }
+
+            return (CreateEmbed::default()
+                        .title(format!("[{}] {} has won the war!", clan.tag, clan.file.name))
+                        .color(Colour::RED)
+                        .thumbnail("attachment://battle_monkey.jpeg")
+                        .description(format!("{} landed the final blow and {}:banana: goes to the clan bank!", winner, pot)),
+                    true, None);
+        }
+
         // add the pot to the user
         let mut userfile = UserValues::get(&winner_id);
         userfile.add_bananas(pot);
+        userfile.add_progress(Stat::PvPWins, 1);
+        // the winner may not be who sent the message, like when the last opponent surrenders
+        achievements::announce(&ctx.http, winner_id, channel).await;
 
         (CreateEmbed::default()
                          .title(format!("{} has won the arena!", winner.clone()))
@@ -225,395 +303,213 @@ impl PvPArena {
                      true, None)
     }
 
-    pub async fn handle_message(&mut self, ctx: &Context, user: UserId, msg: &Message) -> Option<(CreateEmbed, bool, Option<UserId>)> {
-        let content = msg.content.as_str().to_lowercase();
-        let mut split = content.split_whitespace();
-        let first = split.next().unwrap();
-
-        // determine if it is the user's turn
-        let is_turn = self.players[self.turn as usize].user == user;
+    // invalid actions don't use up the player's turn
+    fn undo_turn(&mut self) {
+        if self.turn > 0 {
+            self.turn -= 1;
+        } else {
+            self.turn = (self.players.len() - 1) as u16;
+        }
+    }
 
-        let last_2 = self.players.len() < 3;
+    fn error_embed(title: &str, description: &str) -> CreateEmbed {
+        CreateEmbed::default()
+            .title(title)
+            .color(Colour::RED)
+            .thumbnail("attachment://battle_monkey.jpeg")
+            .description(description)
+            .footer(CreateEmbedFooter::new(ARENA_OPTIONS))
+    }
 
-        if is_turn {
-            self.next_turn();
+    // with more than 2 players the target must be mentioned
+    fn find_target(&self, user: UserId, msg: &Message) -> Result<UserId, (&'static str, &'static str)> {
+        if self.players.len() <= 2 {
+            return Ok(self.players.iter().find(|p| p.user != user).unwrap().user);
         }
 
-        let next_turn_name = self.players[self.turn as usize].user.to_user(&ctx.http).await.unwrap().clone().global_name.unwrap_or("unknown".to_string()).clone();
+        let Some(target) = msg.mentions.first() else {
+            return Err(("You must specify a player to attack.", "You must mention a player to attack. (`attack @player` or `item # @player`)"));
+        };
 
-        let end = match first {
-            "attack" => {
-                // ensure it is the user's turn
-                if !is_turn {
-                    return Some((CreateEmbed::default()
-                        .title("It is not your turn.")
-                        .color(Colour::RED)
-                        .thumbnail("attachment://battle_monkey.jpeg")
-                        .description("Wait for your turn to attack."),
-                        false, None));
-                }
+        if !self.players.iter().any(|p| p.user == target.id && p.user != user) {
+            return Err(("Player not found.", "The player you are trying to attack is not in the game."));
+        }
 
-                // if player count is more than 2, user must specify which player to attack
-                let target = if self.players.len() > 2 {
-                    // check if the msg contains a mention
-                    if msg.mentions.len() == 0 {
-                        if self.turn > 0 {
-                            self.turn -= 1;
-                        } else {
-                            self.turn = (self.players.len() - 1) as u16;
-                        }
-                        return Some((CreateEmbed::default()
-                            .title("You must specify a player to attack.")
-                            .color(Colour::RED)
-                            .thumbnail("attachment://battle_monkey.jpeg")
-                            .description("You must mention a player to attack.")
-                            .footer(CreateEmbedFooter::new("You can type `attack @player`, `item #`, `list` or `surrender`")),
-                            false, None));
-                    }
-                    let target = msg.mentions[0].id;
+        if self.same_side(user, target.id) {
+            return Err(("That's your clanmate!", "You can't attack players from your own clan."));
+        }
 
-                    // ensure the target is in the game
-                    if !self.players.iter().any(|p| p.user == target) {
-                        if self.turn > 0 {
-                            self.turn -= 1;
-                        } else {
-                            self.turn = (self.players.len() - 1) as u16;
-                        }
-                        return Some((CreateEmbed::default()
-                            .title("Player not found.")
-                            .color(Colour::RED)
-                            .thumbnail("attachment://battle_monkey.jpeg")
-                            .description("The player you are trying to attack is not in the game.")
-                            .footer(CreateEmbedFooter::new("You can type `attack @player`, `item #`, `list` or `surrender`")),
-                            false, None));
-                    }
-                    target
-                } else {
-                    // get the other player
-                    self.players.iter().find(|p| p.user != user).unwrap().user
-                };
+        Ok(target.id)
+    }
 
-                let target = self.players.iter_mut().find(|p| p.user == target).unwrap();
+    // resolves an attack or spell from one player against another
+    fn strike(&mut self, user: UserId, target: UserId, action: Action, rng: &mut GameRng) -> Outcome {
+        let attacker = self.players.iter().position(|p| p.user == user).unwrap();
+        let target = self.players.iter().position(|p| p.user == target).unwrap();
+
+        let (attacker, target) = if attacker < target {
+            let (left, right) = self.players.split_at_mut(target);
+            (&mut left[attacker].combatant, &mut right[0].combatant)
+        } else {
+            let (left, right) = self.players.split_at_mut(attacker);
+            (&mut right[0].combatant, &mut left[target].combatant)
+        };
 
-                // get the user's equipped item if they have one
-                let mut userfile = UserValues::get(&user);
+        combat::resolve(attacker, target, action, &mut self.log, rng)
+    }
 
-                if self.enable_items {
-                    let item = userfile.get_equiped();
-                    if let Some(InventoryItem::Weapon { name, damage, .. }) = item {
+    async fn name(ctx: &Context, user: UserId) -> String {
+        user.to_user(&ctx.http).await.map(|u| u.global_name.unwrap_or("unknown".to_string())).unwrap_or("unknown".to_string())
+    }
 
-                        // generate the damage
-                        let damage = rand::thread_rng().gen_range(damage);
+    // renders the result of an attack, the defeated player is removed from the arena
+    async fn handle_strike(&mut self, ctx: &Context, channel: ChannelId, user: UserId, target: UserId, outcome: Outcome) -> (CreateEmbed, bool, Option<UserId>) {
+        if outcome == Outcome::Victory && self.is_last_side(user, target) {
+            return self.handle_win(ctx, channel, user).await;
+        }
 
-                        // apply the damage to the target and return
-                        if damage > target.health {
-                            target.health = 0;
-                        } else {
-                            target.health -= damage;
-                        }
+        let title = if outcome == Outcome::Victory {
+            format!("{} has defeated {}!", Self::name(ctx, user).await, Self::name(ctx, target).await)
+        } else {
+            format!("{} has attacked {}!", Self::name(ctx, user).await, Self::name(ctx, target).await)
+        };
 
-                        let target_name = target.user.to_user(&ctx.http).await.unwrap().clone().global_name.unwrap_or("unknown".to_string());
-
-                        // todo: check if target is dead and handle if required
-                        if target.health == 0 {
-                            return if last_2 {
-                                Some(self.handle_win(ctx, user).await)
-                            } else {
-                                Some((CreateEmbed::default()
-                                          .title(format!("{} has defeated {} using {}!",
-                                                         user.to_user(&ctx.http).await.unwrap().clone().global_name.unwrap_or("unknown".to_string()),
-                                                         target.user.to_user(&ctx.http).await.unwrap().clone().global_name.unwrap_or("unknown".to_string()), name))
-                                          .description(format!("It is {}'s turn to perform an action.\n  Health: {}", next_turn_name, self.players[self.turn as usize].health))
-                                          .thumbnail("attachment://battle_monkey.jpeg")
-                                          .color(Colour::RED)
-                                          .footer(CreateEmbedFooter::new("You can type `attack @player`, `item #`, `list` or `surrender`")),
-                                      false, None))
-                            }
-                        }
+        let next = &self.players[self.turn as usize];
+        let description = format!("{}\n\nIt is {}'s turn to perform an action.\n  Health: {}",
+                                  self.log, Self::name(ctx, next.user).await, next.combatant.health);
 
-                        let next_health = self.players[self.turn as usize].health;
+        (CreateEmbed::default()
+             .title(title)
+             .description(description)
+             .thumbnail("attachment://battle_monkey.jpeg")
+             .color(Colour::RED)
+             .footer(CreateEmbedFooter::new(ARENA_OPTIONS)),
+         false, if outcome == Outcome::Victory { Some(target) } else { None })
+    }
 
-                        return Some((CreateEmbed::default()
-                                         .title(format!("{} has attacked {} for {} damage using {}!", user.to_user(&ctx.http).await.unwrap().clone().global_name.unwrap_or("unknown".to_string()),
-                                                        target_name, damage, name))
-                                         .description(format!("It is {}'s turn to perform an action.\n  Health: {}", next_turn_name, next_health))
-                                         .thumbnail("attachment://battle_monkey.jpeg")
-                                         .color(Colour::RED)
-                                         .footer(CreateEmbedFooter::new("You can type `attack @player`, `item #`, `list` or `surrender`")),
-                                     false, None));
-                    }
-                }
+    pub async fn handle_message(&mut self, ctx: &Context, user: UserId, msg: &Message, rng: &mut GameRng) -> Option<(CreateEmbed, bool, Option<UserId>)> {
+        let content = msg.content.as_str().to_lowercase();
+        let mut split = content.split_whitespace();
+        let first = split.next()?;
 
-                // generate the damage
-                let damage: u32 = rand::thread_rng().gen_range(self.damage_range.clone());
+        // determine if it is the user's turn
+        let is_turn = self.players[self.turn as usize].user == user;
 
-                // apply the damage to the target and return
-                if damage > target.health {
-                    target.health = 0;
-                } else {
-                    target.health -= damage;
-                }
+        if is_turn {
+            self.next_turn();
+        }
 
-                if target.health == 0 {
-                    return if last_2 {
-                        Some(self.handle_win(ctx, user).await)
-                    } else {
-                        let target_name = target.user.to_user(&ctx.http).await.unwrap().clone().global_name.unwrap_or("unknown".to_string());
-                        let target = target.user;
+        self.log.clear();
 
+        match first {
+            "attack" => {
+                // ensure it is the user's turn
+                if !is_turn {
+                    return Some((Self::error_embed("It is not your turn.", "Wait for your turn to attack."), false, None));
+                }
 
-                        Some((CreateEmbed::default()
-                                  .title(format!("{} has defeated {}!",
-                                                 user.to_user(&ctx.http).await.unwrap().clone().global_name.unwrap_or("unknown".to_string()),
-                                                 target_name))
-                                  .description(format!("It is {}'s turn to perform an action.\n  Health: {}", next_turn_name, self.players[self.turn as usize].health))
-                                  .thumbnail("attachment://battle_monkey.jpeg")
-                                  .color(Colour::RED)
-                                  .footer(CreateEmbedFooter::new("You can type `attack <@player>`, `item #`, `list` or `surrender`")),
-                              false, Some(target)))
+                let target = match self.find_target(user, msg) {
+                    Ok(target) => target,
+                    Err((title, description)) => {
+                        self.undo_turn();
+                        return Some((Self::error_embed(title, description), false, None));
                     }
-                }
+                };
 
-                
-                let target_name = target.user.to_user(&ctx.http).await.unwrap().clone().global_name.unwrap_or("unknown".to_string());
+                // use the user's equipped weapon if items are enabled
+                let action = match UserValues::get(&user).get_equiped() {
+                    Some(InventoryItem::Weapon { name, damage, .. }) if self.enable_items => Action::Attack { weapon: Some(damage), with: Some(name) },
+                    _ => Action::Attack { weapon: None, with: None },
+                };
 
-                Some((CreateEmbed::default()
-                                 .title(format!("{} has attacked {} for {} damage!", user.to_user(&ctx.http).await.unwrap().clone().global_name.unwrap_or("unknown".to_string()),
-                                                target_name, damage))
-                          .description(format!("It is {}'s turn to perform an action.\n  Health: {}", next_turn_name, self.players[self.turn as usize].health))
-                          .thumbnail("attachment://battle_monkey.jpeg")
-                          .color(Colour::RED)
-                          .footer(CreateEmbedFooter::new("You can type `attack @player`, `item #`, `list` or `surrender`")),
-                             false, None))
+                let outcome = self.strike(user, target, action, rng);
+                Some(self.handle_strike(ctx, msg.channel_id, user, target, outcome).await)
             }
             "item" => { // use an item
-                // users can use healing potions on their turns
-
                 // if items are disabled, return
                 if !self.enable_items {
                     if is_turn {
-                        if self.turn > 0 {
-                            self.turn -= 1;
-                        } else {
-                            self.turn = (self.players.len() - 1) as u16;
-                        }
+                        self.undo_turn();
                     }
-                    return Some((CreateEmbed::default()
-                                     .title("You can't do that here!")
-                                     .color(Colour::RED)
-                                     .thumbnail("attachment://battle_monkey.jpeg")
-                                     .description("Items are disabled in this arena.")
-                                     .footer(CreateEmbedFooter::new("You can type `attack @player`, `item #`, `list` or `surrender`")),
-                                 false, None));
+                    return Some((Self::error_embed("You can't do that here!", "Items are disabled in this arena."), false, None));
                 }
 
-                // get the item number
-                let Ok(slot) = split.next().unwrap().parse::<u8>() else {
-                    if is_turn {
-                        if self.turn > 0 {
-                            self.turn -= 1;
-                        } else {
-                            self.turn = (self.players.len() - 1) as u16;
-                        }
-                    }
-                    return Some((CreateEmbed::new()
-                                     .title("Invalid Item!")
-                                     .description("You must specify and item slot `item <slot #>`."),
-                                 false, None))
-                };
-
-                // get the item
+                // get the item (1 indexed slot)
                 let mut user_file = UserValues::get(&msg.author.id);
-                let items = user_file.get_items();
-
-                // 1 index slot
-                let slot = slot - 1;
-                
-                let Some(item) = items.get(slot as usize) else {
+                let slot = split.next().and_then(|s| s.parse::<usize>().ok()).and_then(|s| s.checked_sub(1));
+                let Some((slot, item)) = slot.and_then(|slot| user_file.get_items().get(slot).cloned().map(|item| (slot, item))) else {
                     if is_turn {
-                        if self.turn > 0 {
-                            self.turn -= 1;
-                        } else {
-                            self.turn = (self.players.len() - 1) as u16;
-                        }
+                        self.undo_turn();
                     }
-                    return Some((CreateEmbed::default()
-                                     .title("Invalid Item!")
-                                     .color(Colour::RED)
-                                     .thumbnail("attachment://battle_monkey.jpeg")
-                                     .description("You must specify a valid item slot.")
-                                     .footer(CreateEmbedFooter::new("You can type `attack @player`, `item #`, `list` or `surrender`")),
-                                 false, None))
+                    return Some((Self::error_embed("Invalid Item!", "You must specify a valid item slot `item <slot #>`."), false, None));
                 };
 
                 match item {
                     InventoryItem::HealingPotion { health } => {
-                        // apply the healing to the user
-
-                        // it will not count as their turn
+                        // users can heal at any time and it will not count as their turn
                         if is_turn {
-                            if self.turn > 0 {
-                                self.turn -= 1;
-                            } else {
-                                self.turn = (self.players.len() - 1) as u16;
-                            }
+                            self.undo_turn();
                         }
 
-                        // get the user from players (turn is already incremented)
-                        let current_player_loc = self.players.iter().position(|p| p.user == user).unwrap_or(0);
-                        let current_player = &mut self.players[current_player_loc];
-                        current_player.health += health;
-                        if current_player.health > self.max_health {
-                            current_player.health = self.max_health;
-                        }
-                        let current_player_name = current_player.user.to_user(&ctx.http).await.unwrap().clone().global_name.unwrap_or("unknown".to_string());
+                        let player = self.players.iter_mut().find(|p| p.user == user).unwrap();
+                        combat::heal(&mut player.combatant, health, &mut self.log);
+                        let current_health = player.combatant.health;
 
                         // remove the item from the user's inventory
-                        user_file.remove_item_index(slot as usize);
+                        user_file.remove_item_index(slot);
 
-                        if is_turn {
-                            Some((CreateEmbed::default()
-                                      .title(format!("{} has healed to {}hp!", current_player_name, health))
-                                      .color(Colour::RED)
-                                      .thumbnail("attachment://battle_monkey.jpeg")
-                                      .description(format!("It is still your turn to perform an action.\n  Health: {}", current_player.health))
-                                      .footer(CreateEmbedFooter::new("You can type `attack @player`, `item #`, `list` or `surrender`")),
-                                  false, None))
+                        let next = &self.players[self.turn as usize];
+                        let description = if is_turn {
+                            format!("{}\n\nIt is still your turn to perform an action.", self.log)
                         } else {
-                            let next_user = &self.players[(self.turn) as usize];
-                            let next_turn_name = next_user.user.to_user(&ctx.http).await.unwrap().clone().global_name.unwrap_or("unknown".to_string());
-                            let name = user.to_user(&ctx.http).await.unwrap().clone().global_name.unwrap_or("unknown".to_string());
-
-                            Some((CreateEmbed::default()
-                                      .title(format!("{} has healed to {}hp!", name, health))
-                                      .color(Colour::RED)
-                                      .thumbnail("attachment://battle_monkey.jpeg")
-                                      .description(format!("It is still {}'s to perform an action.\n  Health: {}", next_turn_name, next_user.health))
-                                      .footer(CreateEmbedFooter::new("You can type `attack @player`, `item #`, `list` or `surrender`")),
-                                  false, None))
-                        }
+                            format!("{}\n\nIt is still {}'s turn to perform an action.\n  Health: {}",
+                                    self.log, Self::name(ctx, next.user).await, next.combatant.health)
+                        };
+
+                        Some((CreateEmbed::default()
+                                  .title(format!("{} has healed to {}hp!", Self::name(ctx, user).await, current_health))
+                                  .color(Colour::RED)
+                                  .thumbnail("attachment://battle_monkey.jpeg")
+                                  .description(description)
+                                  .footer(CreateEmbedFooter::new(ARENA_OPTIONS)),
+                              false, None))
                     }
                     InventoryItem::SpellTome { name, damage } => {
-                        // users can not use spell tomes on their turns
+                        // users can only use spell tomes on their turns
                         if !is_turn {
-                            return Some((CreateEmbed::default()
-                                             .title("It is not your turn.")
-                                             .color(Colour::RED)
-                                             .thumbnail("attachment://battle_monkey.jpeg")
-                                             .description("Wait for your turn to attack."),
-                                         false, None));
+                            return Some((Self::error_embed("It is not your turn.", "Wait for your turn to attack."), false, None));
                         }
 
-                        // if player count is more than 2, user must specify which player to attack
-                        let target = if self.players.len() > 2 {
-                            // check if the msg contains a mention
-                            if msg.mentions.len() == 0 {
-                                if self.turn > 0 {
-                                    self.turn -= 1;
-                                } else {
-                                    self.turn = (self.players.len() - 1) as u16;
-                                }
-                                return Some((CreateEmbed::default()
-                                                 .title("You must specify a player to attack.")
-                                                 .color(Colour::RED)
-                                                 .thumbnail("attachment://battle_monkey.jpeg")
-                                                 .description("You must mention a player to attack. `item # @player`")
-                                                 .footer(CreateEmbedFooter::new("You can type `attack @player`, `item #`, `list` or `surrender`")),
-                                             false, None));
-                            }
-                            let target = msg.mentions[0].id;
-
-                            // ensure the target is in the game
-                            if !self.players.iter().any(|p| p.user == target) {
-                                if self.turn > 0 {
-                                    self.turn -= 1;
-                                } else {
-                                    self.turn = (self.players.len() - 1) as u16;
-                                }
-                                return Some((CreateEmbed::default()
-                                                 .title("Player not found.")
-                                                 .color(Colour::RED)
-                                                 .thumbnail("attachment://battle_monkey.jpeg")
-                                                 .description("The player you are trying to attack is not in the game.")
-                                                 .footer(CreateEmbedFooter::new("You can type `attack @player`, `item #`, `list` or `surrender`")),
-                                             false, None));
+                        let target = match self.find_target(user, msg) {
+                            Ok(target) => target,
+                            Err((title, description)) => {
+                                self.undo_turn();
+                                return Some((Self::error_embed(title, description), false, None));
                             }
-                            target
-                        } else {
-                            // get the other player
-                            self.players.iter().find(|p| p.user != user).unwrap().user
                         };
 
-                        let target = self.players.iter_mut().find(|p| p.user == target).unwrap();
-
-                        // apply the damage to the target and return
-
-                        let damage = rand::thread_rng().gen_range(damage.clone());
-
-                        if damage > target.health {
-                            target.health = 0;
-                        } else {
-                            target.health -= damage;
-                        }
-
                         // remove the item from the user's inventory
-                        user_file.remove_item_index(slot as usize);
-
-                        if target.health == 0 {
-                            return if last_2 {
-                                Some(self.handle_win(ctx, user).await)
-                            } else {
-                                Some((CreateEmbed::default()
-                                          .title(format!("{} has defeated {} using {} Tome!",
-                                                         user.to_user(&ctx.http).await.unwrap().clone().global_name.unwrap_or("unknown".to_string()),
-                                                         target.user.to_user(&ctx.http).await.unwrap().clone().global_name.unwrap_or("unknown".to_string()), name))
-                                          .description(format!("It is {}'s turn to perform an action.\n  Health: {}", next_turn_name, self.players[self.turn as usize].health))
-                                          .thumbnail("attachment://battle_monkey.jpeg")
-                                          .color(Colour::RED)
-                                          .footer(CreateEmbedFooter::new("You can type `attack @player`, `item #`, `list` or `surrender`")),
-                                      false, None))
-                            }
-                        }
+                        user_file.remove_item_index(slot);
 
-                        Some((CreateEmbed::default()
-                                     .title(format!("{} has attacked {} for {} damage using {} Tome!", user.to_user(&ctx.http).await.unwrap().clone().global_name.unwrap_or("unknown".to_string()),
-                                                    target.user.to_user(&ctx.http).await.unwrap().clone().global_name.unwrap_or("unknown".to_string()), damage, name))
-                                     .description(format!("It is {}'s turn to perform an action.\n  Health: {}", next_turn_name, self.players[self.turn as usize].health))
-                                     .thumbnail("attachment://battle_monkey.jpeg")
-                                     .color(Colour::RED)
-                                     .footer(CreateEmbedFooter::new("You can type `attack @player`, `item #`, `list` or `surrender`")),
-                                 false, None))
+                        let outcome = self.strike(user, target, Action::Spell { name, damage }, rng);
+                        Some(self.handle_strike(ctx, msg.channel_id, user, target, outcome).await)
                     }
                     _ => {
                         if is_turn {
-                            if self.turn > 0 {
-                                self.turn -= 1;
-                            } else {
-                                self.turn = (self.players.len() - 1) as u16;
-                            }
+                            self.undo_turn();
                         }
-                        Some((CreateEmbed::default()
-                                         .title("Invalid Item!")
-                                         .color(Colour::RED)
-                                         .thumbnail("attachment://battle_monkey.jpeg")
-                                         .description("You cannot use that item here!")
-                                         .footer(CreateEmbedFooter::new("You can type `attack @player`, `item #`, `list` or `surrender`")),
-                                     false, None))
+                        Some((Self::error_embed("Invalid Item!", "You cannot use that item here!"), false, None))
                     }
                 }
             }
             "list" => {
-                // get the player names
-                let names = self.list_players(ctx).await;
-
                 if is_turn {
-                    if self.turn > 0 {
-                        self.turn -= 1;
-                    } else {
-                        self.turn = (self.players.len() - 1) as u16;
-                    }
+                    self.undo_turn();
                 }
 
+                // get the player names
+                let names = self.list_players(ctx).await;
+
                 Some((CreateEmbed::default()
                           .title("Players in your arena")
                           .color(Colour::RED)
@@ -631,32 +527,26 @@ impl PvPArena {
                     }
                 }
 
-                // check if there is less than 2 players and determine win
-                if self.players.len() < 3 {
-                    let index = self.players.iter().position(|p| p.user != user).unwrap();
-                    return Some(self.handle_win(ctx, self.players[index].user.clone()).await);
+                // check if only one side is left and determine win
+                let index = self.players.iter().position(|p| p.user != user).unwrap();
+                if self.is_last_side(self.players[index].user, user) {
+                    return Some(self.handle_win(ctx, msg.channel_id, self.players[index].user).await);
                 }
 
                 Some((CreateEmbed::default()
-                          .title(format!("{} surrendered!", user.to_user(&ctx.http).await.unwrap().clone().global_name.unwrap_or("unknown".to_string())))
+                          .title(format!("{} surrendered!", Self::name(ctx, user).await))
                           .color(Colour::RED)
                           .thumbnail("attachment://battle_monkey.jpeg")
                           .description("You have forfeited the game and have been removed from the arena.")
-                          .footer(CreateEmbedFooter::new("You can type `attack`, `item`, or `surrender`")),
+                          .footer(CreateEmbedFooter::new(ARENA_OPTIONS)),
                       false, Some(user)))
             }
             _ => {
                 if is_turn {
-                    if self.turn > 0 {
-                        self.turn -= 1;
-                    } else {
-                        self.turn = (self.players.len() - 1) as u16;
-                    }
+                    self.undo_turn();
                 }
                 None
             }
-        };
-
-        end
+        }
     }
-}
\ No newline at end of file
+}
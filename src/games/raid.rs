@@ -0,0 +1,342 @@
+use rand::Rng;
+use serenity::all::{Colour, CreateEmbed, CreateEmbedFooter, Mentionable, Message, Timestamp, UserId};
+use crate::games::rng::GameRng;
+use crate::inventory::item::InventoryItem;
+use crate::mine_data::Enemy;
+use crate::userfile::UserValues;
+
+const RAID_START_HEALTH: u32 = 100;
+
+struct RaidMember {
+    user: UserId,
+    health: u32,
+    // potions can't heal past the health the member started with
+    max_health: u32,
+    damage_dealt: u64,
+}
+
+impl RaidMember {
+    fn new(user: UserId) -> Self {
+        let health = RAID_START_HEALTH + UserValues::get(&user).get_perks().bonus_health();
+        Self { user, health, max_health: health, damage_dealt: 0 }
+    }
+
+    fn is_alive(&self) -> bool {
+        self.health > 0
+    }
+}
+
+pub struct Raid {
+    pub tier: u8,
+    pub boss: Enemy,
+    host: UserId,
+    members: Vec<RaidMember>,
+    started: bool,
+    max_players: usize,
+    sludge_worth: u32,
+    pub boss_health: u32,
+    pub thumbnail: String,
+}
+
+impl Raid {
+
+    pub fn new(host: UserId, tier: u8, boss: Enemy, sludge_worth: u32, max_players: usize) -> Self {
+        Self {
+            tier,
+            thumbnail: boss.thumbnail.clone(),
+            boss,
+            host,
+            members: vec![RaidMember::new(host)],
+            started: false,
+            max_players,
+            sludge_worth,
+            boss_health: 0,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.started
+    }
+
+    pub fn can_join(&self) -> bool {
+        !self.started && self.members.len() < self.max_players
+    }
+
+    pub fn add_player(&mut self, user: UserId) {
+        self.members.push(RaidMember::new(user));
+    }
+
+    pub fn remove_player(&mut self, user: UserId) {
+        self.members.retain(|m| m.user != user);
+    }
+
+    // with reward scaling, the boss gets tougher (and richer) for every member of the party
+    fn party_scale(&self) -> u32 {
+        if self.boss.reward_scaling {
+            self.members.len() as u32
+        } else {
+            1
+        }
+    }
+
+    fn start(&mut self, rng: &mut GameRng) {
+        let health = rng.gen_range(self.boss.health.clone());
+        // convert to round 100 base number
+        let health = (health - (health % 100)).max(100) * self.party_scale();
+        self.boss_health = health;
+        self.started = true;
+    }
+
+    fn member_list(&self) -> String {
+        self.members.iter()
+            .map(|m| if self.started {
+                format!("{} - {}hp ({} damage)", m.user.mention(), m.health, m.damage_dealt)
+            } else {
+                format!("{}", m.user.mention())
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    pub fn craft_embed<S: Into<String>>(&self, message: S) -> CreateEmbed {
+        let options = if self.started {
+            "`attack`, `item {inventory slot #}`, `list` or `surrender`"
+        } else {
+            "The host can type `start` or `end`, others can `leave`"
+        };
+
+        CreateEmbed::new()
+            .title(format!("Tier {} Raid - {}", self.tier, self.boss.name))
+            .thumbnail(format!("attachment://{}", self.thumbnail))
+            .description(message)
+            .color(Colour::DARK_RED)
+            .field("Boss Health", format!("{}", self.boss_health), true)
+            .field("Party", self.member_list(), false)
+            .field("Options: ", options, false)
+            .timestamp(Timestamp::now())
+            .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"))
+    }
+
+    // the boss hits a random living member of the party
+    fn boss_turn(&mut self, rng: &mut GameRng) -> Option<(UserId, u32)> {
+        let alive = self.members.iter().filter(|m| m.is_alive()).count();
+        if alive == 0 {
+            return None;
+        }
+        let index = rng.gen_range(0..alive);
+        let target = self.members.iter_mut().filter(|m| m.is_alive()).nth(index).unwrap();
+        let damage = rng.gen_range(self.boss.damage.clone());
+        target.health = target.health.saturating_sub(damage);
+        Some((target.user, damage))
+    }
+
+    fn deal_damage(&mut self, user: UserId, damage: u32) -> u32 {
+        let damage = damage.min(self.boss_health);
+        self.boss_health -= damage;
+        if let Some(member) = self.members.iter_mut().find(|m| m.user == user) {
+            member.damage_dealt += damage as u64;
+        }
+        damage
+    }
+
+    // resolve the boss's counter attack after a member acts
+    fn after_action(&mut self, message: String, rng: &mut GameRng) -> (CreateEmbed, bool, Option<UserId>) {
+        if self.boss_health == 0 {
+            return (self.handle_win(rng), true, None);
+        }
+
+        let message = match self.boss_turn(rng) {
+            Some((target, damage)) => {
+                let died = !self.members.iter().any(|m| m.user == target && m.is_alive());
+                format!("{}\nThe {} attacked {} for {} damage!{}", message, self.boss.name, target.mention(), damage,
+                        if died { " They have fallen!" } else { "" })
+            }
+            None => message,
+        };
+
+        if self.members.iter().all(|m| !m.is_alive()) {
+            return (self.handle_wipe(), true, None);
+        }
+
+        (self.craft_embed(message), false, None)
+    }
+
+    // rewards are split between the members based on how much damage they dealt
+    fn handle_win(&self, rng: &mut GameRng) -> CreateEmbed {
+        let drops = &self.boss.drops;
+        let scale = self.party_scale();
+
+        let pool = self.sludge_worth as u64 * rng.gen_range(drops.sludge.clone()) as u64 * scale as u64;
+        let nanner_pool = drops.super_nanners.clone().map(|n| rng.gen_range(n) * scale).unwrap_or(0);
+        let parts_pool = drops.drill_parts.clone().map(|p| rng.gen_range(p) * scale).unwrap_or(0);
+        let total_damage = self.members.iter().map(|m| m.damage_dealt).sum::<u64>().max(1);
+
+        let mut shares = Vec::new();
+        for member in &self.members {
+            let share = member.damage_dealt as f64 / total_damage as f64;
+            let bananas = (pool as f64 * share) as u64;
+            let nanners = (nanner_pool as f64 * share).round() as u16;
+            let parts = (parts_pool as f64 * share).round() as u32;
+
+            let mut user_file = UserValues::get(&member.user);
+            user_file.add_bananas(bananas);
+            if nanners > 0 {
+                user_file.add_super_nanners(nanners);
+            }
+            if parts > 0 {
+                user_file.add_drill_parts(parts);
+            }
+
+            shares.push(format!("{} - {}:banana: {}:zap: {}:gear: ({:.0}%)", member.user.mention(), bananas, nanners, parts, share * 100.0));
+        }
+
+        let mut embed = CreateEmbed::new()
+            .title("Raid Victory!")
+            .thumbnail(format!("attachment://{}", self.thumbnail))
+            .description(format!("The party has defeated the {}! Loot has been split by damage dealt.", self.boss.name))
+            .field("Loot", shares.join("\n"), false)
+            .color(Colour::DARK_GREEN)
+            .timestamp(Timestamp::now())
+            .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"));
+
+        // the top damage dealer also gets an item
+        if let Some(mvp) = self.members.iter().max_by_key(|m| m.damage_dealt) {
+            let item = drops.random_item(rng);
+            let mut user_file = UserValues::get(&mvp.user);
+            if user_file.file.inventory.is_full() {
+                embed = embed.field("MVP Reward", format!("{} earned :x: {} but their inventory is full!", mvp.user.mention(), item), false);
+            } else {
+                user_file.add_item(item.clone());
+                embed = embed.field("MVP Reward", format!("{} earned {}", mvp.user.mention(), item), false);
+            }
+        }
+
+        embed
+    }
+
+    fn handle_wipe(&self) -> CreateEmbed {
+        CreateEmbed::new()
+            .title("Raid Failed!")
+            .thumbnail(format!("attachment://{}", self.thumbnail))
+            .description(format!("The {} has wiped out the entire party!", self.boss.name))
+            .field("Party", self.member_list(), false)
+            .color(Colour::RED)
+            .timestamp(Timestamp::now())
+            .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"))
+    }
+
+    pub fn handle_prestart_message(&mut self, msg: &Message, rng: &mut GameRng) -> Option<(CreateEmbed, bool, Option<UserId>)> {
+        let content = msg.content.as_str().to_lowercase();
+        let first = content.split_whitespace().next()?;
+        let user = msg.author.id;
+
+        match first {
+            "start" => {
+                if user != self.host {
+                    return None;
+                }
+                self.start(rng);
+                Some((self.craft_embed(format!("The {} has awoken! Attack it together!", self.boss.name)), false, None))
+            }
+            "end" => {
+                if user != self.host {
+                    return None;
+                }
+                Some((self.craft_embed("The raid has been cancelled."), true, None))
+            }
+            "list" => Some((self.craft_embed("Waiting for the host to start the raid."), false, None)),
+            "leave" => {
+                if user == self.host {
+                    return Some((self.craft_embed("The host can't leave, type `end` to cancel the raid."), false, None));
+                }
+                Some((self.craft_embed(format!("{} has left the raid.", user.mention())), false, Some(user)))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn handle_message(&mut self, msg: &Message, rng: &mut GameRng) -> Option<(CreateEmbed, bool, Option<UserId>)> {
+        let content = msg.content.as_str().to_lowercase();
+        let mut split = content.split_whitespace();
+        let first = split.next()?;
+        let user = msg.author.id;
+
+        let alive = self.members.iter().any(|m| m.user == user && m.is_alive());
+
+        match first {
+            "attack" => {
+                if !alive {
+                    return Some((self.craft_embed("You have fallen and can't fight anymore!"), false, None));
+                }
+
+                let equipped = UserValues::get(&user).get_equiped();
+                let damage = match &equipped {
+                    Some(InventoryItem::Weapon { damage, .. }) => rng.gen_range(damage.clone()),
+                    _ => rng.gen_range(0..=10),
+                };
+                let damage = self.deal_damage(user, damage);
+
+                let with = match equipped {
+                    Some(item @ InventoryItem::Weapon { .. }) => format!(" with their {}", item),
+                    _ => "".to_string(),
+                };
+                Some(self.after_action(format!("{} attacked the {}{} for {} damage!", user.mention(), self.boss.name, with, damage), rng))
+            }
+            "item" => {
+                if !alive {
+                    return Some((self.craft_embed("You have fallen and can't fight anymore!"), false, None));
+                }
+
+                let Some(Ok(slot)) = split.next().map(|s| s.parse::<usize>()) else {
+                    return Some((self.craft_embed("**INVALID ITEM** To use an item, type `item #`, where # is the slot in your inventory (see `/inventory`)"), false, None));
+                };
+
+                let mut user_file = UserValues::get(&user);
+                let Some(item) = slot.checked_sub(1).and_then(|s| user_file.get_items().get(s).cloned()) else {
+                    return Some((self.craft_embed("**INVALID ITEM** To use an item, type `item #`, where # is the slot in your inventory (see `/inventory`)"), false, None));
+                };
+
+                match item {
+                    InventoryItem::HealingPotion { health } => {
+                        user_file.remove_item_index(slot - 1);
+                        let mut healed = 0;
+                        if let Some(member) = self.members.iter_mut().find(|m| m.user == user) {
+                            healed = health.min(member.max_health.saturating_sub(member.health));
+                            member.health += healed;
+                        }
+                        Some(self.after_action(format!("{} healed for {}hp!", user.mention(), healed), rng))
+                    }
+                    InventoryItem::SpellTome { name, damage } => {
+                        if name.as_str() != "Developer Tome" {
+                            user_file.remove_item_index(slot - 1);
+                        }
+                        let damage = rng.gen_range(damage);
+                        let damage = self.deal_damage(user, damage);
+                        Some(self.after_action(format!("{} used their {} Tome and dealt {} damage!", user.mention(), name, damage), rng))
+                    }
+                    _ => Some((self.craft_embed("You can not use that item here!"), false, None)),
+                }
+            }
+            "list" => Some((self.craft_embed("The party's status:"), false, None)),
+            "surrender" => {
+                // the last member standing surrendering ends the raid
+                if self.members.len() < 2 {
+                    return Some((self.handle_wipe(), true, None));
+                }
+                if user == self.host {
+                    // hand the raid over to someone else
+                    if let Some(member) = self.members.iter().find(|m| m.user != user) {
+                        self.host = member.user;
+                    }
+                }
+                self.remove_player(user);
+                if self.members.iter().all(|m| !m.is_alive()) {
+                    return Some((self.handle_wipe(), true, None));
+                }
+                Some((self.craft_embed(format!("{} fled the raid and forfeits their share of the loot.", user.mention())), false, Some(user)))
+            }
+            _ => None,
+        }
+    }
+
+}
@@ -0,0 +1,48 @@
+use rand::{random, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+// every game draws from one of these so a result can be replayed from its seed
+// chacha is used directly, StdRng's algorithm may change between rand versions and break old replays
+pub struct GameRng {
+    seed: u64,
+    rng: ChaCha20Rng,
+}
+
+impl GameRng {
+
+    pub fn new() -> Self {
+        Self::from_seed(random())
+    }
+
+    pub fn from_seed(seed: u64) -> Self {
+        Self { seed, rng: ChaCha20Rng::seed_from_u64(seed) }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
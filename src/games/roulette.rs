@@ -0,0 +1,315 @@
+use std::fmt::Display;
+use rand::Rng;
+use serenity::all::{ChannelId, Colour, CreateEmbed, CreateEmbedFooter, Mentionable, Message, Timestamp, UserId};
+use crate::games::rng::GameRng;
+use crate::SUPERBOOST;
+
+const RED: [u8; 18] = [1, 3, 5, 7, 9, 12, 14, 16, 18, 19, 21, 23, 25, 27, 30, 32, 34, 36];
+// the american wheel's extra pocket
+pub const DOUBLE_ZERO: u8 = 37;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Wheel {
+    European,
+    American,
+}
+
+impl Wheel {
+    fn pockets(&self) -> u8 {
+        match self {
+            Wheel::European => 37,
+            Wheel::American => 38,
+        }
+    }
+}
+
+impl Display for Wheel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Wheel::European => write!(f, "European"),
+            Wheel::American => write!(f, "American"),
+        }
+    }
+}
+
+fn pocket_name(pocket: u8) -> String {
+    if pocket == DOUBLE_ZERO { "00".to_string() } else { pocket.to_string() }
+}
+
+fn is_red(pocket: u8) -> bool {
+    RED.contains(&pocket)
+}
+
+fn pocket_display(pocket: u8) -> String {
+    let colour = match pocket {
+        0 | DOUBLE_ZERO => ":green_circle:",
+        p if is_red(p) => ":red_circle:",
+        _ => ":black_circle:",
+    };
+    format!("{} {}", colour, pocket_name(pocket))
+}
+
+// the row and column of a number on the betting layout
+fn layout(number: u8) -> (u8, u8) {
+    ((number - 1) / 3, (number - 1) % 3)
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum RouletteBet {
+    // inside bets, one number is a straight, two a split, three a street and four a corner
+    Numbers(Vec<u8>),
+    Red,
+    Black,
+    Odd,
+    Even,
+    Low,
+    High,
+    Dozen(u8),
+    Column(u8),
+}
+
+impl RouletteBet {
+
+    pub fn parse(spec: &str, wheel: Wheel) -> Result<Self, String> {
+        let spec = spec.trim().to_lowercase();
+        let bet = match spec.as_str() {
+            "red" => RouletteBet::Red,
+            "black" => RouletteBet::Black,
+            "odd" => RouletteBet::Odd,
+            "even" => RouletteBet::Even,
+            "low" | "1-18" => RouletteBet::Low,
+            "high" | "19-36" => RouletteBet::High,
+            "dozen1" | "1st12" => RouletteBet::Dozen(1),
+            "dozen2" | "2nd12" => RouletteBet::Dozen(2),
+            "dozen3" | "3rd12" => RouletteBet::Dozen(3),
+            "column1" => RouletteBet::Column(1),
+            "column2" => RouletteBet::Column(2),
+            "column3" => RouletteBet::Column(3),
+            _ => {
+                let mut numbers = Vec::new();
+                for part in spec.split([' ', '-', ',']).filter(|p| !p.is_empty()) {
+                    let number = match part {
+                        "00" if wheel == Wheel::American => DOUBLE_ZERO,
+                        "00" => return Err("Only the American wheel has a 00".to_string()),
+                        _ => match part.parse::<u8>() {
+                            Ok(n) if n <= 36 => n,
+                            _ => return Err(format!("Me don't know the bet `{}`", spec)),
+                        },
+                    };
+                    numbers.push(number);
+                }
+                numbers.sort();
+                numbers.dedup();
+                RouletteBet::Numbers(numbers)
+            }
+        };
+
+        if let RouletteBet::Numbers(numbers) = &bet {
+            if !Self::valid_inside(numbers) {
+                return Err("Inside bets must be 1 number, 2 side by side, a row of 3 or a square of 4".to_string());
+            }
+        }
+        Ok(bet)
+    }
+
+    // checks split, street and corner numbers sit together on the layout
+    fn valid_inside(numbers: &[u8]) -> bool {
+        if numbers.len() == 1 {
+            return true;
+        }
+        // the zeros can only be bet on straight up
+        if numbers.iter().any(|n| *n == 0 || *n == DOUBLE_ZERO) {
+            return false;
+        }
+        let cells = numbers.iter().map(|n| layout(*n)).collect::<Vec<(u8, u8)>>();
+        match cells.len() {
+            2 => {
+                let (a, b) = (cells[0], cells[1]);
+                (a.0 == b.0 && a.1.abs_diff(b.1) == 1) || (a.1 == b.1 && a.0.abs_diff(b.0) == 1)
+            }
+            3 => cells.iter().all(|c| c.0 == cells[0].0),
+            4 => {
+                let top = cells.iter().map(|c| c.0).min().unwrap();
+                let left = cells.iter().map(|c| c.1).min().unwrap();
+                [(top, left), (top, left + 1), (top + 1, left), (top + 1, left + 1)].iter().all(|c| cells.contains(c))
+            }
+            _ => false,
+        }
+    }
+
+    // paid to 1
+    pub fn payout(&self) -> u64 {
+        match self {
+            RouletteBet::Numbers(numbers) => 36 / numbers.len() as u64 - 1,
+            RouletteBet::Dozen(_) | RouletteBet::Column(_) => 2,
+            _ => 1,
+        }
+    }
+
+    pub fn wins(&self, pocket: u8) -> bool {
+        if let RouletteBet::Numbers(numbers) = self {
+            return numbers.contains(&pocket);
+        }
+        // every outside bet loses on the zeros
+        if pocket == 0 || pocket == DOUBLE_ZERO {
+            return false;
+        }
+        match self {
+            RouletteBet::Red => is_red(pocket),
+            RouletteBet::Black => !is_red(pocket),
+            RouletteBet::Odd => !pocket.is_multiple_of(2),
+            RouletteBet::Even => pocket.is_multiple_of(2),
+            RouletteBet::Low => pocket <= 18,
+            RouletteBet::High => pocket >= 19,
+            RouletteBet::Dozen(dozen) => (pocket - 1) / 12 + 1 == *dozen,
+            RouletteBet::Column(column) => (pocket - 1) % 3 + 1 == *column,
+            RouletteBet::Numbers(_) => unreachable!(),
+        }
+    }
+}
+
+impl Display for RouletteBet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RouletteBet::Numbers(numbers) => {
+                let kind = match numbers.len() {
+                    1 => "Straight",
+                    2 => "Split",
+                    3 => "Street",
+                    _ => "Corner",
+                };
+                write!(f, "{} {}", kind, numbers.iter().map(|n| pocket_name(*n)).collect::<Vec<String>>().join("-"))
+            }
+            RouletteBet::Red => write!(f, "Red"),
+            RouletteBet::Black => write!(f, "Black"),
+            RouletteBet::Odd => write!(f, "Odd"),
+            RouletteBet::Even => write!(f, "Even"),
+            RouletteBet::Low => write!(f, "1-18"),
+            RouletteBet::High => write!(f, "19-36"),
+            RouletteBet::Dozen(dozen) => write!(f, "Dozen {}", dozen),
+            RouletteBet::Column(column) => write!(f, "Column {}", column),
+        }
+    }
+}
+
+pub struct PlacedBet {
+    pub user: UserId,
+    pub bet: RouletteBet,
+    pub stake: u64,
+}
+
+impl PlacedBet {
+    // bananas returned to the player, the stake has already been taken
+    pub fn returned(&self, pocket: u8, superboost: bool) -> u64 {
+        if !self.bet.wins(pocket) {
+            return 0;
+        }
+        let mut winnings = self.stake * self.bet.payout();
+        if superboost {
+            winnings *= SUPERBOOST;
+        }
+        self.stake + winnings
+    }
+}
+
+// one spin of the wheel, everyone in the channel can bet on it until betting closes
+pub struct Roulette {
+    pub wheel: Wheel,
+    pub channel: ChannelId,
+    pub bets: Vec<PlacedBet>,
+    pub closes_at: i64,
+}
+
+impl Roulette {
+
+    pub fn new(wheel: Wheel, channel: ChannelId, closes_at: i64) -> Self {
+        Self { wheel, channel, bets: Vec::new(), closes_at }
+    }
+
+    pub fn place_bet(&mut self, user: UserId, bet: RouletteBet, stake: u64) {
+        self.bets.push(PlacedBet { user, bet, stake });
+    }
+
+    pub fn spin(&self, rng: &mut GameRng) -> u8 {
+        rng.gen_range(0..self.wheel.pockets())
+    }
+
+    fn bet_list(&self) -> String {
+        if self.bets.is_empty() {
+            return "No bets yet".to_string();
+        }
+        self.bets.iter()
+            .map(|b| format!("{} - {} on {}", b.user.mention(), b.stake, b.bet))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    pub fn craft_embed<S: Into<String>>(&self, message: S) -> CreateEmbed {
+        CreateEmbed::new()
+            .title(format!("{} Roulette", self.wheel))
+            .description(message)
+            .color(Colour::DARK_GREEN)
+            .field("Betting closes", format!("<t:{}:R>", self.closes_at), true)
+            .field("Bets", self.bet_list(), false)
+            .field("How to bet", "`/roulette` with a number, 2 to 4 numbers like `17-20`, `red`, `black`, `odd`, `even`, `low`, `high`, `dozen1-3` or `column1-3`", false)
+            .timestamp(Timestamp::now())
+            .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"))
+    }
+
+    pub fn result_embed(&self, pocket: u8, results: &[String]) -> CreateEmbed {
+        CreateEmbed::new()
+            .title(format!("{} Roulette", self.wheel))
+            .description(format!("The ball lands on **{}**", pocket_display(pocket)))
+            .color(Colour::DARK_GREEN)
+            .field("Results", if results.is_empty() { "No bets were placed".to_string() } else { results.join("\n") }, false)
+            .timestamp(Timestamp::now())
+            .footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"))
+    }
+
+    // bets are placed with /roulette, players can check the table while they wait
+    pub fn handle_message(&self, msg: &Message) -> Option<CreateEmbed> {
+        let content = msg.content.as_str().to_lowercase();
+        match content.split_whitespace().next()? {
+            "bets" | "list" => Some(self.craft_embed("Waiting for the wheel to spin")),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inside_bets_must_sit_together() {
+        assert_eq!(RouletteBet::parse("17-20", Wheel::European), Ok(RouletteBet::Numbers(vec![17, 20])));
+        assert_eq!(RouletteBet::parse("13 14 15", Wheel::European), Ok(RouletteBet::Numbers(vec![13, 14, 15])));
+        assert_eq!(RouletteBet::parse("1,2,4,5", Wheel::European), Ok(RouletteBet::Numbers(vec![1, 2, 4, 5])));
+        assert!(RouletteBet::parse("3-4", Wheel::European).is_err());
+        assert!(RouletteBet::parse("1 2 5", Wheel::European).is_err());
+        assert!(RouletteBet::parse("00", Wheel::European).is_err());
+        assert!(RouletteBet::parse("00", Wheel::American).is_ok());
+    }
+
+    #[test]
+    fn bets_pay_their_odds() {
+        let bet = |spec: &str, stake: u64| PlacedBet { user: UserId::new(1), bet: RouletteBet::parse(spec, Wheel::American).unwrap(), stake };
+        assert_eq!(bet("17", 100).returned(17, false), 3600);
+        assert_eq!(bet("17-20", 100).returned(20, false), 1800);
+        assert_eq!(bet("13-14-15", 100).returned(14, false), 1200);
+        assert_eq!(bet("1-2-4-5", 100).returned(5, false), 900);
+        assert_eq!(bet("dozen2", 100).returned(24, false), 300);
+        assert_eq!(bet("column3", 100).returned(36, false), 300);
+        assert_eq!(bet("red", 100).returned(1, false), 200);
+        assert_eq!(bet("red", 100).returned(1, true), 100 + 100 * SUPERBOOST);
+    }
+
+    #[test]
+    fn outside_bets_lose_on_zero() {
+        for spec in ["red", "black", "odd", "even", "low", "high", "dozen1", "column1"] {
+            let bet = RouletteBet::parse(spec, Wheel::American).unwrap();
+            assert!(!bet.wins(0), "{}", spec);
+            assert!(!bet.wins(DOUBLE_ZERO), "{}", spec);
+        }
+    }
+}
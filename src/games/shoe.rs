@@ -0,0 +1,91 @@
+use rand::RngCore;
+use crate::games::{Card, Deck};
+use crate::games::rng::GameRng;
+
+// a blackjack shoe that lasts across rounds, once the cut card comes out it is reshuffled between rounds
+pub struct Shoe {
+    pub(super) deck: Deck,
+    decks: u8,
+    // how far into the shoe the cut card sits, from 0.0 to 1.0
+    penetration: f32,
+    cut_card: usize,
+    // reshuffles draw from here so a whole table replays from the game's seed
+    rng: GameRng,
+}
+
+impl Shoe {
+    pub fn new(decks: u8, penetration: f32, rng: &mut GameRng) -> Self {
+        let decks = decks.max(1);
+        let mut deck = Deck::new(decks, false);
+        deck.shuffle(rng);
+
+        let mut shoe = Self {
+            deck,
+            decks,
+            penetration,
+            cut_card: 0,
+            rng: GameRng::from_seed(rng.next_u64()),
+        };
+        shoe.place_cut_card();
+        shoe
+    }
+
+    fn place_cut_card(&mut self) {
+        self.cut_card = (self.deck.cards.len() as f32 * (1.0 - self.penetration.clamp(0.0, 1.0))) as usize;
+    }
+
+    pub fn reshuffle(&mut self) {
+        self.deck = Deck::new(self.decks, false);
+        self.deck.shuffle(&mut self.rng);
+        self.place_cut_card();
+    }
+
+    // true once the cut card has been dealt
+    pub fn needs_reshuffle(&self) -> bool {
+        self.deck.cards.len() <= self.cut_card
+    }
+
+    // an empty shoe is reshuffled mid round rather than running out
+    pub fn deal(&mut self) -> Card {
+        if let Some(card) = self.deck.deal() {
+            return card;
+        }
+        self.reshuffle();
+        self.deck.deal().expect("a fresh shoe always has cards")
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.deck.cards.len()
+    }
+
+    pub fn peek(&self, count: usize) -> Vec<Card> {
+        self.deck.peek(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cut_card_triggers_a_reshuffle() {
+        let mut shoe = Shoe::new(1, 0.5, &mut GameRng::from_seed(0));
+        for _ in 0..25 {
+            shoe.deal();
+        }
+        assert!(!shoe.needs_reshuffle());
+        shoe.deal();
+        assert!(shoe.needs_reshuffle());
+        shoe.reshuffle();
+        assert_eq!(shoe.remaining(), 52);
+    }
+
+    #[test]
+    fn an_empty_shoe_reshuffles_instead_of_running_out() {
+        let mut shoe = Shoe::new(1, 0.75, &mut GameRng::from_seed(0));
+        for _ in 0..200 {
+            shoe.deal();
+        }
+        assert!(shoe.remaining() < 52);
+    }
+}
@@ -1,22 +1,26 @@
-use rand::{Rng, thread_rng};
+use rand::Rng;
 use serenity::all::{Colour, CreateEmbed, CreateEmbedFooter, Message, Timestamp, UserId};
+use crate::achievements::Stat;
+use crate::games::combat::{self, Action, BattleLog, Combatant, Outcome, Stats};
+use crate::games::rng::GameRng;
 use crate::userfile::UserValues;
 
 pub struct SludgeMonsterBattle {
-    pub boss_health: u32,
-    pub player_health: u32,
+    pub boss: Combatant,
+    pub player: Combatant,
     pub thumbnail: String,
-    pub initial_health: u32
+    pub initial_health: u32,
+    pub log: BattleLog,
 }
 
 impl SludgeMonsterBattle {
 
-    pub fn new() -> Self {
+    pub fn new(rng: &mut GameRng) -> Self {
         // create a new sludge monster battle
-        let boss_health = thread_rng().gen_range(1..=5) * 100;
+        let boss_health = rng.gen_range(1..=5) * 100;
         Self {
-            boss_health,
-            player_health: 100,
+            boss: Combatant::new("the sludge monster", boss_health, Stats { max_health: Some(boss_health), damage: 0..=10 }),
+            player: Combatant::new("you", 100, Stats { max_health: Some(100), damage: 0..=25 }),
             thumbnail: if boss_health > 400 {
                 "large_sludge.jpeg".to_string()
             } else if boss_health > 200 {
@@ -24,62 +28,41 @@ impl SludgeMonsterBattle {
             } else {
                 "small_sludge.jpeg".to_string()
             },
-            initial_health: boss_health
+            initial_health: boss_health,
+            log: BattleLog::default(),
         }
     }
 
-    pub fn attack(&mut self) -> u32 {
-        // calculate damage between 0 and 25 hp
-        let damage = thread_rng().gen_range(0..=25);
-        if damage > self.boss_health {
-            self.boss_health = 0;
-            return damage;
-        }
-        self.boss_health -= damage;
-        damage
+    pub fn attack(&mut self, rng: &mut GameRng) -> Outcome {
+        combat::resolve(&mut self.player, &mut self.boss, Action::Attack { weapon: None, with: None }, &mut self.log, rng)
     }
 
-    pub fn attempt_flee(&mut self) -> bool {
-        // calculate if the user flees based on health
-        thread_rng().gen_range(0..self.player_health) == 0
-    }
-
-    pub fn boss_turn(&mut self) -> u32 {
-        // calculate damage between 0 and 25 hp
-        let damage = thread_rng().gen_range(0..=10);
-        if damage > self.player_health {
-            self.player_health = 0;
-            return damage;
-        }
-        self.player_health -= damage;
-        damage
+    pub fn boss_turn(&mut self, rng: &mut GameRng) -> Outcome {
+        combat::resolve(&mut self.boss, &mut self.player, Action::Attack { weapon: None, with: None }, &mut self.log, rng)
     }
 
     pub fn heal_player(&mut self, amt: u32) {
-        // heal the player for 25 hp
-        self.player_health += amt;
-        if self.player_health > 100 {
-            self.player_health = 100;
-        }
+        combat::heal(&mut self.player, amt, &mut self.log);
     }
 
     pub fn craft_embed(&self, message: String) -> CreateEmbed {
-        // create an embed message for the battle
+        let message = if self.log.is_empty() { message } else { self.log.to_string() };
+
         CreateEmbed::new()
             .title("Sludge Monster Battle")
             .description(message)
             .color(Colour::DARK_GREEN)
             .fields(
                 vec![
-                    ("Boss Health", format!("{}", self.boss_health), true),
-                    ("Your Health", self.player_health.to_string(), true),
+                    ("Boss Health", format!("{}", self.boss.health), true),
+                    ("Your Health", self.player.health.to_string(), true),
                     ("Options: ", "`attack` `run` or `surrender`".to_string(), false),
                 ]
             )
     }
 
-    fn handle_player_death(&self, user: UserId) -> (CreateEmbed, bool) {
-        let cost  = thread_rng().gen_range(1..=20) * 100;
+    fn handle_player_death(&self, user: UserId, rng: &mut GameRng) -> (CreateEmbed, bool) {
+        let cost  = rng.gen_range(1..=20) * 100;
 
         let mut user_file = UserValues::get(&user);
 
@@ -100,12 +83,14 @@ impl SludgeMonsterBattle {
                 true)
     }
 
-    pub fn handle_message(&mut self, msg: &Message) -> (CreateEmbed, bool) {
+    pub fn handle_message(&mut self, msg: &Message, rng: &mut GameRng) -> (CreateEmbed, bool) {
         let content = msg.content.as_str().to_lowercase();
 
+        self.log.clear();
+
         match content.trim() {
             "attack" => {
-                let attack = self.attack();
+                let outcome = self.attack(rng);
 
                 /*
                     SLUDGE Monster REWARDS:
@@ -116,7 +101,7 @@ impl SludgeMonsterBattle {
                     100 HP: 2,000 - 10,000
                 */
 
-                if self.boss_health == 0 {
+                if outcome == Outcome::Victory {
                     let reward_high = if self.initial_health == 500 {
                         100
                     } else if self.initial_health == 400 {
@@ -129,10 +114,12 @@ impl SludgeMonsterBattle {
                         10
                     };
                     let reward_low = reward_high / 5;
-                    let reward = thread_rng().gen_range(reward_low..=reward_high) * 1000;
+                    let reward = rng.gen_range(reward_low..=reward_high) * 1000;
 
                     let mut user_file = UserValues::get(&msg.author.id);
                     user_file.add_bananas(reward);
+                    user_file.add_progress(Stat::CreaturesDefeated, 1);
+                    user_file.add_progress(Stat::Defeated("Sludge Monster".to_string()), 1);
 
                     return (CreateEmbed::new()
                                 .title("Sludge Monster Defeated!")
@@ -144,18 +131,16 @@ impl SludgeMonsterBattle {
                             true);
                 }
 
-                let boss_attack = self.boss_turn();
-
-                if self.player_health == 0 {
-                    return self.handle_player_death(msg.author.id);
+                if self.boss_turn(rng) == Outcome::Victory {
+                    return self.handle_player_death(msg.author.id, rng);
                 }
 
-                (self.craft_embed(format!("You attacked the boss for {} damage, but it attacked you for {}!", attack, boss_attack)), false)
+                (self.craft_embed(String::new()), false)
             }
             "run" => {
-                let flee = self.attempt_flee();
+                let outcome = combat::resolve(&mut self.player, &mut self.boss, Action::Flee, &mut self.log, rng);
 
-                if flee {
+                if outcome == Outcome::Fled {
                     return (CreateEmbed::new()
                                 .title("Flee!")
                                 .description("You have successfully fled from the sludge monster.")
@@ -165,16 +150,14 @@ impl SludgeMonsterBattle {
                             true);
                 }
 
-                let boss_attack = self.boss_turn();
-
-                if self.player_health == 0 {
-                    return self.handle_player_death(msg.author.id);
+                if self.boss_turn(rng) == Outcome::Victory {
+                    return self.handle_player_death(msg.author.id, rng);
                 }
 
-                (self.craft_embed(format!("You failed to flee, and the sludge monster attacked you for {} damage!", boss_attack)), false)
+                (self.craft_embed(String::new()), false)
             }
             "surrender" => {
-                self.handle_player_death(msg.author.id)
+                self.handle_player_death(msg.author.id, rng)
             }
             _ => {
                 (self.craft_embed("Me no understand!".to_string()), false)
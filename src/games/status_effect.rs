@@ -0,0 +1,103 @@
+use std::fmt::{Display, Formatter};
+
+#[derive(Clone, PartialEq)]
+pub enum StatusEffect {
+    Poisoned { damage: u32, turns: u32 },
+    Stunned { turns: u32 },
+    Enraged { damage_multiplier: f32 },
+    Shielded { amount: u32 },
+}
+
+impl Display for StatusEffect {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StatusEffect::Poisoned { damage, turns } => write!(f, ":nauseated_face: Poisoned ({}hp for {} turns)", damage, turns),
+            StatusEffect::Stunned { turns } => write!(f, ":dizzy: Stunned ({} turns)", turns),
+            StatusEffect::Enraged { damage_multiplier } => write!(f, ":rage: Enraged ({}x damage)", damage_multiplier),
+            StatusEffect::Shielded { amount } => write!(f, ":shield: Shielded ({}hp)", amount),
+        }
+    }
+}
+
+// the effects active on one side of a battle
+#[derive(Clone, Default)]
+pub struct StatusEffects {
+    effects: Vec<StatusEffect>,
+}
+
+impl StatusEffects {
+
+    // poison and stuns are refreshed instead of stacking, shields stack
+    pub fn apply(&mut self, effect: StatusEffect) {
+        let existing = self.effects.iter_mut().find(|e| std::mem::discriminant(*e) == std::mem::discriminant(&effect));
+        match (existing, effect) {
+            (Some(StatusEffect::Shielded { amount }), StatusEffect::Shielded { amount: added }) => *amount += added,
+            (Some(existing), effect) => *existing = effect,
+            (None, effect) => self.effects.push(effect),
+        }
+    }
+
+    pub fn is_stunned(&self) -> bool {
+        self.effects.iter().any(|e| matches!(e, StatusEffect::Stunned { .. }))
+    }
+
+    pub fn is_enraged(&self) -> bool {
+        self.effects.iter().any(|e| matches!(e, StatusEffect::Enraged { .. }))
+    }
+
+    pub fn damage_multiplier(&self) -> f32 {
+        self.effects.iter().fold(1.0, |multiplier, e| match e {
+            StatusEffect::Enraged { damage_multiplier } => multiplier * damage_multiplier,
+            _ => multiplier,
+        })
+    }
+
+    // shields soak up damage first, returns the damage that gets through
+    pub fn absorb(&mut self, damage: u32) -> u32 {
+        let mut remaining = damage;
+        for effect in self.effects.iter_mut() {
+            if let StatusEffect::Shielded { amount } = effect {
+                let absorbed = remaining.min(*amount);
+                *amount -= absorbed;
+                remaining -= absorbed;
+            }
+        }
+        self.effects.retain(|e| !matches!(e, StatusEffect::Shielded { amount: 0 }));
+        remaining
+    }
+
+    // called at the start of a turn, returns the damage taken and what happened
+    pub fn tick(&mut self) -> (u32, Vec<String>) {
+        let mut damage: u32 = 0;
+        let mut log = Vec::new();
+
+        for effect in self.effects.iter_mut() {
+            match effect {
+                StatusEffect::Poisoned { damage: poison, turns } => {
+                    damage = damage.saturating_add(*poison);
+                    // effects loaded from tier files can start with no turns left
+                    *turns = turns.saturating_sub(1);
+                    log.push(format!("Poison dealt {} damage!", poison));
+                }
+                StatusEffect::Stunned { turns } => {
+                    *turns = turns.saturating_sub(1);
+                }
+                _ => {}
+            }
+        }
+
+        self.effects.retain(|e| !matches!(e, StatusEffect::Poisoned { turns: 0, .. } | StatusEffect::Stunned { turns: 0 }));
+        (damage, log)
+    }
+
+}
+
+impl Display for StatusEffects {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.effects.is_empty() {
+            return write!(f, "None");
+        }
+        let effects = self.effects.iter().map(|e| e.to_string()).collect::<Vec<String>>();
+        write!(f, "{}", effects.join("\n"))
+    }
+}
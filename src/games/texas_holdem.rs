@@ -1,7 +1,7 @@
-use rand::{thread_rng};
 use rand::prelude::SliceRandom;
 use serenity::all::{CreateEmbed, Message, UserId};
 use crate::games::{Card, Deck};
+use crate::games::rng::GameRng;
 
 struct Player {
     user: UserId,
@@ -22,9 +22,9 @@ pub struct TexasHoldem {
 
 impl TexasHoldem {
 
-    pub fn new(buy_in: u64) -> Self {
+    pub fn new(buy_in: u64, rng: &mut GameRng) -> Self {
         let mut deck = Deck::new(2, false);
-        deck.shuffle();
+        deck.shuffle(rng);
         Self {
             buy_in,
             players: Vec::new(),
@@ -38,19 +38,19 @@ impl TexasHoldem {
     pub fn add_player(&mut self, user: UserId) {
         self.players.push(Player {
             user,
-            hand: (self.deck.deal(), self.deck.deal()),
+            hand: (self.deck.deal().unwrap(), self.deck.deal().unwrap()),
             current_bet: 0,
             folded: false,
             played_round: false,
         });
     }
 
-    pub fn start(&mut self) {
+    pub fn start(&mut self, rng: &mut GameRng) {
         self.round = 1;
         self.community_cards = Vec::new();
 
         // shuffle the players
-        self.players.shuffle(&mut thread_rng());
+        self.players.shuffle(rng);
 
         // assign blinds randomly 2% for big blind
         let big_blind_amount = self.buy_in / 50;
@@ -2,13 +2,33 @@ use std::fs;
 use std::fs::OpenOptions;
 use std::path::Path;
 use serde::{Deserialize, Serialize};
-use serenity::all::{ChannelId, GuildId};
+use serenity::all::{ChannelId, GuildId, UserId};
 use crate::hey;
+use crate::games::rng::GameRng;
+use crate::lottery::{DrawResult, Lottery};
+use crate::quests::QuestSettings;
+use crate::seasons::{self, SeasonField, SeasonPlayer, SeasonRecord, Seasons, Standing};
+use crate::userfile::UserValues;
 use std::io::Write;
 
 #[derive(Serialize, Deserialize)]
 pub struct GuildFile {
     spam_channels: Vec<u64>,
+    // the progressive slots jackpot, fed by every spin on a jackpot machine
+    #[serde(default)]
+    jackpot: u64,
+    // percent of every duel pot the house keeps
+    #[serde(default)]
+    duel_rake: u8,
+    #[serde(default)]
+    lottery: Lottery,
+    #[serde(default)]
+    quests: QuestSettings,
+    #[serde(default)]
+    seasons: Seasons,
+    // members with this role can use the admin economy tools
+    #[serde(default)]
+    admin_role: Option<u64>,
 }
 
 pub struct GuildSettings {
@@ -23,6 +43,12 @@ impl GuildSettings {
             id: guild_id.clone(),
             file: GuildFile {
                 spam_channels: Vec::new(),
+                jackpot: 0,
+                duel_rake: 0,
+                lottery: Lottery::default(),
+                quests: QuestSettings::default(),
+                seasons: Seasons::default(),
+                admin_role: None,
             },
         }
     }
@@ -49,6 +75,21 @@ impl GuildSettings {
         }
     }
 
+    pub fn all() -> Vec<Self> {
+        let Ok(paths) = fs::read_dir("./guilds") else {
+            return Vec::new();
+        };
+
+        let mut guilds = Vec::new();
+        for path in paths.flatten() {
+            let name = path.file_name().into_string().unwrap_or_default().replace(".json", "");
+            if let Ok(id) = name.parse::<u64>() {
+                guilds.push(Self::get(&GuildId::from(id)));
+            }
+        }
+        guilds
+    }
+
     fn generate(id: &GuildId) {
         let raw_path = format!("./guilds/{}.json", id.get());
         let path = Path::new(raw_path.as_str());
@@ -138,4 +179,182 @@ impl GuildSettings {
         self.file.spam_channels.contains(&channel_id) || self.file.spam_channels.is_empty()
     }
 
-}
\ No newline at end of file
+    pub fn get_jackpot(&mut self) -> u64 {
+        self.reload();
+        self.file.jackpot
+    }
+
+    pub fn add_to_jackpot(&mut self, amount: u64) {
+        self.reload();
+        self.file.jackpot += amount;
+        self.update();
+    }
+
+    // empties the pool and returns what was in it
+    pub fn take_jackpot(&mut self) -> u64 {
+        self.reload();
+        let jackpot = self.file.jackpot;
+        self.file.jackpot = 0;
+        self.update();
+        jackpot
+    }
+
+    pub fn get_duel_rake(&mut self) -> u8 {
+        self.reload();
+        self.file.duel_rake
+    }
+
+    pub fn set_duel_rake(&mut self, percent: u8) {
+        self.reload();
+        self.file.duel_rake = percent;
+        self.update();
+    }
+
+    pub fn get_lottery(&mut self) -> Lottery {
+        self.reload();
+        self.file.lottery.clone()
+    }
+
+    // sets where draws are announced, the first draw is scheduled if the lottery wasn't running
+    pub fn setup_lottery(&mut self, channel: u64, interval_days: i64, now: i64) {
+        self.reload();
+        let lottery = &mut self.file.lottery;
+        lottery.channel = Some(channel);
+        lottery.interval_days = interval_days;
+        if !lottery.is_running() {
+            lottery.schedule_next(now);
+        }
+        self.update();
+    }
+
+    // returns what the tickets cost
+    pub fn buy_lottery_tickets(&mut self, user: &UserId, tickets: Vec<Vec<u8>>) -> Result<u64, String> {
+        self.reload();
+        let cost = self.file.lottery.add_tickets(user, tickets)?;
+        self.update();
+        Ok(cost)
+    }
+
+    // draws the lottery if it is due, with no tickets sold the draw is pushed back and the pot rolls over
+    pub fn draw_lottery(&mut self, rng: &mut GameRng, now: i64) -> Option<(DrawResult, Option<u64>)> {
+        self.reload();
+        let lottery = &mut self.file.lottery;
+        if !lottery.is_running() || now < lottery.next_draw {
+            return None;
+        }
+        if lottery.tickets.is_empty() {
+            lottery.schedule_next(now);
+            self.update();
+            return None;
+        }
+        let result = lottery.draw(rng, now);
+        let channel = lottery.channel;
+        self.update();
+        Some((result, channel))
+    }
+
+    pub fn get_quest_settings(&mut self) -> QuestSettings {
+        self.reload();
+        self.file.quests.clone()
+    }
+
+    pub fn set_quest_reset_hour(&mut self, hour: u8) {
+        self.reload();
+        self.file.quests.reset_hour = hour;
+        self.update();
+    }
+
+    pub fn set_quest_enabled(&mut self, id: &str, enabled: bool) {
+        self.reload();
+        let disabled = &mut self.file.quests.disabled;
+        disabled.retain(|q| q != id);
+        if !enabled {
+            disabled.push(id.to_string());
+        }
+        self.update();
+    }
+
+    pub fn set_quest_reward(&mut self, id: &str, bananas: u64) {
+        self.reload();
+        self.file.quests.rewards.insert(id.to_string(), bananas);
+        self.update();
+    }
+
+    pub fn get_seasons(&mut self) -> Seasons {
+        self.reload();
+        self.file.seasons.clone()
+    }
+
+    // changes how seasons run, the first season starts if none has yet
+    pub fn setup_seasons(&mut self, channel: Option<u64>, length_days: Option<i64>, reset: Option<Vec<SeasonField>>, keep_percent: Option<u8>, now: i64) {
+        self.reload();
+        let seasons = &mut self.file.seasons;
+        if channel.is_some() {
+            seasons.channel = channel;
+        }
+        if let Some(days) = length_days {
+            // the current season keeps its start, so only its end moves
+            seasons.length_days = days;
+            seasons.ends = seasons.started + days * 86400;
+        }
+        if let Some(reset) = reset {
+            seasons.reset = reset;
+        }
+        if let Some(keep) = keep_percent {
+            seasons.keep_percent = keep;
+        }
+        if !seasons.is_running() {
+            seasons.start(now);
+        }
+        self.update();
+    }
+
+    // the scheduler ends the season the next time it checks, false if it hasn't run for long enough
+    pub fn end_season_now(&mut self, now: i64) -> bool {
+        self.reload();
+        let seasons = &mut self.file.seasons;
+        if now < seasons.earliest_end() {
+            return false;
+        }
+        seasons.ends = seasons.ends.min(now);
+        self.update();
+        true
+    }
+
+    pub fn end_season(&mut self, current: &[Standing], now: i64) -> SeasonRecord {
+        self.reload();
+        let record = self.file.seasons.end(current, now);
+        self.update();
+        seasons::forget_joined(self.id.get());
+        record
+    }
+
+    // only saves when the user is new to the season, users already in it are remembered so this can run on every message
+    pub fn join_season(guild: &GuildId, user: &mut UserValues) {
+        if seasons::has_joined(guild.get(), user.id.get()) {
+            return;
+        }
+        let mut guild_file = Self::get(guild);
+        let seasons = &mut guild_file.file.seasons;
+        if !seasons.is_running() {
+            return;
+        }
+        if !seasons.is_participant(user.id.get()) {
+            seasons.participants.push(SeasonPlayer::new(Standing::of(user)));
+            guild_file.update();
+        }
+        seasons::mark_joined(guild.get(), user.id.get());
+    }
+
+    pub fn get_admin_role(&mut self) -> Option<u64> {
+        self.reload();
+        self.file.admin_role
+    }
+
+    pub fn set_admin_role(&mut self, role: Option<u64>) {
+        self.reload();
+        self.file.admin_role = role;
+        self.update();
+    }
+
+}
@@ -12,6 +12,9 @@ pub const MAX_INVENTORY_SIZE: u8 = 64;
 pub struct Inventory {
     pub items: Vec<item::InventoryItem>,
     pub equiped: Option<item::InventoryItem>,
+    // slots on top of MAX_INVENTORY_SIZE, earned from milestones
+    #[serde(default)]
+    pub extra_slots: u8,
 }
 
 impl Inventory {
@@ -67,8 +70,12 @@ impl Inventory {
         None
     }
 
+    pub fn capacity(&self) -> usize {
+        MAX_INVENTORY_SIZE as usize + self.extra_slots as usize
+    }
+
     pub fn is_full(&self) -> bool {
-        self.items.len() >= MAX_INVENTORY_SIZE as usize
+        self.items.len() >= self.capacity()
     }
 }
 
@@ -80,7 +87,7 @@ impl Clone for Inventory {
         }
 
         Self {
-            items, equiped: self.equiped.clone(),
+            items, equiped: self.equiped.clone(), extra_slots: self.extra_slots,
         }
     }
 }
\ No newline at end of file
@@ -28,12 +28,14 @@ pub enum InventoryItem {
     HealingPotion { health: u32 },
     SpellTome { name: String, damage: RangeInclusive<u32> },
     Weapon { name: String, wtype: WeaponType, damage: RangeInclusive<u32> },
+    // used up automatically to save a daily or weekly streak
+    StreakFreeze,
 }
 
 impl Display for InventoryItem {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            InventoryItem::Minion(_) => write!(f, "Minion"),
+            InventoryItem::Minion(minion) => write!(f, "{} Minion (lvl {})", minion.kind, minion.level),
             InventoryItem::SuperDrill(drill) => write!(f, "Super Drill ({})", drill.tier),
             InventoryItem::HealingPotion { health: max_effectiveness } => write!(f, "Healing Potion ({}hp)", max_effectiveness),
             InventoryItem::SpellTome {
@@ -42,6 +44,7 @@ impl Display for InventoryItem {
             InventoryItem::Weapon {
                 name, wtype, ..
             } => write!(f, "{} ({})", name, wtype),
+            InventoryItem::StreakFreeze => write!(f, "Streak Freeze"),
         }
     }
 }
\ No newline at end of file
@@ -1,50 +1,155 @@
+use std::fmt::{Display, Formatter};
 use serde::{Deserialize, Serialize};
 use serenity::all::Timestamp;
 
 const MINION_PRODUCTION_PER_HOUR: u32 = 20;
 pub const MINION_BASE_MAX_SLUDGE: u32 = 600;
+pub const MINION_MAX_LEVEL: u8 = 10;
+
+// upgrade costs scale with the minion's current level
+const UPGRADE_BANANA_COST: u64 = 25_000;
+const UPGRADE_SUPER_NANNER_COST: u16 = 5;
+
+// alchemists produce super nanners at a low rate
+const ALCHEMIST_HOURS_PER_SUPER_NANNER: f64 = 24.0;
+const ALCHEMIST_BASE_MAX_SUPER_NANNERS: u32 = 3;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Default)]
+pub enum MinionType {
+    #[default]
+    Worker,
+    Speedy,
+    Hoarder,
+    Alchemist,
+}
+
+impl MinionType {
+
+    // multiplier on sludge produced per hour
+    pub fn production_multiplier(&self) -> f64 {
+        match self {
+            MinionType::Worker => 1.0,
+            MinionType::Speedy => 2.0,
+            MinionType::Hoarder => 0.75,
+            MinionType::Alchemist => 0.5,
+        }
+    }
+
+    // multiplier on how much sludge the minion can hold before it is full
+    pub fn capacity_multiplier(&self) -> f64 {
+        match self {
+            MinionType::Worker => 1.0,
+            MinionType::Speedy => 0.75,
+            MinionType::Hoarder => 3.0,
+            MinionType::Alchemist => 1.0,
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            MinionType::Worker => "A reliable sludge miner",
+            MinionType::Speedy => "Mines twice as fast, but can't hold as much",
+            MinionType::Hoarder => "Mines slowly, but holds three times the sludge",
+            MinionType::Alchemist => "Mines little sludge, but slowly brews super nanners",
+        }
+    }
+}
+
+impl Display for MinionType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MinionType::Worker => write!(f, "Worker"),
+            MinionType::Speedy => write!(f, "Speedy"),
+            MinionType::Hoarder => write!(f, "Hoarder"),
+            MinionType::Alchemist => write!(f, "Alchemist"),
+        }
+    }
+}
 
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub struct Minion {
     pub level: u8,
     pub mining_start: Timestamp,
+    #[serde(default)]
+    pub kind: MinionType,
 }
 
 impl Minion {
-    pub fn new() -> Self {
+    pub fn new(kind: MinionType) -> Self {
         Self {
             level: 1,
             mining_start: Timestamp::now(),
+            kind,
         }
     }
 
-    pub fn minute_sludge_production(&self) -> u32 {
-        MINION_PRODUCTION_PER_HOUR * self.level as u32
+    pub fn hourly_sludge_production(&self) -> u32 {
+        (MINION_PRODUCTION_PER_HOUR as f64 * self.level as f64 * self.kind.production_multiplier()) as u32
     }
 
-    pub fn get_sludge_produced(&self) -> u32 {
+    // each level adds half of the base capacity, the owner's minion capacity perk multiplies it
+    pub fn max_sludge(&self, capacity: f64) -> u32 {
+        let level_scale = 1.0 + (self.level as f64 - 1.0) * 0.5;
+        (MINION_BASE_MAX_SLUDGE as f64 * level_scale * self.kind.capacity_multiplier() * capacity) as u32
+    }
+
+    fn hours_mining(&self) -> f64 {
         let now = Timestamp::now();
 
         let duration = now.signed_duration_since(self.mining_start.fixed_offset());
 
-        let seconds = duration.num_seconds() as f64;
+        duration.num_seconds() as f64 / 3600.0
+    }
 
-        let hours_passed = seconds / 3600.0;
+    pub fn get_sludge_produced(&self, capacity: f64) -> u32 {
+        let produced = (self.hourly_sludge_production() as f64 * self.hours_mining()) as u32;
 
-        let produced = (self.minute_sludge_production() as f64 * hours_passed) as u32;
+        produced.min(self.max_sludge(capacity))
+    }
 
-        if produced > MINION_BASE_MAX_SLUDGE {
-            MINION_BASE_MAX_SLUDGE
+    pub fn max_super_nanners(&self) -> u32 {
+        if self.kind == MinionType::Alchemist {
+            ALCHEMIST_BASE_MAX_SUPER_NANNERS + self.level as u32 / 2
         } else {
-            produced
+            0
+        }
+    }
+
+    pub fn get_super_nanners_produced(&self) -> u32 {
+        if self.kind != MinionType::Alchemist {
+            return 0;
         }
+
+        let produced = (self.hours_mining() * self.level as f64 / ALCHEMIST_HOURS_PER_SUPER_NANNER) as u32;
+
+        produced.min(self.max_super_nanners())
     }
 
-    pub fn is_full(&self) -> bool {
-        self.get_sludge_produced() >= MINION_BASE_MAX_SLUDGE
+    pub fn is_full(&self, capacity: f64) -> bool {
+        self.get_sludge_produced(capacity) >= self.max_sludge(capacity)
+    }
+
+    // seconds until the minion can't hold any more sludge
+    pub fn seconds_until_full(&self, capacity: f64) -> u64 {
+        let remaining = self.max_sludge(capacity) - self.get_sludge_produced(capacity);
+        let production = self.hourly_sludge_production().max(1);
+
+        (remaining as f64 / production as f64 * 3600.0) as u64
+    }
+
+    pub fn can_upgrade(&self) -> bool {
+        self.level < MINION_MAX_LEVEL
+    }
+
+    pub fn upgrade_banana_cost(&self) -> u64 {
+        UPGRADE_BANANA_COST * (self.level as u64).pow(2)
+    }
+
+    pub fn upgrade_super_nanner_cost(&self) -> u16 {
+        UPGRADE_SUPER_NANNER_COST * self.level as u16
     }
 
     pub fn mine(&mut self) {
         self.mining_start = Timestamp::now();
     }
-}
\ No newline at end of file
+}
@@ -1,6 +1,35 @@
 use serde::{Deserialize, Serialize};
 
+pub const MAX_SUPER_DRILL_TIER: u8 = 5;
+// upgrading to tier x costs 10 x tier super nanners
+const UPGRADE_NANNERS_PER_TIER: u16 = 10;
+// and 5 x current tier drill parts (dropped by creatures in the mines)
+const UPGRADE_PARTS_PER_TIER: u32 = 5;
+
 #[derive(Deserialize, Serialize, Clone, PartialEq)]
 pub struct SuperDrill {
     pub(crate) tier: u8,
-}
\ No newline at end of file
+}
+
+impl SuperDrill {
+
+    pub fn can_upgrade(&self) -> bool {
+        self.tier < MAX_SUPER_DRILL_TIER
+    }
+
+    // returns the (super nanner, drill part) cost of the next tier
+    pub fn upgrade_cost(&self) -> (u16, u32) {
+        let next_tier = self.tier + 1;
+        (UPGRADE_NANNERS_PER_TIER * next_tier as u16, UPGRADE_PARTS_PER_TIER * self.tier as u32)
+    }
+
+    // multiplier applied to the time it takes to mine (each tier is 15% faster, down to 40%)
+    pub fn mine_time_multiplier(&self) -> f32 {
+        (1.0 - 0.15 * self.tier as f32).max(0.4)
+    }
+
+    // multiplier applied to the sludge mined (each tier adds 25%)
+    pub fn yield_multiplier(&self) -> f32 {
+        1.0 + 0.25 * self.tier as f32
+    }
+}
@@ -0,0 +1,173 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use lazy_static::lazy_static;
+use rand::Rng;
+use serenity::all::{ChannelId, Colour, Command, CommandInteraction, Context, CreateAttachment, CreateCommand, CreateEmbed, CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, Member, PartialGuild, Timestamp, UserId};
+use tokio::sync::Mutex;
+use crate::games::{GamesManager};
+use crate::games::rng::GameRng;
+
+// TODO: broken:
+//    using items breaks the currently equipped item, need to fix
+//    health items are not working in pvp
+//    item index in pvp is 0 based, should be 1 based
+
+pub mod logging;
+pub mod userfile;
+pub mod guildfile;
+pub mod commands;
+pub mod games;
+pub mod inventory;
+pub mod mine_data;
+pub mod notifications;
+pub mod fairness;
+pub mod slot_machines;
+pub mod lottery;
+pub mod rewards;
+pub mod achievements;
+pub mod quests;
+pub mod perks;
+pub mod milestones;
+pub mod seasons;
+pub mod clans;
+pub mod economy;
+pub mod bets;
+
+lazy_static!(
+    pub static ref CRATE_ACTIVE: Mutex<AtomicBool> = Mutex::new(AtomicBool::new(false));
+    pub static ref CRATE_CODE: Mutex<String> = Mutex::new(String::new());
+
+    pub static ref USERS_IN_VOICE: Mutex<Vec<(UserId, Timestamp)>> = Mutex::new(Vec::new());
+
+    // TODO: store channel id with games so they are locked to a channel to prevent bugs
+    //    but be sure players cant do multiple games still
+    pub static ref GAMES: Mutex<GamesManager> = Mutex::new(GamesManager::new());
+
+    pub static ref MINING: Mutex<Vec<UserId>> = Mutex::new(Vec::new());
+
+    pub static ref SUPERBOOST_MODE: AtomicBool = AtomicBool::new(false);
+    pub static ref SKEPZ_WIN_ALWAYS: AtomicBool = AtomicBool::new(false);
+);
+
+pub const CODE_LENGTH: u8 = 6;
+pub const MSG_BANANA_GAIN_MIN: u64 = 5;
+pub const MSG_BANANA_GAIN_MAX: u64 = 25;
+pub const VOICE_MINUTE_BANANA_WORTH: u64 = 150;
+
+pub const SLUDGE_BANANA_WORTH: u64 = 250; // produce 1-10 sludge by default per mining
+
+pub const SUPERBOOST: u64 = 2;
+
+// function that runs every minute to give bananas to users in voice channels
+pub async fn voice_minute_banana() {
+    loop {
+        // sleep for 1 minute
+        tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
+
+        let users_in_voice = USERS_IN_VOICE.lock().await;
+        for (id, time) in &*users_in_voice {
+            let full_dur = Timestamp::now().signed_duration_since(time.fixed_offset()).num_seconds();
+            let duration = full_dur.min(60) as f32 / 60.0;
+            let mut bananas = (duration * VOICE_MINUTE_BANANA_WORTH as f32) as u64;
+            if SUPERBOOST_MODE.load(Ordering::SeqCst) {
+                bananas *= SUPERBOOST;
+            }
+            let mut userfile = userfile::UserValues::get(id);
+            bananas = (bananas as f64 * userfile.get_perks().voice_multiplier()) as u64;
+            userfile.add_bananas(bananas);
+        }
+    }
+}
+
+pub async fn is_admin(guild: &PartialGuild, member: &Member) -> bool {
+    for r in &member.roles {
+        // get the role:
+        if let Some(role) = guild.roles.get(r) {
+            if role.permissions.administrator() {
+                return true;
+            }
+        }
+    }
+
+    return false;
+}
+
+pub fn is_supreme_overlord(id: UserId) -> bool {
+    id == 318884828508454912
+}
+
+pub fn gen_crate_code(rng: &mut GameRng) -> String {
+    let mut code = String::new();
+
+    for x in 0..CODE_LENGTH {
+        code.push((rng.gen_range(33_u8..=126_u8) as char).to_ascii_uppercase());
+        if x != CODE_LENGTH - 1 {
+            code.push(' ');
+        }
+    }
+
+    code = code.replace("`", "'");
+    code = code.replace("\\", "/");
+
+    code
+}
+
+pub async fn spawn_crate(ctx: &Context, channel: &ChannelId) {
+    let code = gen_crate_code(&mut GameRng::new());
+    CRATE_CODE.lock().await.clear();
+    CRATE_CODE.lock().await.push_str(&code);
+    CRATE_ACTIVE.lock().await.store(true, Ordering::SeqCst);
+
+    // send the embed
+    let embed = CreateEmbed::new()
+        .title("Banana Crate")
+        .color(Colour::DARK_GOLD)
+        .description("A crate of bananas has spawned!")
+        .thumbnail("attachment://crate.jpg")
+        .field("Type the code first to get the crate!", format!("Code: `{}`", code.clone()), true)
+        .timestamp(Timestamp::now());
+    let message = CreateMessage::new()
+        .add_file(CreateAttachment::path("./images/crate.jpg").await.unwrap())
+        .embed(embed);
+    if let Err(e) = channel.send_message(&ctx.http, message).await {
+        nay!("Failed to send crate message: {}", e);
+    }
+}
+
+// formats a number of seconds as `1d 2h 3m` style text
+pub fn format_duration(seconds: u64) -> String {
+    let days = seconds / 86400;
+    let hours = (seconds % 86400) / 3600;
+    let minutes = (seconds % 3600) / 60;
+
+    if days > 0 {
+        format!("{}d {}h {}m", days, hours, minutes)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+pub async fn command_response<S: Into<String>>(ctx: &Context, command: &CommandInteraction, msg: S) {
+    let data = CreateInteractionResponseMessage::new().content(msg.into()).ephemeral(true);
+    let builder = CreateInteractionResponse::Message(data);
+    if let Err(err) = command.create_response(&ctx.http, builder).await {
+        nay!("Failed to respond to command: {}", err)
+    }
+}
+
+pub async fn command_response_loud<S: Into<String>>(ctx: &Context, command: &CommandInteraction, msg: S) {
+    let data = CreateInteractionResponseMessage::new().content(msg.into());
+    let builder = CreateInteractionResponse::Message(data);
+    if let Err(err) = command.create_response(&ctx.http, builder).await {
+        nay!("Failed to respond to command: {}", err)
+    }
+}
+
+pub async fn register_command(ctx: &Context, cmd: CreateCommand) {
+    if let Err(e) = Command::create_global_command(&ctx.http, cmd).await {
+        nay!("Failed to register a command: {}", e);
+    }
+}
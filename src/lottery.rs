@@ -0,0 +1,258 @@
+use std::sync::Arc;
+use rand::seq::index::sample;
+use serde::{Deserialize, Serialize};
+use serenity::all::{ChannelId, Colour, CreateAttachment, CreateEmbed, CreateEmbedFooter, CreateMessage, Http, Mentionable, Timestamp, UserId};
+use crate::games::rng::GameRng;
+use crate::guildfile::GuildSettings;
+use crate::userfile::UserValues;
+use crate::{nay, say};
+
+pub const TICKET_PRICE: u64 = 100;
+// every ticket picks this many different numbers from 1 to HIGHEST_NUMBER
+pub const PICKS: usize = 4;
+pub const HIGHEST_NUMBER: u8 = 20;
+pub const MAX_TICKETS_PER_USER: usize = 50;
+pub const DEFAULT_DRAW_INTERVAL_DAYS: i64 = 7;
+// numbers matched and the percent of the pot shared by those tickets, any tier nobody hits rolls over
+pub const PRIZE_TIERS: [(usize, u64); 3] = [(4, 50), (3, 30), (2, 20)];
+const CHECK_INTERVAL_SECS: u64 = 60;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Ticket {
+    pub user: u64,
+    pub numbers: Vec<u8>,
+}
+
+// a guild's lottery, saved in its guild file so tickets and the pot survive restarts
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Lottery {
+    pub pot: u64,
+    pub tickets: Vec<Ticket>,
+    // unix time of the next draw, 0 until an admin sets the lottery up
+    pub next_draw: i64,
+    pub interval_days: i64,
+    // where draws are announced
+    pub channel: Option<u64>,
+    pub draws: u64,
+}
+
+pub struct TicketResult {
+    pub ticket: Ticket,
+    pub matched: usize,
+    pub prize: u64,
+}
+
+pub struct DrawResult {
+    pub draw: u64,
+    pub winning: Vec<u8>,
+    pub pot: u64,
+    pub rollover: u64,
+    pub results: Vec<TicketResult>,
+}
+
+pub fn format_numbers(numbers: &[u8]) -> String {
+    numbers.iter().map(|n| n.to_string()).collect::<Vec<String>>().join(" ")
+}
+
+impl Lottery {
+
+    pub fn is_running(&self) -> bool {
+        self.next_draw > 0
+    }
+
+    pub fn schedule_next(&mut self, now: i64) {
+        self.next_draw = now + self.interval_days.max(1) * 86400;
+    }
+
+    // numbers typed like `3 7 12 19`, they are kept sorted
+    pub fn parse_numbers(spec: &str) -> Result<Vec<u8>, String> {
+        let mut numbers = Vec::new();
+        for part in spec.split([' ', ',', '-']).filter(|p| !p.is_empty()) {
+            match part.parse::<u8>() {
+                Ok(n) if (1..=HIGHEST_NUMBER).contains(&n) => numbers.push(n),
+                _ => return Err(format!("Numbers must be between 1 and {}", HIGHEST_NUMBER)),
+            }
+        }
+        numbers.sort();
+        numbers.dedup();
+        if numbers.len() != PICKS {
+            return Err(format!("Pick {} different numbers", PICKS));
+        }
+        Ok(numbers)
+    }
+
+    pub fn quick_pick(rng: &mut GameRng) -> Vec<u8> {
+        let mut numbers = sample(rng, HIGHEST_NUMBER as usize, PICKS).into_iter()
+            .map(|n| n as u8 + 1)
+            .collect::<Vec<u8>>();
+        numbers.sort();
+        numbers
+    }
+
+    pub fn user_tickets(&self, user: &UserId) -> Vec<&Ticket> {
+        self.tickets.iter().filter(|t| t.user == user.get()).collect()
+    }
+
+    // returns what the tickets cost, nothing is added if any of them can't be
+    pub fn add_tickets(&mut self, user: &UserId, tickets: Vec<Vec<u8>>) -> Result<u64, String> {
+        if !self.is_running() {
+            return Err("The lottery hasn't been set up here yet!".to_string());
+        }
+        if self.user_tickets(user).len() + tickets.len() > MAX_TICKETS_PER_USER {
+            return Err(format!("You can only hold {} tickets per draw", MAX_TICKETS_PER_USER));
+        }
+        let cost = tickets.len() as u64 * TICKET_PRICE;
+        self.tickets.extend(tickets.into_iter().map(|numbers| Ticket { user: user.get(), numbers }));
+        self.pot += cost;
+        Ok(cost)
+    }
+
+    // draws the winning numbers and splits the pot, the tickets are cleared and what's left rolls over
+    pub fn draw(&mut self, rng: &mut GameRng, now: i64) -> DrawResult {
+        let winning = Self::quick_pick(rng);
+        let pot = self.pot;
+
+        let mut results = self.tickets.drain(..).map(|ticket| {
+            let matched = ticket.numbers.iter().filter(|n| winning.contains(n)).count();
+            TicketResult { ticket, matched, prize: 0 }
+        }).collect::<Vec<TicketResult>>();
+
+        let mut paid = 0;
+        for (matched, percent) in PRIZE_TIERS {
+            let winners = results.iter().filter(|r| r.matched == matched).count() as u64;
+            if winners == 0 {
+                continue;
+            }
+            let prize = pot * percent / 100 / winners;
+            for result in results.iter_mut().filter(|r| r.matched == matched) {
+                result.prize = prize;
+                paid += prize;
+            }
+        }
+
+        self.draws += 1;
+        self.pot = pot - paid;
+        self.schedule_next(now);
+
+        DrawResult { draw: self.draws, winning, pot, rollover: self.pot, results }
+    }
+}
+
+impl DrawResult {
+
+    pub fn embed(&self) -> CreateEmbed {
+        let mut embed = CreateEmbed::new()
+            .title(format!("Lottery Draw #{}", self.draw))
+            .description(format!("The winning numbers are **{}**", format_numbers(&self.winning)))
+            .color(Colour::GOLD)
+            .field("Pot", format!("{}:banana:", self.pot), true)
+            .field("Rolls over", format!("{}:banana:", self.rollover), true)
+            .field("Tickets", self.results.len().to_string(), true);
+
+        for (matched, _) in PRIZE_TIERS {
+            let winners = self.results.iter().filter(|r| r.matched == matched).collect::<Vec<&TicketResult>>();
+            let text = match winners.first() {
+                None => "Nobody, this share rolls over".to_string(),
+                Some(first) => {
+                    let mut users = winners.iter().map(|r| UserId::new(r.ticket.user)).collect::<Vec<UserId>>();
+                    users.sort();
+                    users.dedup();
+                    let names = users.iter().take(10).map(|u| u.mention().to_string()).collect::<Vec<String>>().join(", ");
+                    format!("{} ticket(s) won {}:banana: each: {}", winners.len(), first.prize, names)
+                }
+            };
+            embed = embed.field(format!("Matched {}", matched), text, false);
+        }
+
+        embed.timestamp(Timestamp::now())
+            .footer(CreateEmbedFooter::new("Every ticket is listed in the attached audit"))
+    }
+
+    // one line per ticket so anyone can check the draw
+    pub fn audit(&self) -> String {
+        let mut audit = format!("Lottery draw #{}\nWinning numbers: {}\nPot: {}\nRolled over: {}\n\n",
+                                self.draw, format_numbers(&self.winning), self.pot, self.rollover);
+        for result in &self.results {
+            audit.push_str(&format!("{} | {} | matched {} | won {}\n",
+                                    result.ticket.user, format_numbers(&result.ticket.numbers), result.matched, result.prize));
+        }
+        audit
+    }
+}
+
+async fn announce(http: &Arc<Http>, channel: ChannelId, result: &DrawResult) {
+    let message = CreateMessage::new()
+        .embed(result.embed())
+        .add_file(CreateAttachment::bytes(result.audit().into_bytes(), format!("lottery_draw_{}.txt", result.draw)));
+    if let Err(e) = channel.send_message(http, message).await {
+        nay!("Failed to announce lottery draw: {}", e);
+    }
+}
+
+// checks every guild's lottery in the background and draws any that are due
+pub async fn lottery_scheduler(http: Arc<Http>) {
+    loop {
+        tokio::time::sleep(tokio::time::Duration::from_secs(CHECK_INTERVAL_SECS)).await;
+
+        let now = Timestamp::now().unix_timestamp();
+        for mut guild in GuildSettings::all() {
+            let mut rng = GameRng::new();
+            let seed = rng.seed();
+            let Some((result, channel)) = guild.draw_lottery(&mut rng, now) else {
+                continue;
+            };
+
+            for winner in result.results.iter().filter(|r| r.prize > 0) {
+                UserValues::get(&UserId::new(winner.ticket.user)).add_bananas(winner.prize);
+            }
+            say!("Lottery draw #{} in guild {} (seed {}) drew {}, paid {} and rolled over {}",
+                 result.draw, guild.id, seed, format_numbers(&result.winning), result.pot - result.rollover, result.rollover);
+
+            if let Some(channel) = channel {
+                announce(&http, ChannelId::new(channel), &result).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lottery() -> Lottery {
+        Lottery { next_draw: 1, interval_days: 7, ..Default::default() }
+    }
+
+    #[test]
+    fn numbers_must_be_a_full_pick() {
+        assert_eq!(Lottery::parse_numbers("19 3 7 12"), Ok(vec![3, 7, 12, 19]));
+        assert!(Lottery::parse_numbers("1 2 3").is_err());
+        assert!(Lottery::parse_numbers("1 1 2 3").is_err());
+        assert!(Lottery::parse_numbers("1 2 3 21").is_err());
+        let pick = Lottery::quick_pick(&mut GameRng::from_seed(1));
+        assert_eq!(Lottery::parse_numbers(&format_numbers(&pick)), Ok(pick));
+    }
+
+    #[test]
+    fn unclaimed_tiers_roll_over() {
+        // the draw picks from the same seed, so the winning numbers are known up front
+        let winning = Lottery::quick_pick(&mut GameRng::from_seed(5));
+        let miss = (1..=HIGHEST_NUMBER).filter(|n| !winning.contains(n)).take(PICKS).collect::<Vec<u8>>();
+        let three = winning[..3].iter().copied().chain(miss.iter().copied().take(1)).collect::<Vec<u8>>();
+
+        let mut lottery = lottery();
+        lottery.add_tickets(&UserId::new(1), vec![winning.clone(), miss.clone()]).unwrap();
+        lottery.add_tickets(&UserId::new(2), vec![three]).unwrap();
+        lottery.pot = 1000;
+
+        let result = lottery.draw(&mut GameRng::from_seed(5), 0);
+        assert_eq!(result.winning, winning);
+        let prizes = result.results.iter().map(|r| r.prize).collect::<Vec<u64>>();
+        assert_eq!(prizes, vec![500, 0, 300]);
+        // nobody matched 2 so that share stays in the pot
+        assert_eq!(result.rollover, 200);
+        assert_eq!(lottery.pot, 200);
+        assert!(lottery.tickets.is_empty());
+        assert_eq!(lottery.next_draw, 7 * 86400);
+    }
+}
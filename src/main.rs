@@ -1,150 +1,19 @@
 use std::env;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::Ordering;
 use better_term::{Color, Style};
-use lazy_static::lazy_static;
 use rand::{Rng, thread_rng};
-use serenity::all::{ActivityData, ChannelId, Colour, Command, CommandInteraction, Context, CreateAttachment, CreateCommand, CreateEmbed, CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, GatewayIntents, Interaction, Member, Mentionable, Message, OnlineStatus, PartialGuild, Ready, ResumedEvent, Timestamp, UserId, VoiceState};
+use serenity::all::{ActivityData, Colour, Context, CreateAttachment, CreateEmbed, CreateEmbedFooter, CreateMessage, GatewayIntents, Interaction, Mentionable, Message, OnlineStatus, Ready, ResumedEvent, Timestamp, VoiceState};
 use serenity::{async_trait, Client};
 use serenity::client::EventHandler;
-use tokio::sync::Mutex;
-use crate::commands::{admin, banana, blackjack_cmd, buy, collect_minions, discard, equip, fiftyfifty, help, inventory_cmd, join, mine, pvp_command, shop, slots, unequip};
-use crate::games::{GamesManager};
-use crate::mine_data::Mine;
-
-// TODO: broken:
-//    using items breaks the currently equipped item, need to fix
-//    health items are not working in pvp
-//    item index in pvp is 0 based, should be 1 based
-
-pub mod logging;
-pub mod userfile;
-pub mod guildfile;
-mod commands;
-pub mod games;
-mod inventory;
-mod mine_data;
-
-lazy_static!(
-    static ref CRATE_ACTIVE: Mutex<AtomicBool> = Mutex::new(AtomicBool::new(false));
-    static ref CRATE_CODE: Mutex<String> = Mutex::new(String::new());
-
-    static ref USERS_IN_VOICE: Mutex<Vec<(UserId, Timestamp)>> = Mutex::new(Vec::new());
-
-    // TODO: store channel id with games so they are locked to a channel to prevent bugs
-    //    but be sure players cant do multiple games still
-    static ref GAMES: Mutex<GamesManager> = Mutex::new(GamesManager::new());
-
-    static ref MINING: Mutex<Vec<UserId>> = Mutex::new(Vec::new());
-
-    static ref SUPERBOOST_MODE: AtomicBool = AtomicBool::new(false);
-    static ref SKEPZ_WIN_ALWAYS: AtomicBool = AtomicBool::new(false);
-);
-
-const CODE_LENGTH: u8 = 6;
-const MSG_BANANA_GAIN_MIN: u64 = 5;
-const MSG_BANANA_GAIN_MAX: u64 = 25;
-const VOICE_MINUTE_BANANA_WORTH: u64 = 150;
-
-pub const SLUDGE_BANANA_WORTH: u64 = 250; // produce 1-10 sludge by default per mining
-
-const SUPERBOOST: u64 = 2;
-
-// function that runs every minute to give bananas to users in voice channels
-pub async fn voice_minute_banana() {
-    loop {
-        // sleep for 1 minute
-        tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
-
-        let users_in_voice = USERS_IN_VOICE.lock().await;
-        for (id, time) in &*users_in_voice {
-            let full_dur = Timestamp::now().signed_duration_since(time.fixed_offset()).num_seconds();
-            let duration = full_dur.min(60) as f32 / 60.0;
-            let mut bananas = (duration * VOICE_MINUTE_BANANA_WORTH as f32) as u64;
-            if SUPERBOOST_MODE.load(Ordering::SeqCst) {
-                bananas *= SUPERBOOST;
-            }
-            let mut userfile = userfile::UserValues::get(id);
-            userfile.add_bananas(bananas);
-        }
-    }
-}
-
-pub async fn is_admin(guild: &PartialGuild, member: &Member) -> bool {
-    for r in &member.roles {
-        // get the role:
-        if let Some(role) = guild.roles.get(r) {
-            if role.permissions.administrator() {
-                return true;
-            }
-        }
-    }
-
-    return false;
-}
-
-pub fn is_supreme_overlord(id: UserId) -> bool {
-    id == 318884828508454912
-}
-
-pub fn gen_crate_code() -> String {
-    let mut code = String::new();
-
-    for x in 0..CODE_LENGTH {
-        code.push((thread_rng().gen_range(33_u8..=126_u8) as char).to_ascii_uppercase());
-        if x != CODE_LENGTH - 1 {
-            code.push(' ');
-        }
-    }
-
-    code = code.replace("`", "'");
-    code = code.replace("\\", "/");
-
-    code
-}
-
-pub async fn spawn_crate(ctx: &Context, channel: &ChannelId) {
-    let code = gen_crate_code();
-    CRATE_CODE.lock().await.clear();
-    CRATE_CODE.lock().await.push_str(&code);
-    CRATE_ACTIVE.lock().await.store(true, Ordering::SeqCst);
-
-    // send the embed
-    let embed = CreateEmbed::new()
-        .title("Banana Crate")
-        .color(Colour::DARK_GOLD)
-        .description("A crate of bananas has spawned!")
-        .thumbnail("attachment://crate.jpg")
-        .field("Type the code first to get the crate!", format!("Code: `{}`", code.clone()), true)
-        .timestamp(Timestamp::now());
-    let message = CreateMessage::new()
-        .add_file(CreateAttachment::path("./images/crate.jpg").await.unwrap())
-        .embed(embed);
-    if let Err(e) = channel.send_message(&ctx.http, message).await {
-        nay!("Failed to send crate message: {}", e);
-    }
-}
-
-pub async fn command_response<S: Into<String>>(ctx: &Context, command: &CommandInteraction, msg: S) {
-    let data = CreateInteractionResponseMessage::new().content(msg.into()).ephemeral(true);
-    let builder = CreateInteractionResponse::Message(data);
-    if let Err(err) = command.create_response(&ctx.http, builder).await {
-        nay!("Failed to respond to command: {}", err)
-    }
-}
-
-pub async fn command_response_loud<S: Into<String>>(ctx: &Context, command: &CommandInteraction, msg: S) {
-    let data = CreateInteractionResponseMessage::new().content(msg.into());
-    let builder = CreateInteractionResponse::Message(data);
-    if let Err(err) = command.create_response(&ctx.http, builder).await {
-        nay!("Failed to respond to command: {}", err)
-    }
-}
-
-pub async fn register_command(ctx: &Context, cmd: CreateCommand) {
-    if let Err(e) = Command::create_global_command(&ctx.http, cmd).await {
-        nay!("Failed to register a command: {}", e);
-    }
-}
+use ape_bot_v2::commands::{achievements_cmd, admin, admin_economy, admin_lottery, admin_quests, admin_rake, admin_season, banana, blackjack_cmd, blackjack_table, buy, clan_cmd, claim, collect_minions, crash, discard, duel, equip, expedition, fair, fiftyfifty, help, inventory_cmd, join, lottery, mine, minion, minions, monkey_race, notifications_cmd, perks_cmd, pvp_command, quests_cmd, raid, roulette, season_cmd, shop, slots, unequip, verify};
+use ape_bot_v2::{achievements, command_response, games, guildfile, hey, is_supreme_overlord, nay, notifications, register_command, spawn_crate, userfile,
+                 voice_minute_banana, yay, CRATE_ACTIVE, CRATE_CODE, GAMES, MSG_BANANA_GAIN_MAX, MSG_BANANA_GAIN_MIN, SKEPZ_WIN_ALWAYS, SUPERBOOST,
+                 SUPERBOOST_MODE, USERS_IN_VOICE};
+use ape_bot_v2::achievements::Stat;
+use ape_bot_v2::economy::audit;
+use ape_bot_v2::mine_data::Mine;
+use ape_bot_v2::notifications::NotificationEvent;
+use ape_bot_v2::rewards::RewardKind;
 
 struct Handler;
 
@@ -189,19 +58,26 @@ impl EventHandler for Handler {
                 }
                 return;
             }
+            // these change the economy, so they go in the server's audit log like `/admin_economy`, DMs have no log
             if msg.content == "ez" {
-                SKEPZ_WIN_ALWAYS.store(!SKEPZ_WIN_ALWAYS.load(Ordering::SeqCst), Ordering::SeqCst);
+                let enabled = !SKEPZ_WIN_ALWAYS.load(Ordering::SeqCst);
+                SKEPZ_WIN_ALWAYS.store(enabled, Ordering::SeqCst);
+                if let Some(guild) = msg.guild_id {
+                    audit(&guild, &user.id, None, format!("{} rigged wins", if enabled { "enabled" } else { "disabled" }), true, Timestamp::now().unix_timestamp());
+                }
                 return;
             }
             if msg.content.starts_with("gsd") {
-                let recipient = msg.mentions.get(0);
-                if recipient.is_none() {
+                let Some(recipient) = msg.mentions.first() else {
                     return;
-                }
+                };
 
-                let recipient = recipient.unwrap();
                 let mut recipient_file = userfile::UserValues::get(&recipient.id);
                 recipient_file.add_super_drill();
+                if let Some(guild) = msg.guild_id {
+                    audit(&guild, &user.id, Some(&recipient.id), "gave a super drill".to_string(), true, Timestamp::now().unix_timestamp());
+                }
+                return;
             }
         }
 
@@ -229,7 +105,7 @@ impl EventHandler for Handler {
                                 (Some(embed), Some("./images/monkey.png".to_string()))
                             }
                             games::Games::SludgeMonsterBattle(ref mut battle) => {
-                                let (mut embed, end) = battle.handle_message(&msg);
+                                let (mut embed, end) = battle.handle_message(&msg, &mut game.rng);
 
                                 let thumbnail_path = battle.thumbnail.clone();
 
@@ -242,7 +118,7 @@ impl EventHandler for Handler {
                                 (Some(embed), Some(format!("./images/sludge_monsters/{}", thumbnail_path)))
                             }
                             games::Games::MineBattle(ref mut battle) => {
-                                let (mut embed, end) = battle.handle_message(&msg);
+                                let (mut embed, end) = battle.handle_message(&msg, &mut game.rng);
 
                                 let thumbnail = battle.thumbnail.clone();
 
@@ -254,6 +130,65 @@ impl EventHandler for Handler {
 
                                 (Some(embed), Some(format!("./images/sludge_monsters/{}", thumbnail)))
                             }
+                            games::Games::Expedition(ref mut expedition) => {
+                                let (mut embed, end) = expedition.handle_message(&msg, &mut game.rng);
+
+                                embed = embed.thumbnail(format!("attachment://{}", expedition.thumbnail()));
+                                let thumbnail_path = expedition.thumbnail_path();
+
+                                if end {
+                                    lock.end_game(code);
+                                }
+
+                                (Some(embed), Some(thumbnail_path))
+                            }
+                            games::Games::Raid(ref mut raid) => {
+                                let result = if raid.is_running() {
+                                    raid.handle_message(&msg, &mut game.rng)
+                                } else {
+                                    raid.handle_prestart_message(&msg, &mut game.rng)
+                                };
+                                let thumbnail = raid.thumbnail.clone();
+
+                                if let Some((embed, end, user_to_remove)) = result {
+                                    if end {
+                                        lock.end_game(code);
+                                    }
+
+                                    if let Some(usr) = user_to_remove {
+                                        lock.remove_player_from_game(code, usr);
+                                    }
+
+                                    (Some(embed), Some(format!("./images/sludge_monsters/{}", thumbnail)))
+                                } else {
+                                    (None, None)
+                                }
+                            }
+                            games::Games::BlackjackTable(ref mut table) => {
+                                if let Some((embed, end, user_to_remove)) = table.handle_message(&msg) {
+                                    if end {
+                                        lock.end_game(code);
+                                    }
+
+                                    if let Some(usr) = user_to_remove {
+                                        lock.remove_player_from_game(code, usr);
+                                    }
+
+                                    (Some(embed), Some("./images/monkey.png".to_string()))
+                                } else {
+                                    (None, None)
+                                }
+                            }
+                            games::Games::Roulette(ref roulette) => {
+                                (roulette.handle_message(&msg), None)
+                            }
+                            // crash is played with /crash and the cash out button
+                            games::Games::Crash(_) => (None, None),
+                            // duels are settled by their buttons
+                            games::Games::Duel(_) => (None, None),
+                            games::Games::MonkeyRace(ref race) => {
+                                (race.handle_message(&msg), None)
+                            }
                             games::Games::TexasHoldem(ref mut th) => {
                                 let (embed, end) = th.handle_message(&msg);
 
@@ -266,7 +201,7 @@ impl EventHandler for Handler {
                             games::Games::PvP(ref mut arena) => {
                                 if arena.is_running() {
                                     // game is running handle messages
-                                    if let Some((embed, end, user_to_remove)) = arena.handle_message(&ctx, user.id, &msg).await {
+                                    if let Some((embed, end, user_to_remove)) = arena.handle_message(&ctx, user.id, &msg, &mut game.rng).await {
                                         if end {
                                             lock.end_game(code);
                                         }
@@ -320,6 +255,7 @@ impl EventHandler for Handler {
             if let Err(e) = channel.send_message(&ctx.http, builder).await {
                 nay!("Failed to send message: {}", e);
             }
+            achievements::announce(&ctx.http, user.id, channel).await;
             return;
         }
 
@@ -332,11 +268,13 @@ impl EventHandler for Handler {
                     return;
                 }
                 CRATE_ACTIVE.lock().await.store(false, Ordering::SeqCst);
-                let bananas = thread_rng().gen_range(10000..25000);
+                let bananas = (thread_rng().gen_range(10000..25000) as f64 * userfile.get_perks().crate_multiplier()) as u64;
                 if let Err(e) = msg.reply(&ctx.http, format!("You've opened the crate and found {} bananas!", bananas)).await {
                     nay!("Failed to send crate reward message: {}", e);
                 }
                 userfile.add_bananas(bananas);
+                userfile.add_progress(Stat::CratesOpened, 1);
+                achievements::announce(&ctx.http, user.id, channel).await;
             }
             return;
         }
@@ -351,6 +289,7 @@ impl EventHandler for Handler {
         if SUPERBOOST_MODE.load(Ordering::SeqCst) {
             gained_bananas *= SUPERBOOST;
         }
+        gained_bananas = (gained_bananas as f64 * userfile.get_perks().message_multiplier()) as u64;
 
         let msg_content = msg.content.to_ascii_lowercase();
 
@@ -391,6 +330,11 @@ impl EventHandler for Handler {
         }
 
         userfile.add_bananas(gained_bananas);
+        userfile.add_progress(Stat::MessagesSent, 1);
+        achievements::announce(&ctx.http, user.id, channel).await;
+        if let Some(guild) = msg.guild_id {
+            guildfile::GuildSettings::join_season(&guild, &mut userfile);
+        }
 
         // random monkey image check
         if thread_rng().gen_range(0..1000) == 0 {
@@ -407,6 +351,10 @@ impl EventHandler for Handler {
         // crate check
         if thread_rng().gen_range(0..100) == 0 {
             spawn_crate(&ctx, &channel).await;
+            if let Some(guild) = msg.guild_id {
+                notifications::notify_guild(guild, NotificationEvent::CrateSpawn,
+                                            format!("A banana crate has spawned in {}!", channel.mention()), Some(channel)).await;
+            }
         }
     }
 
@@ -414,12 +362,19 @@ impl EventHandler for Handler {
         // register commands here
         register_command(&ctx, help::register()).await;
         register_command(&ctx, banana::info::register()).await;
+        register_command(&ctx, claim::register_daily()).await;
+        register_command(&ctx, claim::register_weekly()).await;
+        register_command(&ctx, achievements_cmd::register()).await;
+        register_command(&ctx, quests_cmd::register()).await;
         register_command(&ctx, banana::levelup::register()).await;
         register_command(&ctx, banana::prestige::register()).await;
         register_command(&ctx, banana::leaderboard::register()).await;
+        register_command(&ctx, season_cmd::register()).await;
+        register_command(&ctx, clan_cmd::register()).await;
         register_command(&ctx, blackjack_cmd::register()).await;
         register_command(&ctx, banana::pay::register()).await;
         register_command(&ctx, banana::ascend::register()).await;
+        register_command(&ctx, perks_cmd::register()).await;
         register_command(&ctx, slots::register()).await;
         register_command(&ctx, fiftyfifty::register()).await;
         register_command(&ctx, mine::register()).await;
@@ -432,8 +387,26 @@ impl EventHandler for Handler {
         register_command(&ctx, unequip::register()).await;
         register_command(&ctx, pvp_command::register()).await;
         register_command(&ctx, join::register()).await;
+        register_command(&ctx, minions::register()).await;
+        register_command(&ctx, minion::register()).await;
+        register_command(&ctx, notifications_cmd::register()).await;
+        register_command(&ctx, expedition::register()).await;
+        register_command(&ctx, raid::register()).await;
+        register_command(&ctx, blackjack_table::register()).await;
+        register_command(&ctx, roulette::register()).await;
+        register_command(&ctx, crash::register()).await;
+        register_command(&ctx, duel::register()).await;
+        register_command(&ctx, monkey_race::register()).await;
+        register_command(&ctx, fair::register()).await;
+        register_command(&ctx, verify::register()).await;
 
         register_command(&ctx, admin::register()).await;
+        register_command(&ctx, admin_rake::register()).await;
+        register_command(&ctx, admin_lottery::register()).await;
+        register_command(&ctx, admin_quests::register()).await;
+        register_command(&ctx, admin_season::register()).await;
+        register_command(&ctx, admin_economy::register()).await;
+        register_command(&ctx, lottery::register()).await;
 
         yay!("{} is connected!", ready.user.name);
         ctx.set_presence(Some(ActivityData::playing("with banana")), OnlineStatus::Online);
@@ -498,6 +471,22 @@ impl EventHandler for Handler {
                         banana::info::run(&ctx, &command, &sender.id).await;
                         return;
                     }
+                    "daily" => {
+                        claim::run(&ctx, &command, &sender.id, RewardKind::Daily).await;
+                        return;
+                    }
+                    "weekly" => {
+                        claim::run(&ctx, &command, &sender.id, RewardKind::Weekly).await;
+                        return;
+                    }
+                    "achievements" => {
+                        achievements_cmd::run(command_options, &ctx, &command, &sender.id).await;
+                        return;
+                    }
+                    "quests" => {
+                        quests_cmd::run(command_options, &ctx, &command, &guild_id, &sender.id).await;
+                        return;
+                    }
                     "levelup" => {
                         banana::levelup::run(command_options, &ctx, &command, &sender.id).await;
                         return;
@@ -510,6 +499,14 @@ impl EventHandler for Handler {
                         banana::leaderboard::run(&ctx, &command).await;
                         return;
                     }
+                    "clan" => {
+                        clan_cmd::run(command_options, &ctx, &command, &sender.id).await;
+                        return;
+                    }
+                    "season" => {
+                        season_cmd::run(command_options, &ctx, &command, &guild_id).await;
+                        return;
+                    }
                     "pay" => {
                         banana::pay::run(command_options, &ctx, &command, &sender.id).await;
                         return;
@@ -518,6 +515,10 @@ impl EventHandler for Handler {
                         banana::ascend::run(&ctx, &command, &sender.id).await;
                         return;
                     }
+                    "perks" => {
+                        perks_cmd::run(command_options, &ctx, &command, &sender.id).await;
+                        return;
+                    }
                     "inventory" => {
                         inventory_cmd::run(&ctx, &command).await;
                         return;
@@ -554,10 +555,54 @@ impl EventHandler for Handler {
                         join::run(command_options, &ctx, &command, &sender.id).await;
                         return;
                     }
+                    "minions" => {
+                        minions::run(&ctx, &command).await;
+                        return;
+                    }
+                    "minion" => {
+                        minion::run(command_options, &ctx, &command).await;
+                        return;
+                    }
+                    "notifications" => {
+                        notifications_cmd::run(command_options, &ctx, &command, &guild_id).await;
+                        return;
+                    }
+                    "fair" => {
+                        fair::run(command_options, &ctx, &command).await;
+                        return;
+                    }
+                    "verify" => {
+                        verify::run(command_options, &ctx, &command).await;
+                        return;
+                    }
+                    "lottery" => {
+                        lottery::run(command_options, &ctx, &command, &guild_id, &sender.id).await;
+                        return;
+                    }
                     "admin_channel" => {
                         admin::run(command_options, &ctx, &command, &guild_id).await;
                         return;
                     }
+                    "admin_rake" => {
+                        admin_rake::run(command_options, &ctx, &command, &guild_id).await;
+                        return;
+                    }
+                    "admin_lottery" => {
+                        admin_lottery::run(command_options, &ctx, &command, &guild_id).await;
+                        return;
+                    }
+                    "admin_quests" => {
+                        admin_quests::run(command_options, &ctx, &command, &guild_id).await;
+                        return;
+                    }
+                    "admin_season" => {
+                        admin_season::run(command_options, &ctx, &command, &guild_id).await;
+                        return;
+                    }
+                    "admin_economy" => {
+                        admin_economy::run(command_options, &ctx, &command, &guild_id, &sender.id).await;
+                        return;
+                    }
                     _ => {}
                 }
 
@@ -567,15 +612,36 @@ impl EventHandler for Handler {
                         "blackjack" => {
                             blackjack_cmd::run(command_options, &ctx, &command, &sender.id).await;
                         }
+                        "blackjack_table" => {
+                            blackjack_table::run(command_options, &ctx, &command, &sender.id).await;
+                        }
                         "slots" => {
                             slots::run(command_options, &ctx, &command, &sender.id).await;
                         }
                         "fiftyfifty" => {
                             fiftyfifty::run(&ctx, &command, &sender.id).await;
                         }
+                        "roulette" => {
+                            roulette::run(command_options, &ctx, &command, &channel, &sender.id).await;
+                        }
+                        "crash" => {
+                            crash::run(command_options, &ctx, &command, &channel, &sender.id).await;
+                        }
+                        "duel" => {
+                            duel::run(command_options, &ctx, &command, &sender.id).await;
+                        }
+                        "monkey_race" => {
+                            monkey_race::run(command_options, &ctx, &command, &channel, &sender.id).await;
+                        }
                         "mine" => {
                             mine::run(command_options, &ctx, &channel, command.clone(), &sender.id).await;
                         }
+                        "expedition" => {
+                            expedition::run(command_options, &ctx, &command, &sender.id).await;
+                        }
+                        "raid" => {
+                            raid::run(command_options, &ctx, &command, &sender.id).await;
+                        }
                         _ => {
                             command_response(&ctx, &command, "That do be a monkey brain moment").await;
                         }
@@ -591,6 +657,14 @@ impl EventHandler for Handler {
                     command_response(&ctx, &command, format!("That command is only allowed in the following channel(s): {}", allowed_channels.join(", "))).await;
                 }
             }
+            Interaction::Component(component) => {
+                let id = component.data.custom_id.as_str();
+                if id.starts_with(crash::CASH_OUT_ID) {
+                    crash::cash_out(&ctx, &component).await;
+                } else if id.starts_with(duel::ACCEPT_ID) || id.starts_with(duel::DECLINE_ID) {
+                    duel::respond(&ctx, &component).await;
+                }
+            }
             _ => {}
         }
     }
@@ -626,8 +700,10 @@ async fn main() {
         Ensure the following directories exist:
         ./guilds
         ./users
+        ./clans
+        ./audit
     */
-    let paths = vec!["./guilds", "./users"];
+    let paths = vec!["./guilds", "./users", "./clans", "./audit"];
     for path in paths {
         if !std::path::Path::new(path).exists() {
             std::fs::create_dir(path).expect("Failed to create directory");
@@ -654,6 +730,11 @@ async fn main() {
             return;
         };
 
+    // spawn an async thread to deliver notifications
+    tokio::spawn(notifications::notification_scheduler(client.http.clone()));
+    tokio::spawn(ape_bot_v2::lottery::lottery_scheduler(client.http.clone()));
+    tokio::spawn(ape_bot_v2::seasons::season_scheduler(client.http.clone()));
+
     if let Err(err) = client.start().await {
         nay!("Client error: {}", err);
     }
@@ -0,0 +1,203 @@
+use std::fs;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use crate::hey;
+use crate::inventory::item::InventoryItem;
+use crate::inventory::minion::MinionType;
+
+lazy_static!(
+    // bet limits and tier locks are checked on every bet and mine, so the file is only read once
+    static ref MILESTONES: MilestoneFile = MilestoneFile::load();
+);
+
+// content a milestone opens up for as long as it stays reached
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum Unlock {
+    // the mine tier can't be mined until the milestone is reached
+    MineTier(u8),
+    // raises the base bet limit, does nothing without one
+    BetLimit(u64),
+}
+
+// paid once, the first time the milestone is reached
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct MilestoneReward {
+    #[serde(default)]
+    pub super_nanners: u16,
+    #[serde(default)]
+    pub items: Vec<InventoryItem>,
+    #[serde(default)]
+    pub minion: Option<MinionType>,
+    #[serde(default)]
+    pub inventory_slots: u8,
+}
+
+fn first_prestige() -> u16 {
+    1
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Milestone {
+    pub id: String,
+    pub name: String,
+    #[serde(default = "first_prestige")]
+    pub prestige: u16,
+    pub level: u16,
+    #[serde(default)]
+    pub reward: MilestoneReward,
+    #[serde(default)]
+    pub unlocks: Vec<Unlock>,
+}
+
+// ./milestones.json
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct MilestoneFile {
+    // the most anyone can bet before milestones raise it, bets aren't limited when it's left out
+    #[serde(default)]
+    pub base_bet_limit: Option<u64>,
+    // sorted in the order they are reached
+    pub milestones: Vec<Milestone>,
+}
+
+impl MilestoneFile {
+    // ./milestones.json as it was at startup
+    pub fn get() -> &'static Self {
+        &MILESTONES
+    }
+
+    fn load() -> Self {
+        let Ok(file) = fs::read_to_string("./milestones.json") else {
+            hey!("No milestones found in ./milestones.json");
+            return Self::default();
+        };
+        let mut loaded: Self = match serde_json::from_str(&file) {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                hey!("Failed to load milestones: {}", e);
+                return Self::default();
+            }
+        };
+        loaded.milestones.sort_by_key(|m| (m.prestige, m.level));
+        loaded
+    }
+
+    // the highest bet limit out of the reached milestones, unlimited without a base limit
+    pub fn max_bet(&self, level: u16, prestige: u16, ascension: u16) -> u64 {
+        let Some(base) = self.base_bet_limit else {
+            return u64::MAX;
+        };
+        self.milestones.iter()
+            .filter(|m| m.is_reached(level, prestige, ascension))
+            .flat_map(|m| m.unlocks.iter())
+            .filter_map(|u| if let Unlock::BetLimit(limit) = u { Some(*limit) } else { None })
+            .fold(base, u64::max)
+    }
+}
+
+impl Milestone {
+    // every milestone in ./milestones.json, in the order they are reached
+    pub fn all() -> &'static [Self] {
+        &MilestoneFile::get().milestones
+    }
+
+    // ascending resets prestige, so ascended users keep every prestige milestone
+    pub fn is_reached(&self, level: u16, prestige: u16, ascension: u16) -> bool {
+        ascension > 0 || (prestige, level) >= (self.prestige, self.level)
+    }
+
+    pub fn requirement(&self) -> String {
+        if self.prestige > 1 {
+            format!("Prestige {} level {}", self.prestige, self.level)
+        } else {
+            format!("Level {}", self.level)
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if self.reward.super_nanners > 0 {
+            parts.push(format!("{}:zap:", self.reward.super_nanners));
+        }
+        for item in &self.reward.items {
+            parts.push(item.to_string());
+        }
+        if let Some(kind) = &self.reward.minion {
+            parts.push(format!("a {} Minion", kind));
+        }
+        if self.reward.inventory_slots > 0 {
+            parts.push(format!("{} inventory slots", self.reward.inventory_slots));
+        }
+        for unlock in &self.unlocks {
+            parts.push(match unlock {
+                Unlock::MineTier(tier) => format!("access to mine tier {}", tier),
+                Unlock::BetLimit(limit) => format!("a {}:banana: bet limit", limit),
+            });
+        }
+        parts.join(", ")
+    }
+}
+
+// one line per milestone for embeds
+pub fn summary(milestones: &[Milestone]) -> String {
+    milestones.iter()
+        .map(|m| format!("**{}** - {}", m.name, m.describe()))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+// the reward items that were lost to a full inventory
+pub fn missed_summary(items: &[InventoryItem]) -> String {
+    let items = items.iter().map(|i| i.to_string()).collect::<Vec<String>>().join(", ");
+    format!(":x: {} didn't fit, your inventory is full!", items)
+}
+
+// the milestone still needed to mine a tier, if any
+pub fn tier_lock(milestones: &[Milestone], tier: u8, level: u16, prestige: u16, ascension: u16) -> Option<&Milestone> {
+    let unlocked_by = milestones.iter()
+        .filter(|m| m.unlocks.contains(&Unlock::MineTier(tier)))
+        .collect::<Vec<&Milestone>>();
+    if unlocked_by.iter().any(|m| m.is_reached(level, prestige, ascension)) {
+        return None;
+    }
+    unlocked_by.first().copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn milestone(id: &str, prestige: u16, level: u16, unlocks: Vec<Unlock>) -> Milestone {
+        Milestone { id: id.to_string(), name: id.to_string(), prestige, level, reward: MilestoneReward::default(), unlocks }
+    }
+
+    #[test]
+    fn unlocks_follow_level_prestige_and_ascension() {
+        let milestones = vec![
+            milestone("level_50", 1, 50, vec![Unlock::BetLimit(1_000_000)]),
+            milestone("prestige_2", 2, 1, vec![Unlock::MineTier(3), Unlock::BetLimit(5_000_000)]),
+        ];
+        let mut file = MilestoneFile { base_bet_limit: None, milestones: milestones.clone() };
+        assert_eq!(file.max_bet(50, 1, 0), u64::MAX);
+        file.base_bet_limit = Some(250_000);
+        assert_eq!(file.max_bet(10, 1, 0), 250_000);
+        assert_eq!(file.max_bet(50, 1, 0), 1_000_000);
+        assert_eq!(file.max_bet(1, 2, 0), 5_000_000);
+        assert!(tier_lock(&milestones, 3, 100, 1, 0).is_some());
+        assert!(tier_lock(&milestones, 2, 1, 1, 0).is_none());
+
+        // prestiging resets the level but the level 50 milestone stays reached
+        assert!(milestones[0].is_reached(1, 2, 0));
+        assert!(tier_lock(&milestones, 3, 1, 2, 0).is_none());
+        // ascending resets prestige too
+        assert!(milestones[1].is_reached(1, 1, 1));
+    }
+
+    #[test]
+    fn bundled_milestones_are_valid() {
+        let milestones = serde_json::from_str::<MilestoneFile>(&fs::read_to_string("./milestones.json").unwrap()).unwrap().milestones;
+        for (i, milestone) in milestones.iter().enumerate() {
+            assert!(milestone.prestige >= 1 && milestone.level >= 1, "{}", milestone.id);
+            assert!(milestones[..i].iter().all(|m| m.id != milestone.id), "duplicate id {}", milestone.id);
+        }
+    }
+}
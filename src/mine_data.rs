@@ -3,6 +3,7 @@ use std::fs;
 use std::ops::RangeInclusive;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
+use crate::games::rng::GameRng;
 use crate::inventory::item::InventoryItem;
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -10,12 +11,14 @@ pub struct DropTable {
     pub sludge: RangeInclusive<u32>,
     pub super_nanners: Option<RangeInclusive<u32>>,
     pub items: Vec<InventoryItem>,
+    // parts used to upgrade the super drill, only dropped by some creatures
+    #[serde(default)]
+    pub drill_parts: Option<RangeInclusive<u32>>,
 }
 
 impl DropTable {
 
-    pub fn random_item(&self) -> InventoryItem {
-        let mut rng = rand::thread_rng();
+    pub fn random_item(&self, rng: &mut GameRng) -> InventoryItem {
         if self.items.len() == 0 {
             return InventoryItem::HealingPotion { health: 10 };
         }
@@ -25,6 +28,23 @@ impl DropTable {
 
 }
 
+// special moves a creature can use on its turn, chances are percentages rolled every turn
+#[derive(Serialize, Deserialize, Clone)]
+pub enum Ability {
+    // damages the player at the start of each of their turns
+    Poison { chance: u32, damage: u32, turns: u32 },
+    // the player loses their next turns
+    Stun { chance: u32, turns: u32 },
+    // heals the creature for a percentage of the damage it deals
+    Lifesteal { percent: u32 },
+    // deals more damage once its health drops below a percentage
+    Enrage { below_percent: u32, damage_multiplier: f32 },
+    // calls in a weaker creature that must be killed first
+    Summon { chance: u32, name: String, health: RangeInclusive<u32>, damage: RangeInclusive<u32>, max: u32 },
+    // absorbs incoming damage until broken
+    Shield { chance: u32, amount: u32 },
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Enemy {
     pub name: String,
@@ -32,9 +52,44 @@ pub struct Enemy {
     pub damage: RangeInclusive<u32>,
     pub reward_scaling: bool,
     pub thumbnail: String,
+    #[serde(default)]
+    pub abilities: Vec<Ability>,
     pub drops: DropTable,
 }
 
+#[derive(Serialize, Deserialize, Clone)]
+pub struct MerchantOffer {
+    pub item: InventoryItem,
+    pub price: u64,
+}
+
+// settings for multi-stage expeditions into a tier, chances are percentages rolled at each depth
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ExpeditionConfig {
+    pub depth: u8,
+    pub cave_in_chance: u32,
+    pub cave_in_damage: RangeInclusive<u32>,
+    pub chamber_chance: u32,
+    pub merchant_chance: u32,
+    pub creature_chance: u32,
+    // sludge found at each depth, multiplied by how deep the player is
+    pub sludge_per_depth: RangeInclusive<u32>,
+    pub merchant_offers: Vec<MerchantOffer>,
+    pub boss: Enemy,
+}
+
+impl ExpeditionConfig {
+
+    pub fn random_offer(&self, rng: &mut GameRng) -> Option<MerchantOffer> {
+        if self.merchant_offers.is_empty() {
+            return None;
+        }
+        let index = rng.gen_range(0..self.merchant_offers.len());
+        Some(self.merchant_offers[index].clone())
+    }
+
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct MineTier {
     pub required_super_drill_tier: u8,
@@ -42,12 +97,13 @@ pub struct MineTier {
     pub super_nanner_chance: u32,
     pub creatures: Vec<Enemy>,
     pub drop_table: DropTable,
+    #[serde(default)]
+    pub expedition: Option<ExpeditionConfig>,
 }
 
 impl MineTier {
 
-    pub fn random_enemy(&self) -> &Enemy {
-        let mut rng = rand::thread_rng();
+    pub fn random_enemy(&self, rng: &mut GameRng) -> &Enemy {
         let index = rng.gen_range(0..self.creatures.len());
         &self.creatures[index]
     }
@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use serenity::all::{ChannelId, Colour, CreateEmbed, CreateEmbedFooter, CreateMessage, GuildId, Http, Mentionable, Timestamp, UserId};
+use tokio::sync::Mutex;
+use crate::{nay, userfile};
+
+// how often queued notifications are delivered
+const DELIVERY_INTERVAL_SECS: u64 = 5;
+// how often every user's minions are checked for being full
+const MINION_CHECK_INTERVAL_SECS: i64 = 600;
+
+lazy_static!(
+    static ref PENDING: Mutex<Vec<Notification>> = Mutex::new(Vec::new());
+    // the last time each user was sent each kind of notification
+    static ref LAST_SENT: Mutex<HashMap<(UserId, NotificationEvent), Timestamp>> = Mutex::new(HashMap::new());
+);
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NotificationEvent {
+    MinionsFull,
+    MineComplete,
+    CrateSpawn,
+    PvPChallenge,
+    ClanInvite,
+}
+
+impl NotificationEvent {
+
+    pub fn values() -> Vec<Self> {
+        vec![
+            NotificationEvent::MinionsFull,
+            NotificationEvent::MineComplete,
+            NotificationEvent::CrateSpawn,
+            NotificationEvent::PvPChallenge,
+            NotificationEvent::ClanInvite,
+        ]
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "minions_full" => Some(NotificationEvent::MinionsFull),
+            "mine_complete" => Some(NotificationEvent::MineComplete),
+            "crate_spawn" => Some(NotificationEvent::CrateSpawn),
+            "pvp_challenge" => Some(NotificationEvent::PvPChallenge),
+            "clan_invite" => Some(NotificationEvent::ClanInvite),
+            _ => None,
+        }
+    }
+
+    // minimum seconds between two notifications of the same kind to the same user
+    pub fn cooldown(&self) -> i64 {
+        match self {
+            NotificationEvent::MinionsFull => 6 * 60 * 60,
+            NotificationEvent::MineComplete => 60,
+            NotificationEvent::CrateSpawn => 30 * 60,
+            NotificationEvent::PvPChallenge => 60,
+            NotificationEvent::ClanInvite => 60,
+        }
+    }
+
+    // mining already pinged in the channel before notifications existed, everything else is opt in
+    pub fn default_mode(&self) -> NotifyMode {
+        match self {
+            NotificationEvent::MineComplete | NotificationEvent::PvPChallenge | NotificationEvent::ClanInvite => NotifyMode::Channel,
+            NotificationEvent::MinionsFull | NotificationEvent::CrateSpawn => NotifyMode::Off,
+        }
+    }
+}
+
+impl Display for NotificationEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NotificationEvent::MinionsFull => write!(f, "Minions Full"),
+            NotificationEvent::MineComplete => write!(f, "Mining Complete"),
+            NotificationEvent::CrateSpawn => write!(f, "Crate Spawned"),
+            NotificationEvent::PvPChallenge => write!(f, "PvP Challenge"),
+            NotificationEvent::ClanInvite => write!(f, "Clan Invite"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum NotifyMode {
+    Dm,
+    Channel,
+    Off,
+}
+
+impl NotifyMode {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "dm" => Some(NotifyMode::Dm),
+            "channel" => Some(NotifyMode::Channel),
+            "off" => Some(NotifyMode::Off),
+            _ => None,
+        }
+    }
+}
+
+impl Display for NotifyMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NotifyMode::Dm => write!(f, "DM"),
+            NotifyMode::Channel => write!(f, "Channel Ping"),
+            NotifyMode::Off => write!(f, "Off"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct NotificationSettings {
+    modes: HashMap<NotificationEvent, NotifyMode>,
+    // where channel pings go for events that don't happen in a channel (set with /notifications)
+    pub channel: Option<u64>,
+    pub guild: Option<u64>,
+}
+
+impl NotificationSettings {
+    pub fn mode(&self, event: NotificationEvent) -> NotifyMode {
+        self.modes.get(&event).copied().unwrap_or(event.default_mode())
+    }
+
+    pub fn set_mode(&mut self, event: NotificationEvent, mode: NotifyMode) {
+        self.modes.insert(event, mode);
+    }
+}
+
+pub struct Notification {
+    pub user: UserId,
+    pub event: NotificationEvent,
+    pub message: String,
+    // the channel the event happened in, if any
+    pub channel: Option<ChannelId>,
+}
+
+// queue a notification, it will be delivered by the scheduler according to the user's preferences
+pub async fn notify<S: Into<String>>(user: UserId, event: NotificationEvent, message: S, channel: Option<ChannelId>) {
+    PENDING.lock().await.push(Notification { user, event, message: message.into(), channel });
+}
+
+// notify every user in a guild that opted into an event
+pub async fn notify_guild<S: Into<String>>(guild: GuildId, event: NotificationEvent, message: S, channel: Option<ChannelId>) {
+    let message = message.into();
+    for user in userfile::UserValues::all() {
+        let settings = &user.file.notifications;
+        if settings.guild == Some(guild.get()) && settings.mode(event) != NotifyMode::Off {
+            notify(user.id, event, message.clone(), channel).await;
+        }
+    }
+}
+
+async fn deliver(http: &Arc<Http>, notification: Notification) {
+    let settings = userfile::UserValues::get(&notification.user).file.notifications;
+
+    let embed = CreateEmbed::new()
+        .title(notification.event.to_string())
+        .description(notification.message)
+        .color(Colour::GOLD)
+        .footer(CreateEmbedFooter::new("Change these with /notifications"))
+        .timestamp(Timestamp::now());
+
+    match settings.mode(notification.event) {
+        NotifyMode::Off => {}
+        NotifyMode::Dm => {
+            if let Err(e) = notification.user.direct_message(http, CreateMessage::new().embed(embed)).await {
+                nay!("Failed to send notification DM to {}: {}", notification.user, e);
+            }
+        }
+        NotifyMode::Channel => {
+            let Some(channel) = notification.channel.or(settings.channel.map(ChannelId::from)) else {
+                return;
+            };
+            let message = CreateMessage::new()
+                .content(format!("{}", notification.user.mention()))
+                .embed(embed);
+            if let Err(e) = channel.send_message(http, message).await {
+                nay!("Failed to send notification to {}: {}", notification.user, e);
+            }
+        }
+    }
+}
+
+// queue a notification for every user with a full minion
+async fn check_minions() {
+    for user in userfile::UserValues::all() {
+        if user.file.notifications.mode(NotificationEvent::MinionsFull) == NotifyMode::Off {
+            continue;
+        }
+        let capacity = user.file.perks.minion_capacity();
+        let full = user.file.inventory.get_minions().iter().filter(|m| m.is_full(capacity)).count();
+        if full > 0 {
+            notify(user.id, NotificationEvent::MinionsFull,
+                   format!("{} of your minions are full! Collect their sludge with `/collect_minions`", full), None).await;
+        }
+    }
+}
+
+// delivers queued notifications in the background, skipping any that are still on cooldown
+pub async fn notification_scheduler(http: Arc<Http>) {
+    let mut last_minion_check = Timestamp::now();
+    loop {
+        tokio::time::sleep(tokio::time::Duration::from_secs(DELIVERY_INTERVAL_SECS)).await;
+
+        if Timestamp::now().unix_timestamp() - last_minion_check.unix_timestamp() >= MINION_CHECK_INTERVAL_SECS {
+            last_minion_check = Timestamp::now();
+            check_minions().await;
+        }
+
+        let pending = std::mem::take(&mut *PENDING.lock().await);
+        for notification in pending {
+            let key = (notification.user, notification.event);
+            {
+                let mut last_sent = LAST_SENT.lock().await;
+                if let Some(time) = last_sent.get(&key) {
+                    if Timestamp::now().unix_timestamp() - time.unix_timestamp() < notification.event.cooldown() {
+                        continue;
+                    }
+                }
+                last_sent.insert(key, Timestamp::now());
+            }
+
+            deliver(&http, notification).await;
+        }
+    }
+}
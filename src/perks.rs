@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use serde::{Deserialize, Serialize};
+
+pub const POINTS_PER_ASCENSION: u32 = 3;
+pub const MAX_RANK: u8 = 5;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Perk {
+    MessageGains,
+    VoiceGains,
+    MinionCapacity,
+    CrateLuck,
+    CombatHealth,
+    ItemFind,
+}
+
+impl Perk {
+
+    pub fn values() -> Vec<Self> {
+        vec![
+            Perk::MessageGains,
+            Perk::VoiceGains,
+            Perk::MinionCapacity,
+            Perk::CrateLuck,
+            Perk::CombatHealth,
+            Perk::ItemFind,
+        ]
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "message_gains" => Some(Perk::MessageGains),
+            "voice_gains" => Some(Perk::VoiceGains),
+            "minion_capacity" => Some(Perk::MinionCapacity),
+            "crate_luck" => Some(Perk::CrateLuck),
+            "combat_health" => Some(Perk::CombatHealth),
+            "item_find" => Some(Perk::ItemFind),
+            _ => None,
+        }
+    }
+
+    // what every rank adds
+    pub fn description(&self) -> &'static str {
+        match self {
+            Perk::MessageGains => "+20% bananas from messages",
+            Perk::VoiceGains => "+20% bananas from voice channels",
+            Perk::MinionCapacity => "+10% sludge your minions can hold",
+            Perk::CrateLuck => "+10% bananas from crates",
+            Perk::CombatHealth => "+10 health in mine fights, expeditions and raids",
+            Perk::ItemFind => "+5% chance to find items while mining and from creatures",
+        }
+    }
+}
+
+impl Display for Perk {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Perk::MessageGains => write!(f, "Message Gains"),
+            Perk::VoiceGains => write!(f, "Voice Gains"),
+            Perk::MinionCapacity => write!(f, "Minion Capacity"),
+            Perk::CrateLuck => write!(f, "Crate Luck"),
+            Perk::CombatHealth => write!(f, "Combat Health"),
+            Perk::ItemFind => write!(f, "Item Find"),
+        }
+    }
+}
+
+// a user's perk ranks, saved in their user file
+// points come from ascending so they don't need saving, whatever isn't spent is available
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct PerkTree {
+    ranks: HashMap<Perk, u8>,
+}
+
+impl PerkTree {
+
+    pub fn rank(&self, perk: Perk) -> u8 {
+        self.ranks.get(&perk).copied().unwrap_or(0)
+    }
+
+    pub fn spent(&self) -> u32 {
+        self.ranks.values().map(|r| *r as u32).sum()
+    }
+
+    pub fn available(&self, ascension: u16) -> u32 {
+        (ascension as u32 * POINTS_PER_ASCENSION).saturating_sub(self.spent())
+    }
+
+    // returns the perk's new rank
+    pub fn allocate(&mut self, perk: Perk, points: u8, ascension: u16) -> Result<u8, String> {
+        if points as u32 > self.available(ascension) {
+            return Err(format!("You only have {} perk point(s), ascend to earn more", self.available(ascension)));
+        }
+        let rank = self.rank(perk) + points;
+        if rank > MAX_RANK {
+            return Err(format!("{} can only go up to rank {}", perk, MAX_RANK));
+        }
+        self.ranks.insert(perk, rank);
+        Ok(rank)
+    }
+
+    fn scale(&self, perk: Perk, percent_per_rank: u32) -> f64 {
+        1.0 + (self.rank(perk) as u32 * percent_per_rank) as f64 / 100.0
+    }
+
+    pub fn message_multiplier(&self) -> f64 {
+        self.scale(Perk::MessageGains, 20)
+    }
+
+    pub fn voice_multiplier(&self) -> f64 {
+        self.scale(Perk::VoiceGains, 20)
+    }
+
+    pub fn minion_capacity(&self) -> f64 {
+        self.scale(Perk::MinionCapacity, 10)
+    }
+
+    pub fn crate_multiplier(&self) -> f64 {
+        self.scale(Perk::CrateLuck, 10)
+    }
+
+    pub fn bonus_health(&self) -> u32 {
+        self.rank(Perk::CombatHealth) as u32 * 10
+    }
+
+    // percent added to the chance of finding an item
+    pub fn item_find_chance(&self) -> u32 {
+        self.rank(Perk::ItemFind) as u32 * 5
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points_come_from_ascensions_and_ranks_are_capped() {
+        let mut tree = PerkTree::default();
+        assert!(tree.allocate(Perk::CrateLuck, 1, 0).is_err());
+        assert_eq!(tree.available(2), 6);
+
+        assert_eq!(tree.allocate(Perk::CrateLuck, 4, 2), Ok(4));
+        assert!(tree.allocate(Perk::CrateLuck, 2, 2).is_err());
+        assert!(tree.allocate(Perk::ItemFind, 3, 2).is_err());
+        assert_eq!(tree.allocate(Perk::CrateLuck, 1, 2), Ok(5));
+        assert_eq!(tree.available(2), 1);
+
+        assert_eq!(tree.crate_multiplier(), 1.5);
+        assert_eq!(tree.message_multiplier(), 1.0);
+        assert_eq!(tree.bonus_health(), 0);
+    }
+}
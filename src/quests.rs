@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+use std::fs;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use crate::achievements::Stat;
+use crate::games::rng::GameRng;
+use crate::hey;
+
+pub const QUESTS_PER_BOARD: usize = 3;
+// guild rewards can be at most this many times the quest's default
+pub const MAX_REWARD_MULTIPLIER: u64 = 5;
+const DAY: i64 = 86400;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct QuestTemplate {
+    pub id: String,
+    pub description: String,
+    pub stat: Stat,
+    pub goal: u64,
+    pub bananas: u64,
+    #[serde(default)]
+    pub super_nanners: u16,
+}
+
+impl QuestTemplate {
+    // every quest in ./quests.json, guilds can turn them off or change their rewards
+    pub fn all() -> Vec<Self> {
+        let Ok(file) = fs::read_to_string("./quests.json") else {
+            hey!("No quests found in ./quests.json");
+            return Vec::new();
+        };
+        match serde_json::from_str(&file) {
+            Ok(quests) => quests,
+            Err(e) => {
+                hey!("Failed to load quests: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    // the most bananas a guild can set the quest to reward
+    pub fn max_reward(&self) -> u64 {
+        self.bananas.saturating_mul(MAX_REWARD_MULTIPLIER)
+    }
+}
+
+// a guild's quest pool and when its boards refresh, saved in the guild file
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct QuestSettings {
+    // hour of the day (UTC) boards refresh
+    pub reset_hour: u8,
+    pub disabled: Vec<String>,
+    // banana rewards that replace the default for a quest
+    pub rewards: HashMap<String, u64>,
+}
+
+impl QuestSettings {
+
+    // the quests boards are drawn from, with this guild's rewards
+    pub fn pool(&self, templates: Vec<QuestTemplate>) -> Vec<QuestTemplate> {
+        templates.into_iter()
+            .filter(|q| !self.disabled.contains(&q.id))
+            .map(|mut q| {
+                if let Some(bananas) = self.rewards.get(&q.id) {
+                    q.bananas = (*bananas).min(q.max_reward());
+                }
+                q
+            })
+            .collect()
+    }
+
+    pub fn next_reset(&self, now: i64) -> i64 {
+        let offset = self.reset_hour as i64 * 3600;
+        ((now - offset).div_euclid(DAY) + 1) * DAY + offset
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Quest {
+    pub template: QuestTemplate,
+    pub progress: u64,
+    pub claimed: bool,
+}
+
+impl Quest {
+    pub fn is_complete(&self) -> bool {
+        self.progress >= self.template.goal
+    }
+}
+
+// a user's daily quests, saved in their user file
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct QuestBoard {
+    pub quests: Vec<Quest>,
+    // the guild the board was drawn for, its settings are used when it refreshes
+    pub guild: u64,
+    pub expires: i64,
+}
+
+impl QuestBoard {
+
+    pub fn draw(pool: &[QuestTemplate], guild: u64, expires: i64, rng: &mut GameRng) -> Self {
+        let quests = pool.choose_multiple(rng, QUESTS_PER_BOARD)
+            .map(|template| Quest { template: template.clone(), progress: 0, claimed: false })
+            .collect();
+        Self { quests, guild, expires }
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        now >= self.expires
+    }
+
+    pub fn progress(&mut self, stat: &Stat, amount: u64) {
+        for quest in self.quests.iter_mut().filter(|q| q.template.stat.key() == stat.key()) {
+            quest.progress = quest.progress.saturating_add(amount).min(quest.template.goal);
+        }
+    }
+
+    // marks every finished quest as claimed, returns the bananas, super nanners and quests claimed
+    pub fn claim(&mut self) -> (u64, u16, usize) {
+        let mut reward: (u64, u16, usize) = (0, 0, 0);
+        for quest in self.quests.iter_mut().filter(|q| q.is_complete() && !q.claimed) {
+            quest.claimed = true;
+            reward.0 = reward.0.saturating_add(quest.template.bananas);
+            reward.1 = reward.1.saturating_add(quest.template.super_nanners);
+            reward.2 += 1;
+        }
+        reward
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boards_track_and_pay_out_once() {
+        let mut settings = QuestSettings::default();
+        settings.disabled.push("chatter".to_string());
+        settings.rewards.insert("pvp_win".to_string(), 123);
+        settings.rewards.insert("mine_tier_1".to_string(), u64::MAX);
+
+        let pool = settings.pool(QuestTemplate::all());
+        assert!(pool.len() >= QUESTS_PER_BOARD);
+        assert!(pool.iter().all(|q| q.id != "chatter"));
+        assert_eq!(pool.iter().find(|q| q.id == "pvp_win").unwrap().bananas, 123);
+        let default = QuestTemplate::all().into_iter().find(|q| q.id == "mine_tier_1").unwrap();
+        assert_eq!(pool.iter().find(|q| q.id == "mine_tier_1").unwrap().bananas, default.max_reward());
+
+        let pvp = pool.iter().find(|q| q.id == "pvp_win").unwrap().clone();
+        let mut board = QuestBoard { quests: vec![Quest { template: pvp, progress: 0, claimed: false }], guild: 1, expires: 100 };
+        board.progress(&Stat::BlackjackWins, 1);
+        assert_eq!(board.claim(), (0, 0, 0));
+        board.progress(&Stat::PvPWins, 5);
+        assert_eq!(board.quests[0].progress, 1);
+        assert_eq!(board.claim(), (123, 0, 1));
+        assert_eq!(board.claim(), (0, 0, 0));
+        assert!(board.is_expired(100));
+    }
+
+    #[test]
+    fn boards_reset_at_the_guilds_hour() {
+        let mut settings = QuestSettings::default();
+        assert_eq!(settings.next_reset(10), DAY);
+        assert_eq!(settings.next_reset(DAY), 2 * DAY);
+        settings.reset_hour = 6;
+        assert_eq!(settings.next_reset(3600), 6 * 3600);
+        assert_eq!(settings.next_reset(7 * 3600), DAY + 6 * 3600);
+    }
+}
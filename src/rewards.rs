@@ -0,0 +1,157 @@
+use std::fmt::Display;
+use serde::{Deserialize, Serialize};
+
+const DAY: i64 = 86400;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum RewardKind {
+    Daily,
+    Weekly,
+}
+
+impl RewardKind {
+    // how long until the next claim, a streak is lost once a whole period passes without one
+    pub fn period(&self) -> i64 {
+        match self {
+            RewardKind::Daily => DAY,
+            RewardKind::Weekly => 7 * DAY,
+        }
+    }
+
+    fn base_reward(&self) -> u64 {
+        match self {
+            RewardKind::Daily => 500,
+            RewardKind::Weekly => 5000,
+        }
+    }
+
+    // percent added per claim in a row, up to double the base
+    fn streak_bonus(&self) -> u64 {
+        match self {
+            RewardKind::Daily => 10,
+            RewardKind::Weekly => 25,
+        }
+    }
+
+    // bananas for a claim, scaled by the streak, prestige and ascension
+    pub fn reward(&self, streak: u32, prestige: u16, ascension: u16) -> u64 {
+        let bonus = (streak.saturating_sub(1) as u64 * self.streak_bonus()).min(100);
+        self.base_reward() * (100 + bonus) / 100 * prestige.max(1) as u64 * (ascension as u64 + 1)
+    }
+}
+
+impl Display for RewardKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RewardKind::Daily => write!(f, "Daily"),
+            RewardKind::Weekly => write!(f, "Weekly"),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub enum ClaimCheck {
+    TooSoon { next: i64 },
+    Continues,
+    // periods skipped since the streak could last have been kept going
+    Missed { periods: u32 },
+}
+
+pub struct ClaimOutcome {
+    pub streak: u32,
+    pub reward: u64,
+    pub freezes_used: u32,
+    // the streak that was lost, 0 if it carried on
+    pub lost: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Streak {
+    pub count: u32,
+    pub best: u32,
+    // unix time of the last claim, 0 if never claimed
+    pub last_claim: i64,
+}
+
+impl Streak {
+
+    pub fn next_claim(&self, kind: RewardKind) -> i64 {
+        if self.last_claim == 0 { 0 } else { self.last_claim + kind.period() }
+    }
+
+    pub fn check(&self, kind: RewardKind, now: i64) -> ClaimCheck {
+        if self.last_claim == 0 {
+            return ClaimCheck::Continues;
+        }
+        let elapsed = now - self.last_claim;
+        if elapsed < kind.period() {
+            return ClaimCheck::TooSoon { next: self.next_claim(kind) };
+        }
+        let missed = (elapsed / kind.period() - 1) as u32;
+        if missed == 0 { ClaimCheck::Continues } else { ClaimCheck::Missed { periods: missed } }
+    }
+
+    // returns the streak the claim is paid at
+    pub fn claim(&mut self, now: i64, keep_streak: bool) -> u32 {
+        self.count = if keep_streak { self.count + 1 } else { 1 };
+        self.best = self.best.max(self.count);
+        self.last_claim = now;
+        self.count
+    }
+}
+
+// a user's daily and weekly claims, saved in their user file
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct RewardStreaks {
+    pub daily: Streak,
+    pub weekly: Streak,
+}
+
+impl RewardStreaks {
+    pub fn get(&self, kind: RewardKind) -> &Streak {
+        match kind {
+            RewardKind::Daily => &self.daily,
+            RewardKind::Weekly => &self.weekly,
+        }
+    }
+
+    pub fn get_mut(&mut self, kind: RewardKind) -> &mut Streak {
+        match kind {
+            RewardKind::Daily => &mut self.daily,
+            RewardKind::Weekly => &mut self.weekly,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn streaks_break_after_a_missed_period() {
+        let mut streak = Streak::default();
+        assert_eq!(streak.check(RewardKind::Daily, 1000), ClaimCheck::Continues);
+        streak.claim(1000, true);
+
+        assert_eq!(streak.check(RewardKind::Daily, 1000 + DAY - 1), ClaimCheck::TooSoon { next: 1000 + DAY });
+        assert_eq!(streak.check(RewardKind::Daily, 1000 + DAY), ClaimCheck::Continues);
+        // late is fine as long as a whole day wasn't skipped
+        assert_eq!(streak.check(RewardKind::Daily, 1000 + 2 * DAY - 1), ClaimCheck::Continues);
+        assert_eq!(streak.check(RewardKind::Daily, 1000 + 2 * DAY), ClaimCheck::Missed { periods: 1 });
+        assert_eq!(streak.check(RewardKind::Daily, 1000 + 4 * DAY), ClaimCheck::Missed { periods: 3 });
+
+        assert_eq!(streak.claim(1000 + DAY, true), 2);
+        assert_eq!(streak.claim(1000 + 5 * DAY, false), 1);
+        assert_eq!(streak.best, 2);
+    }
+
+    #[test]
+    fn rewards_scale_with_streak_and_progress() {
+        assert_eq!(RewardKind::Daily.reward(1, 1, 0), 500);
+        assert_eq!(RewardKind::Daily.reward(6, 1, 0), 750);
+        // the streak bonus caps at double
+        assert_eq!(RewardKind::Daily.reward(50, 1, 0), 1000);
+        assert_eq!(RewardKind::Daily.reward(1, 3, 2), 500 * 3 * 3);
+        assert_eq!(RewardKind::Weekly.reward(3, 1, 0), 7500);
+    }
+}
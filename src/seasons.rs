@@ -0,0 +1,439 @@
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::sync::{Arc, Mutex};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use serenity::all::{ChannelId, Colour, CreateEmbed, CreateEmbedFooter, CreateMessage, Http, Mentionable, Timestamp, UserId};
+use crate::guildfile::GuildSettings;
+use crate::userfile::UserValues;
+use crate::{nay, say};
+
+pub const DEFAULT_SEASON_DAYS: i64 = 30;
+// seasons can't be shorter than this, even when ended early
+pub const MIN_SEASON_DAYS: i64 = 7;
+pub const DEFAULT_KEEP_PERCENT: u8 = 10;
+// how many of the top players are kept in the archive
+pub const ARCHIVED_PLAYERS: usize = 10;
+// bananas, super nanners and badge for the top three
+pub const SEASON_REWARDS: [(u64, u16, &str); 3] = [(100_000, 25, "Champion"), (50_000, 15, "Runner-up"), (25_000, 10, "Third Place")];
+// rewards are only paid when at least this many players gained something during the season
+pub const MIN_REWARDED_PLAYERS: usize = 5;
+const CHECK_INTERVAL_SECS: u64 = 60;
+
+lazy_static!(
+    // guild and user pairs known to be in the guild's current season, so chat doesn't read the guild file on every message
+    static ref JOINED: Mutex<HashSet<(u64, u64)>> = Mutex::new(HashSet::new());
+);
+
+pub fn has_joined(guild: u64, user: u64) -> bool {
+    JOINED.lock().is_ok_and(|joined| joined.contains(&(guild, user)))
+}
+
+pub fn mark_joined(guild: u64, user: u64) {
+    if let Ok(mut joined) = JOINED.lock() {
+        joined.insert((guild, user));
+    }
+}
+
+// players that carried nothing over leave when a season ends, so they have to be checked again
+pub fn forget_joined(guild: u64) {
+    if let Ok(mut joined) = JOINED.lock() {
+        joined.retain(|(g, _)| *g != guild);
+    }
+}
+
+// what a season end can soft reset in the guild's standings, global progress is never touched
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum SeasonField {
+    Bananas,
+    Level,
+    Prestige,
+}
+
+impl SeasonField {
+
+    pub fn values() -> Vec<Self> {
+        vec![SeasonField::Bananas, SeasonField::Level, SeasonField::Prestige]
+    }
+
+    // a list like `bananas, level`
+    pub fn parse_list(spec: &str) -> Result<Vec<Self>, String> {
+        let mut fields = Vec::new();
+        for part in spec.split([' ', ',']).filter(|p| !p.is_empty()) {
+            let field = match part.to_lowercase().as_str() {
+                "bananas" => SeasonField::Bananas,
+                "level" => SeasonField::Level,
+                "prestige" => SeasonField::Prestige,
+                "none" => continue,
+                _ => return Err(format!("`{}` can't be reset, pick from bananas, level and prestige", part)),
+            };
+            if !fields.contains(&field) {
+                fields.push(field);
+            }
+        }
+        Ok(fields)
+    }
+}
+
+impl Display for SeasonField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SeasonField::Bananas => write!(f, "Bananas"),
+            SeasonField::Level => write!(f, "Level"),
+            SeasonField::Prestige => write!(f, "Prestige"),
+        }
+    }
+}
+
+// keeps a percent of the value
+pub fn soft_reset(value: u64, keep_percent: u8) -> u64 {
+    (value as u128 * keep_percent.min(100) as u128 / 100) as u64
+}
+
+// a player's stats, either global or what counts towards a season
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Standing {
+    pub user: u64,
+    pub level: u16,
+    pub prestige: u16,
+    pub ascension: u16,
+    pub bananas: u64,
+}
+
+impl Standing {
+
+    pub fn of(user: &mut UserValues) -> Self {
+        // one reload covers the rest
+        let bananas = user.get_bananas();
+        Self {
+            user: user.id.get(),
+            level: user.file.level,
+            prestige: user.file.prestige,
+            ascension: user.file.ascension,
+            bananas,
+        }
+    }
+
+    // ascension carries over between seasons, so it doesn't count towards the ranking
+    pub fn describe(&self) -> String {
+        format!("{} Prestige: `{}` Level: `{}` Bananas: `{}`", UserId::new(self.user).mention(), self.prestige, self.level, self.bananas)
+    }
+
+    // what was gained since the earlier stats, levels count from scratch after a prestige or ascension
+    pub fn since(&self, earlier: &Standing) -> Self {
+        let ascended = self.ascension > earlier.ascension;
+        let prestiged = ascended || self.prestige > earlier.prestige;
+        Self {
+            user: self.user,
+            level: if prestiged { self.level } else { self.level.saturating_sub(earlier.level) },
+            prestige: if ascended { self.prestige } else { self.prestige.saturating_sub(earlier.prestige) },
+            ascension: self.ascension,
+            bananas: self.bananas.saturating_sub(earlier.bananas),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.level == 0 && self.prestige == 0 && self.bananas == 0
+    }
+}
+
+// a player in a guild's seasons
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct SeasonPlayer {
+    pub user: u64,
+    // the player's global stats when they joined this season
+    pub start: Standing,
+    // what the soft resets left from earlier seasons
+    pub carried: Standing,
+}
+
+impl SeasonPlayer {
+
+    pub fn new(current: Standing) -> Self {
+        Self {
+            user: current.user,
+            carried: Standing { user: current.user, level: 0, prestige: 0, ascension: current.ascension, bananas: 0 },
+            start: current,
+        }
+    }
+
+    // the player's season stats from their current global stats
+    pub fn standing(&self, current: &Standing) -> Standing {
+        let gained = current.since(&self.start);
+        Standing {
+            user: self.user,
+            level: self.carried.level.saturating_add(gained.level),
+            prestige: self.carried.prestige.saturating_add(gained.prestige),
+            ascension: current.ascension,
+            bananas: self.carried.bananas.saturating_add(gained.bananas),
+        }
+    }
+}
+
+// best first, by prestige, then level, then bananas
+pub fn rank(mut standings: Vec<Standing>) -> Vec<Standing> {
+    standings.sort_by_key(|s| std::cmp::Reverse((s.prestige, s.level, s.bananas)));
+    standings
+}
+
+fn rewards_paid() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SeasonRecord {
+    pub number: u32,
+    pub started: i64,
+    pub ended: i64,
+    pub standings: Vec<Standing>,
+    // false when too few players were active for the top three to be rewarded
+    #[serde(default = "rewards_paid")]
+    pub rewarded: bool,
+}
+
+// a guild's seasons, saved in its guild file
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Seasons {
+    // the current season, 0 until an admin starts the first one
+    pub number: u32,
+    pub started: i64,
+    pub ends: i64,
+    pub length_days: i64,
+    // where season ends are announced
+    pub channel: Option<u64>,
+    pub reset: Vec<SeasonField>,
+    pub keep_percent: u8,
+    // everyone who has been active in the guild this season or carried something over
+    #[serde(default)]
+    pub participants: Vec<SeasonPlayer>,
+    pub archive: Vec<SeasonRecord>,
+}
+
+impl Default for Seasons {
+    fn default() -> Self {
+        Self {
+            number: 0,
+            started: 0,
+            ends: 0,
+            length_days: DEFAULT_SEASON_DAYS,
+            channel: None,
+            reset: SeasonField::values(),
+            keep_percent: DEFAULT_KEEP_PERCENT,
+            participants: Vec::new(),
+            archive: Vec::new(),
+        }
+    }
+}
+
+impl Seasons {
+
+    pub fn is_running(&self) -> bool {
+        self.number > 0
+    }
+
+    pub fn is_due(&self, now: i64) -> bool {
+        self.is_running() && now >= self.ends
+    }
+
+    pub fn start(&mut self, now: i64) {
+        self.number += 1;
+        self.started = now;
+        self.ends = now + self.length_days.max(MIN_SEASON_DAYS) * 86400;
+    }
+
+    // the earliest the current season can end
+    pub fn earliest_end(&self) -> i64 {
+        self.started + MIN_SEASON_DAYS * 86400
+    }
+
+    pub fn is_participant(&self, user: u64) -> bool {
+        self.participants.iter().any(|p| p.user == user)
+    }
+
+    // season stats for every participant with current stats
+    pub fn standings(&self, current: &[Standing]) -> Vec<Standing> {
+        self.participants.iter()
+            .filter_map(|p| current.iter().find(|c| c.user == p.user).map(|c| p.standing(c)))
+            .collect()
+    }
+
+    pub fn record(&self, number: u32) -> Option<&SeasonRecord> {
+        self.archive.iter().find(|r| r.number == number)
+    }
+
+    // archives the top players, soft resets everyone's season stats and starts the next season
+    pub fn end(&mut self, current: &[Standing], now: i64) -> SeasonRecord {
+        // players with nothing to show for the season aren't ranked
+        let mut standings = rank(self.standings(current).into_iter().filter(|s| !s.is_empty()).collect());
+        let rewarded = standings.len() >= MIN_REWARDED_PLAYERS;
+        standings.truncate(ARCHIVED_PLAYERS);
+        let record = SeasonRecord { number: self.number, started: self.started, ended: now, standings, rewarded };
+        self.archive.push(record.clone());
+
+        let (reset, keep) = (self.reset.clone(), self.keep_percent);
+        for player in self.participants.iter_mut() {
+            let Some(now_stats) = current.iter().find(|c| c.user == player.user) else {
+                continue;
+            };
+            let mut carried = player.standing(now_stats);
+            for field in &reset {
+                match field {
+                    SeasonField::Bananas => carried.bananas = soft_reset(carried.bananas, keep),
+                    SeasonField::Level => carried.level = soft_reset(carried.level as u64, keep) as u16,
+                    SeasonField::Prestige => carried.prestige = soft_reset(carried.prestige as u64, keep) as u16,
+                }
+            }
+            player.carried = carried;
+            player.start = now_stats.clone();
+        }
+        // players have to be active again unless they carried something over
+        self.participants.retain(|p| !p.carried.is_empty());
+
+        self.start(now);
+        record
+    }
+}
+
+impl SeasonRecord {
+
+    // the top three and what they won, nobody when too few players were active
+    pub fn rewards(&self) -> Vec<(&(u64, u16, &'static str), &Standing)> {
+        if !self.rewarded {
+            return Vec::new();
+        }
+        SEASON_REWARDS.iter().zip(&self.standings).collect()
+    }
+
+    pub fn embed(&self) -> CreateEmbed {
+        let standings = if self.standings.is_empty() {
+            "Nobody played this season".to_string()
+        } else {
+            self.standings.iter().enumerate()
+                .map(|(i, s)| format!("**{}.** {}", i + 1, s.describe()))
+                .collect::<Vec<String>>()
+                .join("\n")
+        };
+
+        let mut embed = CreateEmbed::new()
+            .title(format!("Season {} Results", self.number))
+            .description(format!("<t:{}:d> to <t:{}:d>", self.started, self.ended))
+            .color(Colour::GOLD)
+            .field("Standings", standings, false);
+        for ((bananas, nanners, badge), standing) in self.rewards() {
+            embed = embed.field(*badge, format!("{} won {}:banana:, {}:zap: and a badge", UserId::new(standing.user).mention(), bananas, nanners), false);
+        }
+        if !self.rewarded && !self.standings.is_empty() {
+            embed = embed.field("Rewards", format!("At least {} players have to be active for the top three to be rewarded", MIN_REWARDED_PLAYERS), false);
+        }
+        embed.footer(CreateEmbedFooter::new("Brought to you by A.P.E. Inc©"))
+            .timestamp(Timestamp::now())
+    }
+}
+
+pub fn badge(number: u32, place: &str) -> String {
+    format!("Season {} {}", number, place)
+}
+
+// checks every guild's season in the background and ends any that are due
+pub async fn season_scheduler(http: Arc<Http>) {
+    loop {
+        tokio::time::sleep(tokio::time::Duration::from_secs(CHECK_INTERVAL_SECS)).await;
+
+        let now = Timestamp::now().unix_timestamp();
+        for mut guild in GuildSettings::all() {
+            let seasons = guild.get_seasons();
+            if !seasons.is_due(now) {
+                continue;
+            }
+
+            let current = seasons.participants.iter()
+                .map(|p| Standing::of(&mut UserValues::get(&UserId::new(p.user))))
+                .collect::<Vec<Standing>>();
+            let record = guild.end_season(&current, now);
+
+            for ((bananas, nanners, place), standing) in record.rewards() {
+                let mut userfile = UserValues::get(&UserId::new(standing.user));
+                userfile.add_bananas(*bananas);
+                userfile.add_super_nanners(*nanners);
+                userfile.add_badge(badge(record.number, place));
+            }
+            say!("Season {} ended in guild {} with {} player(s)", record.number, guild.id, seasons.participants.len());
+
+            if let Some(channel) = seasons.channel {
+                let next = guild.get_seasons();
+                let message = CreateMessage::new()
+                    .embed(record.embed()
+                        .field("Next Season", format!("Season {} ends <t:{}:R>", next.number, next.ends), false));
+                if let Err(e) = ChannelId::new(channel).send_message(&http, message).await {
+                    nay!("Failed to announce season end: {}", e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn standing(user: u64, prestige: u16, level: u16, bananas: u64) -> Standing {
+        Standing { user, level, prestige, ascension: 5, bananas }
+    }
+
+    #[test]
+    fn ending_archives_the_top_and_starts_the_next_season() {
+        let mut seasons = Seasons { length_days: 1, ..Default::default() };
+        assert!(!seasons.is_due(100));
+        seasons.start(0);
+        assert_eq!(seasons.ends, MIN_SEASON_DAYS * 86400);
+        assert!(!seasons.is_due(100));
+        assert!(seasons.is_due(7 * 86400));
+
+        // everyone joins with the same global stats, players who gained nothing aren't ranked
+        seasons.participants = (1..30).map(|id| SeasonPlayer::new(standing(id, 1, 1, 1000))).collect();
+        let mut current = vec![standing(1, 1, 91, 1010), standing(2, 3, 5, 1000), standing(3, 1, 91, 1500)];
+        current.extend((10..20).map(|id| standing(id, 1, 2, 1000)));
+        current.extend((20..30).map(|id| standing(id, 1, 1, 1000)));
+        let record = seasons.end(&current, 7 * 86400);
+
+        let order = record.standings.iter().map(|s| s.user).collect::<Vec<u64>>();
+        assert_eq!(order[..3], [2, 3, 1]);
+        assert_eq!(record.standings[0], Standing { user: 2, level: 5, prestige: 2, ascension: 5, bananas: 0 });
+        assert_eq!(record.standings.len(), ARCHIVED_PLAYERS);
+        assert!(record.standings.iter().all(|s| s.user < 20));
+        assert_eq!(record.rewards().len(), 3);
+        assert_eq!(seasons.number, 2);
+        assert_eq!(seasons.ends, 14 * 86400);
+        assert!(seasons.record(1).is_some());
+
+        // 10% of the season's stats carry over, players with nothing left have to be active again
+        let carried = seasons.participants.iter().map(|p| (p.user, p.carried.level, p.carried.bananas)).collect::<Vec<_>>();
+        assert_eq!(carried, vec![(1, 9, 1), (3, 9, 50)]);
+        assert_eq!(seasons.participants[0].standing(&standing(1, 1, 91, 1010)).level, 9);
+    }
+
+    #[test]
+    fn small_seasons_pay_no_rewards() {
+        let mut seasons = Seasons::default();
+        seasons.start(0);
+        seasons.participants = (1..=MIN_REWARDED_PLAYERS as u64).map(|id| SeasonPlayer::new(standing(id, 1, 1, 1000))).collect();
+
+        // one player short of the minimum, the idle player doesn't count
+        let mut current = (2..=MIN_REWARDED_PLAYERS as u64).map(|id| standing(id, 1, 1, 1001)).collect::<Vec<Standing>>();
+        current.push(standing(1, 1, 1, 1000));
+        let record = seasons.end(&current, 7 * 86400);
+        assert_eq!(record.standings.len(), MIN_REWARDED_PLAYERS - 1);
+        assert!(!record.rewarded);
+        assert!(record.rewards().is_empty());
+    }
+
+    #[test]
+    fn soft_resets_keep_a_share() {
+        assert_eq!(soft_reset(1000, 10), 100);
+        assert_eq!(soft_reset(u64::MAX, 100), u64::MAX);
+        assert_eq!(soft_reset(5, 10), 0);
+        assert_eq!(SeasonField::parse_list("level, Bananas level"), Ok(vec![SeasonField::Level, SeasonField::Bananas]));
+        assert_eq!(SeasonField::parse_list("none"), Ok(vec![]));
+        assert!(SeasonField::parse_list("ascension").is_err());
+    }
+}
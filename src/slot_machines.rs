@@ -0,0 +1,405 @@
+use std::collections::HashMap;
+use std::fs;
+use rand::Rng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use crate::games::rng::GameRng;
+use crate::hey;
+
+// played when no machine is picked
+pub const DEFAULT_MACHINE: &str = "classic";
+// how many times a losing spin is redrawn before settling for what it landed
+const MAX_REDRAWS: usize = 100;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SlotSymbol {
+    pub name: String,
+    pub emoji: String,
+    // substitutes for any symbol on a payline except scatters
+    #[serde(default)]
+    pub wild: bool,
+    // pays by how many land anywhere instead of on a payline
+    #[serde(default)]
+    pub scatter: bool,
+}
+
+// landing `count` of `symbol` pays `multiplier` times the line bet, or the whole bet for scatters
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Pay {
+    pub symbol: String,
+    pub count: usize,
+    pub multiplier: f32,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct JackpotConfig {
+    // percentage of every spin added to the server's jackpot pool
+    pub contribution_percent: f32,
+    // landing this many of the symbol on a payline wins the pool
+    pub symbol: String,
+    pub count: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SlotMachine {
+    pub name: String,
+    pub description: String,
+    pub min_bet: u64,
+    // how many symbols of each reel are shown
+    pub rows: usize,
+    pub symbols: Vec<SlotSymbol>,
+    // one strip of symbol names per reel, a spin shows `rows` symbols in a row from a random stop
+    pub reels: Vec<Vec<String>>,
+    // the row read on each reel, the bet is split evenly between the lines
+    pub paylines: Vec<Vec<usize>>,
+    pub paytable: Vec<Pay>,
+    #[serde(default)]
+    pub jackpot: Option<JackpotConfig>,
+    // the share of spins that land a win on the first line, every other spin is redrawn until nothing pays
+    #[serde(default)]
+    pub win_chance: Option<f64>,
+}
+
+// a single paying combination, scatter wins have no line
+pub struct SlotWin {
+    pub line: Option<usize>,
+    pub symbol: String,
+    pub count: usize,
+    pub winnings: u64,
+}
+
+pub struct SpinResult {
+    // the symbols shown, indexed by reel then row
+    pub grid: Vec<Vec<String>>,
+    pub wins: Vec<SlotWin>,
+    pub jackpot: bool,
+}
+
+impl SpinResult {
+    pub fn winnings(&self) -> u64 {
+        self.wins.iter().map(|w| w.winnings).sum()
+    }
+}
+
+impl SlotMachine {
+
+    pub fn symbol(&self, name: &str) -> Option<&SlotSymbol> {
+        self.symbols.iter().find(|s| s.name == name)
+    }
+
+    pub fn emoji(&self, name: &str) -> String {
+        self.symbol(name).map(|s| s.emoji.clone()).unwrap_or(name.to_string())
+    }
+
+    fn is_wild(&self, name: &str) -> bool {
+        self.symbol(name).map(|s| s.wild).unwrap_or(false)
+    }
+
+    fn is_scatter(&self, name: &str) -> bool {
+        self.symbol(name).map(|s| s.scatter).unwrap_or(false)
+    }
+
+    // the best multiplier for landing `count` of a symbol
+    fn pay(&self, symbol: &str, count: usize) -> f32 {
+        self.paytable.iter()
+            .filter(|p| p.symbol == symbol && p.count <= count)
+            .map(|p| p.multiplier)
+            .fold(0.0, f32::max)
+    }
+
+    // checks the machine only references symbols, rows and reels that exist
+    pub fn validate(&self) -> Result<(), String> {
+        if self.reels.is_empty() || self.paylines.is_empty() || self.rows == 0 {
+            return Err("needs at least one reel, payline and row".to_string());
+        }
+        for (i, reel) in self.reels.iter().enumerate() {
+            if reel.is_empty() {
+                return Err(format!("reel {} is empty", i + 1));
+            }
+            if let Some(unknown) = reel.iter().find(|s| self.symbol(s).is_none()) {
+                return Err(format!("reel {} has unknown symbol {}", i + 1, unknown));
+            }
+        }
+        for (i, line) in self.paylines.iter().enumerate() {
+            if line.len() != self.reels.len() || line.iter().any(|row| *row >= self.rows) {
+                return Err(format!("payline {} doesn't fit the reels", i + 1));
+            }
+        }
+        if let Some(unknown) = self.paytable.iter().find(|p| self.symbol(&p.symbol).is_none()) {
+            return Err(format!("paytable has unknown symbol {}", unknown.symbol));
+        }
+        if self.win_chance.is_some_and(|chance| !(0.0..=1.0).contains(&chance)) {
+            return Err("win_chance must be between 0 and 1".to_string());
+        }
+        if self.win_chance.is_some() && !self.reels[0].iter().any(|s| self.pays_full_line(s)) {
+            return Err("win_chance needs a symbol on the first reel that pays a full line".to_string());
+        }
+        Ok(())
+    }
+
+    fn free_spin(&self, rng: &mut GameRng) -> Vec<Vec<String>> {
+        self.reels.iter().map(|reel| {
+            let stop = rng.gen_range(0..reel.len());
+            (0..self.rows).map(|row| reel[(stop + row) % reel.len()].clone()).collect()
+        }).collect()
+    }
+
+    pub fn spin(&self, rng: &mut GameRng) -> Vec<Vec<String>> {
+        let Some(chance) = self.win_chance else {
+            return self.free_spin(rng);
+        };
+        if rng.gen_bool(chance) {
+            // the winning symbol is as likely as it is on the first reel, out of the ones that pay a line
+            let winners = self.reels[0].iter().filter(|s| self.pays_full_line(s)).collect::<Vec<&String>>();
+            if let Some(symbol) = winners.choose(rng) {
+                return self.filled(symbol);
+            }
+        }
+        let mut grid = self.free_spin(rng);
+        for _ in 0..MAX_REDRAWS {
+            if self.evaluate(grid.clone(), 1).wins.is_empty() {
+                break;
+            }
+            grid = self.free_spin(rng);
+        }
+        grid
+    }
+
+    // scatters and symbols missing from the paytable don't win when they fill a line
+    fn pays_full_line(&self, symbol: &String) -> bool {
+        let line = vec![symbol; self.reels.len()];
+        !self.is_scatter(symbol) && self.line_match(&line).is_some_and(|(symbol, count)| self.pay(&symbol, count) > 0.0)
+    }
+
+    // every reel showing the one symbol
+    pub fn filled(&self, symbol: &str) -> Vec<Vec<String>> {
+        vec![vec![symbol.to_string(); self.rows]; self.reels.len()]
+    }
+
+    // the best paying line the machine has, used for rigged spins
+    pub fn best_grid(&self) -> Vec<Vec<String>> {
+        let best = self.paytable.iter()
+            .filter(|p| !self.is_scatter(&p.symbol))
+            .max_by(|a, b| a.multiplier.total_cmp(&b.multiplier))
+            .map(|p| p.symbol.clone())
+            .unwrap_or_else(|| self.reels[0][0].clone());
+        self.filled(&best)
+    }
+
+    // the symbol a payline pays for and how many matched from the left
+    fn line_match(&self, symbols: &[&String]) -> Option<(String, usize)> {
+        // wilds take the place of the first symbol that isn't one
+        let line_symbol = symbols.iter().find(|s| !self.is_wild(s)).unwrap_or(&symbols[0]);
+        if self.is_scatter(line_symbol) {
+            return None;
+        }
+        let count = symbols.iter().take_while(|s| *s == line_symbol || self.is_wild(s)).count();
+        Some((line_symbol.to_string(), count))
+    }
+
+    pub fn evaluate(&self, grid: Vec<Vec<String>>, bet: u64) -> SpinResult {
+        let line_bet = bet as f32 / self.paylines.len() as f32;
+        let mut wins = Vec::new();
+        let mut jackpot = false;
+
+        for (i, line) in self.paylines.iter().enumerate() {
+            let symbols = line.iter().enumerate().map(|(reel, row)| &grid[reel][*row]).collect::<Vec<&String>>();
+            let Some((symbol, count)) = self.line_match(&symbols) else {
+                continue;
+            };
+            if let Some(config) = &self.jackpot {
+                if config.symbol == symbol && count >= config.count {
+                    jackpot = true;
+                }
+            }
+            let multiplier = self.pay(&symbol, count);
+            if multiplier > 0.0 {
+                wins.push(SlotWin { line: Some(i + 1), symbol, count, winnings: (line_bet * multiplier) as u64 });
+            }
+        }
+
+        for scatter in self.symbols.iter().filter(|s| s.scatter) {
+            let count = grid.iter().flatten().filter(|s| **s == scatter.name).count();
+            let multiplier = self.pay(&scatter.name, count);
+            if multiplier > 0.0 {
+                wins.push(SlotWin { line: None, symbol: scatter.name.clone(), count, winnings: (bet as f32 * multiplier) as u64 });
+            }
+        }
+
+        SpinResult { grid, wins, jackpot }
+    }
+
+    pub fn play(&self, bet: u64, rng: &mut GameRng) -> SpinResult {
+        let grid = self.spin(rng);
+        self.evaluate(grid, bet)
+    }
+
+    // the grid as rows of emojis
+    pub fn render(&self, grid: &[Vec<String>]) -> String {
+        (0..self.rows).map(|row| {
+            grid.iter().map(|reel| self.emoji(&reel[row])).collect::<Vec<String>>().join(" | ")
+        }).collect::<Vec<String>>().join("\n")
+    }
+
+    // percentage of a bet added to the server's jackpot pool
+    pub fn jackpot_contribution(&self, bet: u64) -> u64 {
+        match &self.jackpot {
+            Some(config) => (bet as f32 * config.contribution_percent / 100.0) as u64,
+            None => 0,
+        }
+    }
+}
+
+pub struct SlotMachines {
+    pub machines: HashMap<String, SlotMachine>,
+}
+
+impl SlotMachines {
+
+    pub fn get() -> Self {
+        // load all machines from ./slot_machines/*.json, the file name is the machine's id
+        let mut machines = HashMap::new();
+
+        let Ok(paths) = fs::read_dir("./slot_machines") else {
+            hey!("No slot machines found in ./slot_machines");
+            return Self { machines };
+        };
+        for path in paths {
+            let Ok(path) = path.map(|p| p.path()) else {
+                hey!("Failed to read an entry in ./slot_machines");
+                continue;
+            };
+            let Some(id) = path.file_stem().and_then(|s| s.to_str()).map(|s| s.to_string()) else {
+                hey!("Slot machine file {:?} has no usable name", path);
+                continue;
+            };
+            let file = match fs::read_to_string(&path) {
+                Ok(file) => file,
+                Err(e) => {
+                    hey!("Failed to read slot machine {}: {}", id, e);
+                    continue;
+                }
+            };
+            let machine: SlotMachine = match serde_json::from_str(&file) {
+                Ok(machine) => machine,
+                Err(e) => {
+                    hey!("Failed to load slot machine {}: {}", id, e);
+                    continue;
+                }
+            };
+            if let Err(e) = machine.validate() {
+                hey!("Slot machine {} is invalid: {}", id, e);
+                continue;
+            }
+
+            machines.insert(id, machine);
+        }
+
+        Self {
+            machines
+        }
+    }
+
+    pub fn get_machine(&self, id: &str) -> Option<&SlotMachine> {
+        self.machines.get(id)
+    }
+
+    // machine ids sorted so the command choices keep their order
+    pub fn ids(&self) -> Vec<String> {
+        let mut ids = self.machines.keys().cloned().collect::<Vec<String>>();
+        ids.sort();
+        ids
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol(name: &str, wild: bool, scatter: bool) -> SlotSymbol {
+        SlotSymbol { name: name.to_string(), emoji: name.to_string(), wild, scatter }
+    }
+
+    fn pay(symbol: &str, count: usize, multiplier: f32) -> Pay {
+        Pay { symbol: symbol.to_string(), count, multiplier }
+    }
+
+    fn machine() -> SlotMachine {
+        SlotMachine {
+            name: "Test".to_string(),
+            description: String::new(),
+            min_bet: 100,
+            rows: 1,
+            symbols: vec![symbol("A", false, false), symbol("B", false, false), symbol("W", true, false), symbol("S", false, true)],
+            reels: vec![vec!["A".to_string()]; 3],
+            paylines: vec![vec![0, 0, 0]],
+            paytable: vec![pay("A", 3, 10.0), pay("B", 2, 1.0), pay("S", 2, 5.0)],
+            jackpot: Some(JackpotConfig { contribution_percent: 1.0, symbol: "A".to_string(), count: 3 }),
+            win_chance: None,
+        }
+    }
+
+    fn grid(symbols: &[&str]) -> Vec<Vec<String>> {
+        symbols.iter().map(|s| vec![s.to_string()]).collect()
+    }
+
+    #[test]
+    fn wilds_complete_a_line() {
+        let result = machine().evaluate(grid(&["W", "A", "W"]), 100);
+        assert_eq!(result.winnings(), 1000);
+        assert!(result.jackpot);
+    }
+
+    #[test]
+    fn lines_pay_from_the_left() {
+        let result = machine().evaluate(grid(&["B", "B", "A"]), 100);
+        assert_eq!(result.winnings(), 100);
+        assert!(!result.jackpot);
+
+        let result = machine().evaluate(grid(&["A", "B", "B"]), 100);
+        assert_eq!(result.winnings(), 0);
+    }
+
+    #[test]
+    fn scatters_pay_anywhere() {
+        let result = machine().evaluate(grid(&["S", "A", "S"]), 100);
+        assert_eq!(result.winnings(), 500);
+        assert!(result.wins[0].line.is_none());
+    }
+
+    #[test]
+    fn win_chance_sets_the_hit_rate() {
+        let mut machine = machine();
+        // forced wins can't land on the scatter or the symbol that pays nothing
+        machine.reels = vec![vec!["A".to_string(), "B".to_string(), "S".to_string(), "S".to_string(), "C".to_string()]; 3];
+        machine.symbols.push(symbol("C", false, false));
+        machine.paytable = vec![pay("A", 3, 3.0), pay("B", 3, 2.0), pay("S", 5, 1.0)];
+        machine.win_chance = Some(0.1);
+        assert_eq!(machine.validate(), Ok(()));
+
+        let mut rng = GameRng::from_seed(1);
+        let spins = 10000;
+        let wins = (0..spins).filter(|_| machine.play(100, &mut rng).winnings() > 0).count();
+        let rate = wins as f64 / spins as f64;
+        assert!((rate - 0.1).abs() < 0.01, "win rate {}", rate);
+
+        for seed in 0..100 {
+            assert_eq!(machine.spin(&mut GameRng::from_seed(seed)), machine.spin(&mut GameRng::from_seed(seed)));
+        }
+        assert_eq!(machine.best_grid(), grid(&["A", "A", "A"]));
+
+        machine.reels[0] = vec!["S".to_string(), "C".to_string()];
+        assert!(machine.validate().is_err());
+    }
+
+    #[test]
+    fn bundled_machines_are_valid() {
+        // SlotMachines::get skips broken files, so load them directly
+        for path in fs::read_dir("./slot_machines").unwrap() {
+            let path = path.unwrap().path();
+            let machine: SlotMachine = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+            assert_eq!(machine.validate(), Ok(()), "{:?}", path);
+        }
+    }
+}
@@ -2,13 +2,23 @@ use std::fs;
 use std::fs::OpenOptions;
 use std::path::Path;
 use serde::{Deserialize, Serialize};
-use serenity::all::{Timestamp, UserId};
+use serenity::all::{GuildId, Timestamp, UserId};
 use std::io::Write;
 use crate::hey;
+use crate::achievements::{queue_unlock, Achievement, AchievementProgress, Stat};
+use crate::economy::{adjust, Adjustment, EconomyField};
+use crate::fairness::{FairnessSettings, FairRound};
+use crate::games::rng::GameRng;
+use crate::guildfile::GuildSettings;
 use crate::inventory::Inventory;
 use crate::inventory::item::InventoryItem;
 use crate::inventory::minion::Minion;
 use crate::inventory::super_drill::SuperDrill;
+use crate::milestones::{self, Milestone, MilestoneFile};
+use crate::notifications::{NotificationEvent, NotificationSettings, NotifyMode};
+use crate::perks::{Perk, PerkTree};
+use crate::quests::{QuestBoard, QuestTemplate};
+use crate::rewards::{ClaimCheck, ClaimOutcome, RewardKind, RewardStreaks};
 
 const BASE_PRICE: u64 = 150;
 const LEVEL_MULTIPLIER: u64 = 75;
@@ -22,8 +32,40 @@ pub struct UserFile {
     pub(crate) super_nanners: u16,
     pub(crate) ascension: u16,
     pub(crate) mine_tier: u8,
+    #[serde(default)]
+    pub(crate) drill_parts: u32,
 
     pub(crate) inventory: Inventory,
+
+    #[serde(default)]
+    pub(crate) notifications: NotificationSettings,
+
+    #[serde(default)]
+    pub(crate) fairness: FairnessSettings,
+
+    #[serde(default)]
+    pub(crate) streaks: RewardStreaks,
+
+    #[serde(default)]
+    pub(crate) achievements: AchievementProgress,
+
+    #[serde(default)]
+    pub(crate) quests: QuestBoard,
+
+    #[serde(default)]
+    pub(crate) perks: PerkTree,
+
+    // ids of milestones already paid out
+    #[serde(default)]
+    pub(crate) milestones: Vec<String>,
+
+    // cosmetic badges from season placings
+    #[serde(default)]
+    pub(crate) badges: Vec<String>,
+
+    // the tag of the user's clan
+    #[serde(default)]
+    pub(crate) clan: Option<String>,
 }
 
 #[derive(Clone)]
@@ -43,11 +85,31 @@ impl UserValues {
                 super_nanners: 0,
                 ascension: 0,
                 mine_tier: 1,
+                drill_parts: 0,
 
                 inventory: Inventory {
                     items: Vec::new(),
                     equiped: None,
-                }
+                    extra_slots: 0,
+                },
+
+                notifications: NotificationSettings::default(),
+
+                fairness: FairnessSettings::default(),
+
+                streaks: RewardStreaks::default(),
+
+                achievements: AchievementProgress::default(),
+
+                quests: QuestBoard::default(),
+
+                perks: PerkTree::default(),
+
+                milestones: Vec::new(),
+
+                badges: Vec::new(),
+
+                clan: None,
             }
         }
     }
@@ -56,6 +118,22 @@ impl UserValues {
         Self::read(id)
     }
 
+    // every user with a user file
+    pub fn all() -> Vec<Self> {
+        let Ok(paths) = fs::read_dir("./users") else {
+            return Vec::new();
+        };
+
+        let mut users = Vec::new();
+        for path in paths.flatten() {
+            let name = path.file_name().into_string().unwrap_or_default().replace(".json", "");
+            if let Ok(id) = name.parse::<u64>() {
+                users.push(Self::get(&UserId::from(id)));
+            }
+        }
+        users
+    }
+
     fn read(id: &UserId) -> Self {
         let raw_path = format!("./users/{}.json", id.get());
         let path = Path::new(raw_path.as_str());
@@ -215,6 +293,19 @@ impl UserValues {
         self.update();
     }
 
+    pub fn get_perks(&mut self) -> PerkTree {
+        self.reload();
+        self.file.perks.clone()
+    }
+
+    // spends perk points, returns the perk's new rank
+    pub fn allocate_perk(&mut self, perk: Perk, points: u8) -> Result<u8, String> {
+        self.reload();
+        let rank = self.file.perks.allocate(perk, points, self.file.ascension)?;
+        self.update();
+        Ok(rank)
+    }
+
     pub fn get_ascension(&mut self) -> u16 {
         self.reload();
         self.file.ascension
@@ -345,24 +436,371 @@ impl UserValues {
         }
     }
 
+    pub fn get_super_drill(&mut self) -> Option<SuperDrill> {
+        self.reload();
+        self.file.inventory.get_super_drill()
+    }
+
+    // upgrades the super drill to the next tier, paying its cost. returns false if it can't be upgraded
+    pub fn upgrade_super_drill(&mut self) -> bool {
+        self.reload();
+        let Some(drill) = self.file.inventory.get_super_drill() else {
+            return false;
+        };
+        let (nanners, parts) = drill.upgrade_cost();
+        if !drill.can_upgrade() || self.file.super_nanners < nanners || self.file.drill_parts < parts {
+            return false;
+        }
+
+        for item in &mut self.file.inventory.items {
+            if let InventoryItem::SuperDrill(ref mut drill) = item {
+                drill.tier += 1;
+                break;
+            }
+        }
+        self.file.super_nanners -= nanners;
+        self.file.drill_parts -= parts;
+        self.update();
+        true
+    }
+
+    pub fn get_drill_parts(&mut self) -> u32 {
+        self.reload();
+        self.file.drill_parts
+    }
+
+    pub fn add_drill_parts(&mut self, amt: u32) {
+        self.reload();
+        self.file.drill_parts += amt;
+        self.update();
+    }
+
+    pub fn get_notification_settings(&mut self) -> NotificationSettings {
+        self.reload();
+        self.file.notifications.clone()
+    }
+
+    // also remembers where the user asked for notifications, for events that don't happen in a channel
+    pub fn set_notification_mode(&mut self, event: NotificationEvent, mode: NotifyMode, channel: u64, guild: u64) {
+        self.reload();
+        self.file.notifications.set_mode(event, mode);
+        self.file.notifications.channel = Some(channel);
+        self.file.notifications.guild = Some(guild);
+        self.update();
+    }
+
+    pub fn get_fairness(&mut self) -> FairnessSettings {
+        self.reload();
+        self.file.fairness.clone()
+    }
+
+    pub fn set_fair_mode(&mut self, enabled: bool) {
+        self.reload();
+        self.file.fairness.enabled = enabled;
+        self.update();
+    }
+
+    pub fn set_client_seed(&mut self, seed: String) {
+        self.reload();
+        self.file.fairness.client_seed = seed;
+        self.update();
+    }
+
+    // None unless the user has turned on provably fair mode
+    pub fn next_fair_round(&mut self) -> Option<FairRound> {
+        self.reload();
+        if !self.file.fairness.enabled {
+            return None;
+        }
+        let round = self.file.fairness.next_round();
+        self.update();
+        Some(round)
+    }
+
     pub fn get_minions(&mut self) -> Vec<Minion> {
         self.reload();
         self.file.inventory.get_minions()
     }
 
-    pub fn collect_minions(&mut self) -> u64 {
+    // returns the sludge and super nanners collected, super nanners past the most a user can hold are lost
+    pub fn collect_minions(&mut self) -> (u64, u16) {
         self.reload();
         // loop through the minions and collect the sludge
         let mut sludge: u64 = 0;
+        let mut nanners: u32 = 0;
+        let capacity = self.file.perks.minion_capacity();
         for minion in &mut self.file.inventory.items {
             if let InventoryItem::Minion(ref mut minion) = minion {
-                sludge += minion.get_sludge_produced() as u64;
+                sludge += minion.get_sludge_produced(capacity) as u64;
+                nanners = nanners.saturating_add(minion.get_super_nanners_produced());
                 minion.mining_start = Timestamp::now();
             }
         }
+        let before = self.file.super_nanners;
+        self.file.super_nanners = before.saturating_add(u16::try_from(nanners).unwrap_or(u16::MAX));
         // update the minions
         self.update();
-        sludge
+        (sludge, self.file.super_nanners - before)
+    }
+
+    // raises the level of the minion in the given inventory slot, returns false if there is no minion to upgrade
+    pub fn upgrade_minion(&mut self, slot: usize) -> bool {
+        self.reload();
+        let Some(InventoryItem::Minion(ref mut minion)) = self.file.inventory.items.get_mut(slot) else {
+            return false;
+        };
+        if !minion.can_upgrade() {
+            return false;
+        }
+        minion.level += 1;
+        self.update();
+        true
+    }
+
+    pub fn get_streaks(&mut self) -> RewardStreaks {
+        self.reload();
+        self.file.streaks.clone()
+    }
+
+    pub fn count_streak_freezes(&mut self) -> u32 {
+        self.reload();
+        self.file.inventory.items.iter().filter(|i| matches!(i, InventoryItem::StreakFreeze)).count() as u32
+    }
+
+    // claims a daily or weekly reward, a missed period uses up a streak freeze each if there are enough to cover the gap
+    // returns when the next claim is allowed if it is too soon
+    pub fn claim_reward(&mut self, kind: RewardKind, now: i64, boost: u64) -> Result<ClaimOutcome, i64> {
+        self.reload();
+        let streak = self.file.streaks.get(kind);
+        let previous = streak.count;
+
+        let (keep, freezes_used) = match streak.check(kind, now) {
+            ClaimCheck::TooSoon { next } => return Err(next),
+            ClaimCheck::Continues => (true, 0),
+            ClaimCheck::Missed { periods } => {
+                let freezes = self.file.inventory.items.iter().filter(|i| matches!(i, InventoryItem::StreakFreeze)).count() as u32;
+                if freezes >= periods {
+                    for _ in 0..periods {
+                        let index = self.file.inventory.items.iter().position(|i| matches!(i, InventoryItem::StreakFreeze)).unwrap();
+                        self.file.inventory.items.remove(index);
+                    }
+                    (true, periods)
+                } else {
+                    (false, 0)
+                }
+            }
+        };
+
+        let streak = self.file.streaks.get_mut(kind).claim(now, keep);
+        let reward = kind.reward(streak, self.file.prestige, self.file.ascension) * boost;
+        self.file.bananas += reward;
+        self.update();
+
+        Ok(ClaimOutcome { streak, reward, freezes_used, lost: if keep { 0 } else { previous } })
+    }
+
+    pub fn get_achievements(&mut self) -> AchievementProgress {
+        self.reload();
+        self.file.achievements.clone()
+    }
+
+    pub fn add_progress(&mut self, stat: Stat, amount: u64) {
+        self.reload();
+        let unlocked = self.file.achievements.add(&stat, amount, Achievement::all());
+        self.grant_achievements(unlocked);
+
+        // a board left over from an earlier day is redrawn from its guild's pool before counting
+        let now = Timestamp::now().unix_timestamp();
+        if self.file.quests.is_expired(now) && self.file.quests.guild != 0 {
+            let guild = GuildId::new(self.file.quests.guild);
+            self.file.quests = Self::draw_quests(&guild, now);
+        }
+        if !self.file.quests.is_expired(now) {
+            self.file.quests.progress(&stat, amount);
+        }
+        self.update();
+    }
+
+    pub fn set_progress(&mut self, stat: Stat, value: u64) {
+        self.reload();
+        let unlocked = self.file.achievements.set(&stat, value, Achievement::all());
+        self.grant_achievements(unlocked);
+        self.update();
+    }
+
+    // rewards are paid straight away, the unlock is announced next time the user is active
+    fn grant_achievements(&mut self, unlocked: Vec<Achievement>) {
+        for achievement in unlocked {
+            self.file.bananas += achievement.reward.bananas;
+            self.file.super_nanners = self.file.super_nanners.saturating_add(achievement.reward.super_nanners);
+            queue_unlock(self.id, achievement);
+        }
     }
-}
 
+    // only titles the user has earned can be shown, None clears it
+    pub fn set_title(&mut self, title: Option<String>) -> bool {
+        self.reload();
+        if let Some(title) = &title {
+            if !self.file.achievements.titles.contains(title) {
+                return false;
+            }
+        }
+        self.file.achievements.title = title;
+        self.update();
+        true
+    }
+
+    fn draw_quests(guild: &GuildId, now: i64) -> QuestBoard {
+        let settings = GuildSettings::get(guild).get_quest_settings();
+        let pool = settings.pool(QuestTemplate::all());
+        QuestBoard::draw(&pool, guild.get(), settings.next_reset(now), &mut GameRng::new())
+    }
+
+    // the user's quests, a new board is drawn from the guild's pool once the last one expires
+    pub fn get_quests(&mut self, guild: &GuildId, now: i64) -> QuestBoard {
+        self.reload();
+        if self.file.quests.is_expired(now) {
+            self.file.quests = Self::draw_quests(guild, now);
+            self.update();
+        }
+        self.file.quests.clone()
+    }
+
+    // pays out every finished quest, returns the bananas, super nanners and quests claimed
+    pub fn claim_quests(&mut self, now: i64) -> (u64, u16, usize) {
+        self.reload();
+        if self.file.quests.is_expired(now) {
+            return (0, 0, 0);
+        }
+        let (bananas, nanners, claimed) = self.file.quests.claim();
+        self.file.bananas = self.file.bananas.saturating_add(bananas);
+        self.file.super_nanners = self.file.super_nanners.saturating_add(nanners);
+        self.update();
+        (bananas, nanners, claimed)
+    }
+
+    // pays out every reached milestone that hasn't been paid before, returns the newly reached ones and the items that didn't fit
+    // claimed ids are kept through prestiges and ascensions so re-levelling never pays twice
+    pub fn claim_milestones(&mut self) -> (Vec<Milestone>, Vec<InventoryItem>) {
+        self.reload();
+        let reached = Milestone::all().iter()
+            .filter(|m| m.is_reached(self.file.level, self.file.prestige, self.file.ascension))
+            .filter(|m| !self.file.milestones.contains(&m.id))
+            .cloned()
+            .collect::<Vec<Milestone>>();
+        if reached.is_empty() {
+            return (reached, Vec::new());
+        }
+
+        // slots are added first so the items have room
+        for milestone in &reached {
+            let reward = &milestone.reward;
+            self.file.super_nanners = self.file.super_nanners.saturating_add(reward.super_nanners);
+            self.file.inventory.extra_slots = self.file.inventory.extra_slots.saturating_add(reward.inventory_slots);
+            self.file.milestones.push(milestone.id.clone());
+        }
+
+        let mut missed = Vec::new();
+        for milestone in &reached {
+            let reward = &milestone.reward;
+            let minion = reward.minion.as_ref().map(|kind| InventoryItem::Minion(Minion::new(kind.clone())));
+            for item in reward.items.iter().cloned().chain(minion) {
+                if self.file.inventory.is_full() {
+                    missed.push(item);
+                } else {
+                    self.file.inventory.items.push(item);
+                }
+            }
+        }
+        self.update();
+        (reached, missed)
+    }
+
+    // the first milestone the user hasn't reached yet
+    pub fn next_milestone(&mut self) -> Option<Milestone> {
+        self.reload();
+        Milestone::all().iter().find(|m| !m.is_reached(self.file.level, self.file.prestige, self.file.ascension)).cloned()
+    }
+
+    pub fn max_bet(&mut self) -> u64 {
+        self.reload();
+        MilestoneFile::get().max_bet(self.file.level, self.file.prestige, self.file.ascension)
+    }
+
+    // the milestone the user still needs to mine at the tier, if any
+    pub fn mine_tier_lock(&mut self, tier: u8) -> Option<Milestone> {
+        self.reload();
+        milestones::tier_lock(Milestone::all(), tier, self.file.level, self.file.prestige, self.file.ascension).cloned()
+    }
+
+    pub fn get_badges(&mut self) -> Vec<String> {
+        self.reload();
+        self.file.badges.clone()
+    }
+
+    pub fn add_badge(&mut self, badge: String) {
+        self.reload();
+        self.file.badges.push(badge);
+        self.update();
+    }
+
+    pub fn get_clan(&mut self) -> Option<String> {
+        self.reload();
+        self.file.clan.clone()
+    }
+
+    pub fn set_clan(&mut self, tag: Option<String>) {
+        self.reload();
+        self.file.clan = tag;
+        self.update();
+    }
+
+    pub fn get_economy(&mut self, field: EconomyField) -> u64 {
+        self.reload();
+        match field {
+            EconomyField::Bananas => self.file.bananas,
+            EconomyField::SuperNanners => self.file.super_nanners as u64,
+            EconomyField::Level => self.file.level as u64,
+            EconomyField::Prestige => self.file.prestige as u64,
+            EconomyField::Ascension => self.file.ascension as u64,
+        }
+    }
+
+    // returns the old and new value
+    pub fn adjust_economy(&mut self, field: EconomyField, adjustment: Adjustment, amount: u64) -> (u64, u64) {
+        let old = self.get_economy(field);
+        let new = adjust(field, old, adjustment, amount);
+        match field {
+            EconomyField::Bananas => self.file.bananas = new,
+            EconomyField::SuperNanners => self.file.super_nanners = new as u16,
+            EconomyField::Level => self.file.level = new as u16,
+            EconomyField::Prestige => self.file.prestige = new as u16,
+            EconomyField::Ascension => self.file.ascension = new as u16,
+        }
+        self.update();
+        (old, new)
+    }
+
+    // gives the user a super drill at the tier, or changes the tier of the one they have
+    pub fn set_super_drill_tier(&mut self, tier: u8) {
+        self.reload();
+        let drill = self.file.inventory.items.iter_mut().find_map(|item| match item {
+            InventoryItem::SuperDrill(drill) => Some(drill),
+            _ => None,
+        });
+        match drill {
+            Some(drill) => drill.tier = tier,
+            None => self.file.inventory.items.push(InventoryItem::SuperDrill(SuperDrill { tier })),
+        }
+        self.update();
+    }
+
+    // back to a fresh user file, the clan is kept so the clan's member list stays right
+    pub fn reset(&mut self) {
+        self.reload();
+        let clan = self.file.clan.take();
+        self.file = Self::new(&self.id).file;
+        self.file.clan = clan;
+        self.update();
+    }
+}